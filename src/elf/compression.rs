@@ -0,0 +1,437 @@
+use super::defs::*;
+use super::error::{ElfError, Structure};
+use super::notes::Reader;
+use super::parser::{CompressedSection, ParsedElf, ParsedIdent, RangeType, SectionContents};
+use super::ranges::Ranges;
+use miniz_oxide::inflate::{decompress_to_vec_zlib_with_limit, TINFLStatus};
+use ruzstd::decoding::StreamingDecoder;
+use std::convert::{TryFrom, TryInto};
+use std::io::Read;
+
+// .zdebug sections start with "ZLIB" and the size of the decompressed data as
+// a big-endian 64-bit integer, sections without these aren't compressed
+const ZDEBUG_MAGIC: &[u8] = b"ZLIB";
+const ZDEBUG_HEADER_SIZE: usize = 12;
+
+// name (also the class of its range), offset and size of a field of the header
+type Field = (&'static str, usize, usize);
+
+const CHDR32_FIELDS: [Field; 3] = [("ch_type", 0, 4), ("ch_size", 4, 4), ("ch_addralign", 8, 4)];
+const CHDR64_FIELDS: [Field; 4] = [
+    ("ch_type", 0, 4),
+    ("ch_reserved", 4, 4),
+    ("ch_size", 8, 8),
+    ("ch_addralign", 16, 8),
+];
+const ZDEBUG_FIELDS: [Field; 2] = [("zlib_magic", 0, 4), ("zlib_size", 4, 8)];
+
+fn decompress(compression: u32, input: &[u8], size: usize) -> Result<Vec<u8>, String> {
+    let too_large = || format!("more than the {} bytes of the header", size);
+
+    let data = match compression {
+        ELFCOMPRESS_ZLIB => {
+            decompress_to_vec_zlib_with_limit(input, size).map_err(|e| match e.status {
+                TINFLStatus::HasMoreOutput => too_large(),
+                _ => e.to_string(),
+            })?
+        }
+        _ => {
+            let mut input = input;
+            let mut data = vec![];
+
+            // one byte more than expected tells data which is too large, a
+            // stream can be made of several frames
+            while !input.is_empty() && data.len() <= size {
+                let decoder = StreamingDecoder::new(&mut input).map_err(|e| e.to_string())?;
+                let limit = ((size - data.len()) as u64).saturating_add(1);
+
+                decoder
+                    .take(limit)
+                    .read_to_end(&mut data)
+                    .map_err(|e| e.to_string())?;
+            }
+
+            data
+        }
+    };
+
+    if data.len() > size {
+        Err(too_large())
+    } else if data.len() < size {
+        Err(format!(
+            "{} bytes instead of the {} of the header",
+            data.len(),
+            size
+        ))
+    } else {
+        Ok(data)
+    }
+}
+
+impl<'a> ParsedElf<'a> {
+    pub(super) fn parse_compressed_sections(&mut self, ident: &ParsedIdent) {
+        let reader = Reader::new(ident.endianness, ident.class);
+
+        for idx in 0..self.shdrs.len() {
+            let shdr = &self.shdrs[idx];
+            let section = match self.section_data(shdr) {
+                Some(section) => section,
+                None => continue,
+            };
+
+            let compressed = if shdr.flags & SHF_COMPRESSED != 0 {
+                self.parse_chdr(idx, section, reader)
+            } else if self.shnstrtab.get(shdr.name).starts_with(".zdebug") {
+                self.parse_zdebug_header(idx, section)
+            } else {
+                None
+            };
+
+            if let Some(compressed) = compressed {
+                self.compressed.insert(idx as u16, compressed);
+            }
+        }
+    }
+
+    fn parse_chdr(
+        &mut self,
+        idx: usize,
+        section: &[u8],
+        reader: Reader,
+    ) -> Option<CompressedSection> {
+        let start = self.shdrs[idx].file_offset;
+        let fields: &[Field] = if reader.word == 4 {
+            &CHDR32_FIELDS
+        } else {
+            &CHDR64_FIELDS
+        };
+        let (_, last_offset, last_size) = fields[fields.len() - 1];
+        let header_size = last_offset + last_size;
+
+        if section.len() < header_size {
+            let error = ElfError::Truncated {
+                offset: start,
+                structure: Structure::CompressedSection(idx as u16),
+                size: header_size,
+                available: section.len(),
+            };
+
+            self.diagnose(error, Some((start, section.len())));
+
+            return None;
+        }
+
+        self.add_header_fields(start, fields);
+
+        let compression = reader.u32(section, 0)?;
+        // ch_size and ch_addralign are the last two words
+        let size = reader.word(section, header_size - 2 * reader.word)?;
+        let alignment = reader.word(section, header_size - reader.word)?;
+
+        let data = match compression {
+            ELFCOMPRESS_ZLIB | ELFCOMPRESS_ZSTD => {
+                self.decompress_section(idx, section, compression, header_size, size, "ch_size")
+            }
+            _ => {
+                let error = ElfError::UnknownValue {
+                    offset: start,
+                    structure: Structure::CompressedSection(idx as u16),
+                    field: "ch_type",
+                    value: compression as u64,
+                };
+
+                self.diagnose_field(error, start, header_size, "ch_type");
+
+                None
+            }
+        };
+
+        Some(CompressedSection {
+            compression,
+            legacy: false,
+            size,
+            alignment,
+            header_size,
+            data: data.map(Into::into),
+        })
+    }
+
+    fn parse_zdebug_header(&mut self, idx: usize, section: &[u8]) -> Option<CompressedSection> {
+        if !section.starts_with(ZDEBUG_MAGIC) || section.len() < ZDEBUG_HEADER_SIZE {
+            return None;
+        }
+
+        let start = self.shdrs[idx].file_offset;
+        let size = u64::from_be_bytes(section[4..ZDEBUG_HEADER_SIZE].try_into().ok()?);
+
+        self.add_header_fields(start, &ZDEBUG_FIELDS);
+
+        let data = self.decompress_section(
+            idx,
+            section,
+            ELFCOMPRESS_ZLIB,
+            ZDEBUG_HEADER_SIZE,
+            size,
+            "zlib_size",
+        );
+
+        Some(CompressedSection {
+            compression: ELFCOMPRESS_ZLIB,
+            legacy: true,
+            size,
+            alignment: 0,
+            header_size: ZDEBUG_HEADER_SIZE,
+            data: data.map(Into::into),
+        })
+    }
+
+    fn add_header_fields(&mut self, start: usize, fields: &[Field]) {
+        for (name, offset, size) in fields.iter() {
+            self.ranges
+                .add_range(start + offset, *size, RangeType::ChdrField(name));
+        }
+    }
+
+    // `size_field` is the field of the header which holds `size`
+    fn decompress_section(
+        &mut self,
+        idx: usize,
+        section: &[u8],
+        compression: u32,
+        header_size: usize,
+        size: u64,
+        size_field: &'static str,
+    ) -> Option<Vec<u8>> {
+        let start = self.shdrs[idx].file_offset;
+        let structure = Structure::CompressedSection(idx as u16);
+
+        let size = match usize::try_from(size) {
+            Ok(size) => size,
+            Err(_) => {
+                let error = ElfError::FieldOverflow {
+                    offset: start,
+                    structure,
+                    field: size_field,
+                };
+
+                self.diagnose_field(error, start, header_size, size_field);
+
+                return None;
+            }
+        };
+
+        match decompress(compression, &section[header_size..], size) {
+            Ok(data) => Some(data),
+            Err(reason) => {
+                let error = ElfError::Decompression {
+                    offset: start,
+                    structure,
+                    reason,
+                };
+                let location = (start + header_size, section.len() - header_size);
+
+                self.diagnose(error, Some(location).filter(|(_, len)| *len > 0));
+
+                None
+            }
+        }
+    }
+
+    // contents of section `idx` as its decoders see them: the decompressed
+    // data of compressed sections, None if that isn't available
+    pub fn section_contents(&self, idx: usize) -> Option<SectionContents<'a>> {
+        match self.compressed.get(&(idx as u16)) {
+            Some(compressed) => compressed.data.clone().map(SectionContents::Decompressed),
+            None => self
+                .section_data(self.shdrs.get(idx)?)
+                .map(SectionContents::File),
+        }
+    }
+
+    // file offset of the contents of section `idx`. Decompressed contents
+    // aren't in the file, offsets in them are relative to the section
+    pub(super) fn contents_offset(&self, idx: usize) -> usize {
+        if self.compressed.contains_key(&(idx as u16)) {
+            0
+        } else {
+            self.shdrs[idx].file_offset
+        }
+    }
+
+    // runs `decode` on the contents of section `idx`. Ranges in decompressed
+    // contents would cover unrelated bytes of the file, these are dropped
+    // along with the locations of the diagnostics
+    pub(super) fn decode_contents(&mut self, idx: usize, decode: impl FnOnce(&mut Self)) {
+        if !self.compressed.contains_key(&(idx as u16)) {
+            return decode(self);
+        }
+
+        let ranges = std::mem::replace(&mut self.ranges, Ranges::new(0));
+        let diagnostics = self.diagnostics.len();
+
+        decode(self);
+
+        self.ranges = ranges;
+
+        for diagnostic in self.diagnostics[diagnostics..].iter_mut() {
+            diagnostic.location = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_elf::{build, section, Bytes, Section};
+    use super::*;
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    const TEXT: &[u8] = b"compressed contents\0";
+
+    // an Elf64_Chdr followed by `data`
+    fn compressed(compression: u32, size: u64, data: &[u8]) -> Section {
+        let data = Bytes::default()
+            .u32(compression)
+            .u32(0)
+            .u64(size)
+            .u64(1)
+            .raw(data);
+
+        Section {
+            flags: SHF_COMPRESSED,
+            ..section(".debug_str", SHT_PROGBITS, data)
+        }
+    }
+
+    // a Zstandard frame holding `data` in a single raw block
+    fn zstd_frame(data: &[u8]) -> Vec<u8> {
+        let block_header = 1 | (data.len() as u32) << 3;
+
+        Bytes::default()
+            .raw(&[0x28, 0xb5, 0x2f, 0xfd, 0x20, data.len() as u8])
+            .raw(&block_header.to_le_bytes()[..3])
+            .raw(data)
+            .0
+    }
+
+    fn decompression_error<'a>(elf: &'a ParsedElf) -> (&'a str, Option<(usize, usize)>) {
+        assert_eq!(elf.diagnostics.len(), 1);
+
+        match &elf.diagnostics[0].error {
+            ElfError::Decompression {
+                offset,
+                structure,
+                reason,
+            } => {
+                assert_eq!(*offset, elf.shdrs[1].file_offset);
+                assert_eq!(*structure, Structure::CompressedSection(1));
+
+                (reason.as_str(), elf.diagnostics[0].location)
+            }
+            _ => panic!("not a decompression error"),
+        }
+    }
+
+    #[test]
+    fn zlib_and_zstd_sections() {
+        for (compression, data) in [
+            (ELFCOMPRESS_ZLIB, compress_to_vec_zlib(TEXT, 6)),
+            (ELFCOMPRESS_ZSTD, zstd_frame(TEXT)),
+        ] {
+            let buf = build(&[compressed(compression, TEXT.len() as u64, &data)], &[]);
+            let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+            let chdr = &elf.compressed[&1];
+
+            assert_eq!(
+                (
+                    chdr.compression,
+                    chdr.legacy,
+                    chdr.size,
+                    chdr.alignment,
+                    chdr.header_size
+                ),
+                (compression, false, TEXT.len() as u64, 1, 24)
+            );
+            assert_eq!(chdr.data.as_deref(), Some(TEXT));
+            assert_eq!(elf.section_contents(1).as_deref(), Some(TEXT));
+            assert_eq!(elf.contents_offset(1), 0);
+            assert!(elf.diagnostics.is_empty());
+        }
+    }
+
+    #[test]
+    fn data_larger_or_smaller_than_ch_size() {
+        let data = compress_to_vec_zlib(TEXT, 6);
+
+        for (size, expected) in [
+            (4, "more than the 4 bytes of the header"),
+            (100, "20 bytes instead of the 100 of the header"),
+        ] {
+            let buf = build(&[compressed(ELFCOMPRESS_ZLIB, size, &data)], &[]);
+            let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+            let start = elf.shdrs[1].file_offset;
+
+            assert!(elf.compressed[&1].data.is_none());
+            assert!(elf.section_contents(1).is_none());
+            assert_eq!(
+                decompression_error(&elf),
+                (expected, Some((start + 24, data.len())))
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_stream() {
+        let data = compress_to_vec_zlib(TEXT, 6);
+        let cut = &data[..data.len() / 2];
+        let buf = build(&[compressed(ELFCOMPRESS_ZLIB, TEXT.len() as u64, cut)], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[1].file_offset;
+        let (_, location) = decompression_error(&elf);
+
+        assert!(elf.compressed[&1].data.is_none());
+        assert_eq!(location, Some((start + 24, cut.len())));
+    }
+
+    #[test]
+    fn truncated_header_and_unknown_type() {
+        let short = Section {
+            flags: SHF_COMPRESSED,
+            ..section(".debug_str", SHT_PROGBITS, Bytes::default().u32(1).u32(0))
+        };
+        let buf = build(&[short, compressed(7, 0, &[])], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let (first, second) = (elf.shdrs[1].file_offset, elf.shdrs[2].file_offset);
+        let diagnostics: Vec<_> = elf
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (&diagnostic.error, diagnostic.location))
+            .collect();
+
+        assert!(!elf.compressed.contains_key(&1));
+        assert_eq!(elf.compressed[&2].compression, 7);
+        assert!(elf.compressed[&2].data.is_none());
+        assert_eq!(
+            diagnostics,
+            [
+                (
+                    &ElfError::Truncated {
+                        offset: first,
+                        structure: Structure::CompressedSection(1),
+                        size: 24,
+                        available: 8,
+                    },
+                    Some((first, 8))
+                ),
+                (
+                    &ElfError::UnknownValue {
+                        offset: second,
+                        structure: Structure::CompressedSection(2),
+                        field: "ch_type",
+                        value: 7,
+                    },
+                    Some((second, 4))
+                )
+            ]
+        );
+    }
+}
@@ -33,6 +33,12 @@ pub const ELF_ET_HIOS: u16 = 0xfeff;
 pub const ELF_ET_LOPROC: u16 = 0xff00;
 pub const ELF_ET_HIPROC: u16 = 0xffff;
 
+pub const EM_386: u16 = 3;
+pub const EM_ARM: u16 = 40;
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+pub const EM_RISCV: u16 = 243;
+
 pub const PT_NULL: u32 = 0;
 pub const PT_LOAD: u32 = 1;
 pub const PT_DYNAMIC: u32 = 2;
@@ -55,9 +61,235 @@ pub const PF_R: u32 = 0b100;
 pub const PF_MASKOS: u32 = 0x00ff_0000;
 pub const PF_MASKPROC: u32 = 0xff00_0000;
 
+pub const DT_NULL: i64 = 0;
+pub const DT_NEEDED: i64 = 1;
+pub const DT_PLTRELSZ: i64 = 2;
+pub const DT_PLTGOT: i64 = 3;
+pub const DT_HASH: i64 = 4;
+pub const DT_STRTAB: i64 = 5;
+pub const DT_SYMTAB: i64 = 6;
+pub const DT_RELA: i64 = 7;
+pub const DT_RELASZ: i64 = 8;
+pub const DT_RELAENT: i64 = 9;
+pub const DT_STRSZ: i64 = 10;
+pub const DT_SYMENT: i64 = 11;
+pub const DT_INIT: i64 = 12;
+pub const DT_FINI: i64 = 13;
+pub const DT_SONAME: i64 = 14;
+pub const DT_RPATH: i64 = 15;
+pub const DT_SYMBOLIC: i64 = 16;
+pub const DT_REL: i64 = 17;
+pub const DT_RELSZ: i64 = 18;
+pub const DT_RELENT: i64 = 19;
+pub const DT_PLTREL: i64 = 20;
+pub const DT_DEBUG: i64 = 21;
+pub const DT_TEXTREL: i64 = 22;
+pub const DT_JMPREL: i64 = 23;
+pub const DT_BIND_NOW: i64 = 24;
+pub const DT_INIT_ARRAY: i64 = 25;
+pub const DT_FINI_ARRAY: i64 = 26;
+pub const DT_INIT_ARRAYSZ: i64 = 27;
+pub const DT_FINI_ARRAYSZ: i64 = 28;
+pub const DT_RUNPATH: i64 = 29;
+pub const DT_FLAGS: i64 = 30;
+pub const DT_PREINIT_ARRAY: i64 = 32;
+pub const DT_PREINIT_ARRAYSZ: i64 = 33;
+pub const DT_SYMTAB_SHNDX: i64 = 34;
+pub const DT_RELRSZ: i64 = 35;
+pub const DT_RELR: i64 = 36;
+pub const DT_RELRENT: i64 = 37;
+pub const DT_GNU_PRELINKED: i64 = 0x6fff_fdf5;
+pub const DT_GNU_CONFLICTSZ: i64 = 0x6fff_fdf6;
+pub const DT_GNU_LIBLISTSZ: i64 = 0x6fff_fdf7;
+pub const DT_CHECKSUM: i64 = 0x6fff_fdf8;
+pub const DT_PLTPADSZ: i64 = 0x6fff_fdf9;
+pub const DT_MOVEENT: i64 = 0x6fff_fdfa;
+pub const DT_MOVESZ: i64 = 0x6fff_fdfb;
+pub const DT_FEATURE_1: i64 = 0x6fff_fdfc;
+pub const DT_POSFLAG_1: i64 = 0x6fff_fdfd;
+pub const DT_SYMINSZ: i64 = 0x6fff_fdfe;
+pub const DT_SYMINENT: i64 = 0x6fff_fdff;
+pub const DT_GNU_HASH: i64 = 0x6fff_fef5;
+pub const DT_TLSDESC_PLT: i64 = 0x6fff_fef6;
+pub const DT_TLSDESC_GOT: i64 = 0x6fff_fef7;
+pub const DT_GNU_CONFLICT: i64 = 0x6fff_fef8;
+pub const DT_GNU_LIBLIST: i64 = 0x6fff_fef9;
+pub const DT_CONFIG: i64 = 0x6fff_fefa;
+pub const DT_DEPAUDIT: i64 = 0x6fff_fefb;
+pub const DT_AUDIT: i64 = 0x6fff_fefc;
+pub const DT_PLTPAD: i64 = 0x6fff_fefd;
+pub const DT_MOVETAB: i64 = 0x6fff_fefe;
+pub const DT_SYMINFO: i64 = 0x6fff_feff;
+pub const DT_VERSYM: i64 = 0x6fff_fff0;
+pub const DT_RELACOUNT: i64 = 0x6fff_fff9;
+pub const DT_RELCOUNT: i64 = 0x6fff_fffa;
+pub const DT_FLAGS_1: i64 = 0x6fff_fffb;
+pub const DT_VERDEF: i64 = 0x6fff_fffc;
+pub const DT_VERDEFNUM: i64 = 0x6fff_fffd;
+pub const DT_VERNEED: i64 = 0x6fff_fffe;
+pub const DT_VERNEEDNUM: i64 = 0x6fff_ffff;
+pub const DT_AUXILIARY: i64 = 0x7fff_fffd;
+pub const DT_FILTER: i64 = 0x7fff_ffff;
+
+pub const DF_ORIGIN: u64 = 0x1;
+pub const DF_SYMBOLIC: u64 = 0x2;
+pub const DF_TEXTREL: u64 = 0x4;
+pub const DF_BIND_NOW: u64 = 0x8;
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_GLOBAL: u64 = 0x2;
+pub const DF_1_GROUP: u64 = 0x4;
+pub const DF_1_NODELETE: u64 = 0x8;
+pub const DF_1_LOADFLTR: u64 = 0x10;
+pub const DF_1_INITFIRST: u64 = 0x20;
+pub const DF_1_NOOPEN: u64 = 0x40;
+pub const DF_1_ORIGIN: u64 = 0x80;
+pub const DF_1_DIRECT: u64 = 0x100;
+pub const DF_1_TRANS: u64 = 0x200;
+pub const DF_1_INTERPOSE: u64 = 0x400;
+pub const DF_1_NODEFLIB: u64 = 0x800;
+pub const DF_1_NODUMP: u64 = 0x1000;
+pub const DF_1_CONFALT: u64 = 0x2000;
+pub const DF_1_ENDFILTEE: u64 = 0x4000;
+pub const DF_1_DISPRELDNE: u64 = 0x8000;
+pub const DF_1_DISPRELPND: u64 = 0x0001_0000;
+pub const DF_1_NODIRECT: u64 = 0x0002_0000;
+pub const DF_1_IGNMULDEF: u64 = 0x0004_0000;
+pub const DF_1_NOKSYMS: u64 = 0x0008_0000;
+pub const DF_1_NOHDR: u64 = 0x0010_0000;
+pub const DF_1_EDITED: u64 = 0x0020_0000;
+pub const DF_1_NORELOC: u64 = 0x0040_0000;
+pub const DF_1_SYMINTPOSE: u64 = 0x0080_0000;
+pub const DF_1_GLOBAUDIT: u64 = 0x0100_0000;
+pub const DF_1_SINGLETON: u64 = 0x0200_0000;
+pub const DF_1_STUB: u64 = 0x0400_0000;
+pub const DF_1_PIE: u64 = 0x0800_0000;
+
+pub const NT_GNU_ABI_TAG: u32 = 0x1;
+pub const NT_GNU_HWCAP: u32 = 0x2;
 pub const NT_GNU_BUILD_ID: u32 = 0x3;
+pub const NT_GNU_GOLD_VERSION: u32 = 0x4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 0x5;
+pub const NT_GO_BUILD_ID: u32 = 0x4;
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe_1a7e;
+pub const NT_ANDROID_TYPE_IDENT: u32 = 0x1;
+pub const NT_FREEBSD_ABI_TAG: u32 = 0x1;
+pub const NT_FREEBSD_NOINIT_TAG: u32 = 0x2;
+pub const NT_FREEBSD_ARCH_TAG: u32 = 0x3;
+pub const NT_FREEBSD_FEATURE_CTL: u32 = 0x4;
+pub const NT_NETBSD_IDENT: u32 = 0x1;
+
+// core dumps, owned by "CORE" or "LINUX"
+pub const NT_PRSTATUS: u32 = 0x1;
+pub const NT_FPREGSET: u32 = 0x2;
+pub const NT_PRPSINFO: u32 = 0x3;
+pub const NT_TASKSTRUCT: u32 = 0x4;
+pub const NT_AUXV: u32 = 0x6;
+pub const NT_PRXFPREG: u32 = 0x46e6_2b7f;
+pub const NT_FILE: u32 = 0x4649_4c45;
+pub const NT_SIGINFO: u32 = 0x5349_4749;
+pub const NT_386_TLS: u32 = 0x200;
+pub const NT_X86_XSTATE: u32 = 0x202;
+pub const NT_ARM_TLS: u32 = 0x401;
+pub const NT_ARM_HW_BREAK: u32 = 0x402;
+pub const NT_ARM_HW_WATCH: u32 = 0x403;
+pub const NT_ARM_SYSTEM_CALL: u32 = 0x404;
+pub const NT_ARM_SVE: u32 = 0x405;
+pub const NT_ARM_PAC_MASK: u32 = 0x406;
+pub const NT_ARM_TAGGED_ADDR_CTRL: u32 = 0x409;
+
+pub const AT_NULL: u64 = 0;
+pub const AT_IGNORE: u64 = 1;
+pub const AT_EXECFD: u64 = 2;
+pub const AT_PHDR: u64 = 3;
+pub const AT_PHENT: u64 = 4;
+pub const AT_PHNUM: u64 = 5;
+pub const AT_PAGESZ: u64 = 6;
+pub const AT_BASE: u64 = 7;
+pub const AT_FLAGS: u64 = 8;
+pub const AT_ENTRY: u64 = 9;
+pub const AT_NOTELF: u64 = 10;
+pub const AT_UID: u64 = 11;
+pub const AT_EUID: u64 = 12;
+pub const AT_GID: u64 = 13;
+pub const AT_EGID: u64 = 14;
+pub const AT_PLATFORM: u64 = 15;
+pub const AT_HWCAP: u64 = 16;
+pub const AT_CLKTCK: u64 = 17;
+pub const AT_SECURE: u64 = 23;
+pub const AT_BASE_PLATFORM: u64 = 24;
+pub const AT_RANDOM: u64 = 25;
+pub const AT_HWCAP2: u64 = 26;
+pub const AT_RSEQ_FEATURE_SIZE: u64 = 27;
+pub const AT_RSEQ_ALIGN: u64 = 28;
+pub const AT_HWCAP3: u64 = 29;
+pub const AT_HWCAP4: u64 = 30;
+pub const AT_EXECFN: u64 = 31;
+pub const AT_SYSINFO: u64 = 32;
+pub const AT_SYSINFO_EHDR: u64 = 33;
+pub const AT_MINSIGSTKSZ: u64 = 51;
+
+pub const GNU_ABI_TAG_LINUX: u32 = 0;
+pub const GNU_ABI_TAG_HURD: u32 = 1;
+pub const GNU_ABI_TAG_SOLARIS: u32 = 2;
+pub const GNU_ABI_TAG_FREEBSD: u32 = 3;
+pub const GNU_ABI_TAG_NETBSD: u32 = 4;
+pub const GNU_ABI_TAG_SYLLABLE: u32 = 5;
+pub const GNU_ABI_TAG_NACL: u32 = 6;
+
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 0x1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 0x2;
+pub const GNU_PROPERTY_1_NEEDED: u32 = 0xb000_8000;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+pub const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc000_8001;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc000_8002;
+pub const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc001_0001;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc001_0002;
+
+pub const GNU_PROPERTY_1_NEEDED_INDIRECT_EXTERN_ACCESS: u32 = 0x1;
+
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x1;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x2;
+pub const GNU_PROPERTY_X86_FEATURE_1_LAM_U48: u32 = 0x4;
+pub const GNU_PROPERTY_X86_FEATURE_1_LAM_U57: u32 = 0x8;
+
+pub const GNU_PROPERTY_X86_ISA_1_BASELINE: u32 = 0x1;
+pub const GNU_PROPERTY_X86_ISA_1_V2: u32 = 0x2;
+pub const GNU_PROPERTY_X86_ISA_1_V3: u32 = 0x4;
+pub const GNU_PROPERTY_X86_ISA_1_V4: u32 = 0x8;
+
+pub const GNU_PROPERTY_X86_FEATURE_2_X86: u32 = 0x1;
+pub const GNU_PROPERTY_X86_FEATURE_2_X87: u32 = 0x2;
+pub const GNU_PROPERTY_X86_FEATURE_2_MMX: u32 = 0x4;
+pub const GNU_PROPERTY_X86_FEATURE_2_XMM: u32 = 0x8;
+pub const GNU_PROPERTY_X86_FEATURE_2_YMM: u32 = 0x10;
+pub const GNU_PROPERTY_X86_FEATURE_2_ZMM: u32 = 0x20;
+pub const GNU_PROPERTY_X86_FEATURE_2_FXSR: u32 = 0x40;
+pub const GNU_PROPERTY_X86_FEATURE_2_XSAVE: u32 = 0x80;
+pub const GNU_PROPERTY_X86_FEATURE_2_XSAVEOPT: u32 = 0x100;
+pub const GNU_PROPERTY_X86_FEATURE_2_XSAVEC: u32 = 0x200;
+pub const GNU_PROPERTY_X86_FEATURE_2_TMM: u32 = 0x400;
+pub const GNU_PROPERTY_X86_FEATURE_2_MASK: u32 = 0x800;
+
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 0x1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 0x2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_GCS: u32 = 0x4;
+
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+pub const VERSYM_VERSION: u16 = 0x7fff;
+
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+pub const VER_FLG_INFO: u16 = 0x4;
 
 pub const SHN_UNDEF: u16 = 0;
+pub const SHN_ABS: u16 = 0xfff1;
+pub const SHN_COMMON: u16 = 0xfff2;
+pub const SHN_XINDEX: u16 = 0xffff;
 
 pub const SHT_NULL: u32 = 0;
 pub const SHT_PROGBITS: u32 = 1;
@@ -75,7 +307,9 @@ pub const SHT_INIT_ARRAY: u32 = 14;
 pub const SHT_FINI_ARRAY: u32 = 15;
 pub const SHT_LOOS: u32 = 0x6000_0000;
 pub const SHT_GNU_HASH: u32 = 0x6fff_fff6;
+pub const SHT_VER_DEF: u32 = 0x6fff_fffd;
 pub const SHT_VER_NEED: u32 = 0x6fff_fffe;
+pub const SHT_VER_SYM: u32 = 0x6fff_ffff;
 pub const SHT_HIOS: u32 = 0x6fff_ffff;
 pub const SHT_LOPROC: u32 = 0x7000_0000;
 pub const SHT_HIPROC: u32 = 0x7fff_ffff;
@@ -83,9 +317,32 @@ pub const SHT_HIPROC: u32 = 0x7fff_ffff;
 pub const SHF_WRITE: u64 = 0b001;
 pub const SHF_ALLOC: u64 = 0b010;
 pub const SHF_EXECINSTR: u64 = 0b100;
+pub const SHF_COMPRESSED: u64 = 0x800;
 pub const SHF_MASKOS: u64 = 0x0f00_0000;
 pub const SHF_MASKPROC: u64 = 0xf000_0000;
 
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STB_WEAK: u8 = 2;
+pub const STB_GNU_UNIQUE: u8 = 10;
+
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+pub const STT_GNU_IFUNC: u8 = 10;
+
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
 pub fn type_to_string(e_type: u16) -> String {
     match e_type {
         ELF_ET_NONE => String::from("None (NONE)"),
@@ -124,7 +381,7 @@ pub fn machine_to_string(e_machine: u16) -> String {
         0 => String::from("None"),
         1 => String::from("AT&T WE 32100"),
         2 => String::from("SPARC"),
-        3 => String::from("x86"),
+        EM_386 => String::from("x86"),
         4 => String::from("Motorolla 68000"),
         5 => String::from("Motorolla 88000"),
         6 => String::from("Intel MCU"),
@@ -137,13 +394,13 @@ pub fn machine_to_string(e_machine: u16) -> String {
         20 => String::from("PowerPC"),
         21 => String::from("PowerPC 64-bit"),
         22 => String::from("S390"),
-        40 => String::from("ARM Aarch32"),
+        EM_ARM => String::from("ARM Aarch32"),
         50 => String::from("Itanium IA-64"),
-        62 => String::from("x86-64"),
-        183 => String::from("ARM Aarch64"),
+        EM_X86_64 => String::from("x86-64"),
+        EM_AARCH64 => String::from("ARM Aarch64"),
         190 => String::from("CUDA"),
         224 => String::from("AMDGPU"),
-        243 => String::from("RISC-V"),
+        EM_RISCV => String::from("RISC-V"),
         x => format!("Unknown: {}", x),
     }
 }
@@ -205,8 +462,9 @@ pub fn shtype_to_string(shtype: u32) -> String {
         SHT_DYNSYM => String::from("DYNSYM"),
         SHT_LOOS => String::from("LOOS"),
         SHT_GNU_HASH => String::from("GNU_HASH (OS-specific)"),
+        SHT_VER_DEF => String::from("VER_DEF (OS-specific)"),
         SHT_VER_NEED => String::from("VER_NEED (OS-specific)"),
-        SHT_HIOS => String::from("HIOS"),
+        SHT_VER_SYM => String::from("VER_SYM (OS-specific)"),
         SHT_LOPROC => String::from("LOPROC"),
         SHT_HIPROC => String::from("HIPROC"),
         x => format!("Unknown: {}", x),
@@ -228,9 +486,1377 @@ pub fn shflags_to_string(flags: u64) -> String {
         s.push('X');
     }
 
+    if flags & SHF_COMPRESSED != 0 {
+        s.push('C');
+    }
+
     if s.is_empty() {
         s.push('0');
     }
 
     s
 }
+
+pub fn elfcompress_to_string(compression: u32) -> String {
+    match compression {
+        ELFCOMPRESS_ZLIB => String::from("ZLIB"),
+        ELFCOMPRESS_ZSTD => String::from("ZSTD"),
+        x => format!("Unknown: {}", x),
+    }
+}
+
+pub fn stbind_to_string(bind: u8) -> String {
+    match bind {
+        STB_LOCAL => String::from("LOCAL"),
+        STB_GLOBAL => String::from("GLOBAL"),
+        STB_WEAK => String::from("WEAK"),
+        STB_GNU_UNIQUE => String::from("UNIQUE"),
+        x => format!("Unknown: {}", x),
+    }
+}
+
+pub fn sttype_to_string(stype: u8) -> String {
+    match stype {
+        STT_NOTYPE => String::from("NOTYPE"),
+        STT_OBJECT => String::from("OBJECT"),
+        STT_FUNC => String::from("FUNC"),
+        STT_SECTION => String::from("SECTION"),
+        STT_FILE => String::from("FILE"),
+        STT_COMMON => String::from("COMMON"),
+        STT_TLS => String::from("TLS"),
+        STT_GNU_IFUNC => String::from("IFUNC"),
+        x => format!("Unknown: {}", x),
+    }
+}
+
+pub fn stvis_to_string(visibility: u8) -> String {
+    match visibility {
+        STV_DEFAULT => String::from("DEFAULT"),
+        STV_INTERNAL => String::from("INTERNAL"),
+        STV_HIDDEN => String::from("HIDDEN"),
+        STV_PROTECTED => String::from("PROTECTED"),
+        x => format!("Unknown: {}", x),
+    }
+}
+
+pub fn shndx_to_string(shndx: u16) -> String {
+    match shndx {
+        SHN_UNDEF => String::from("UND"),
+        SHN_ABS => String::from("ABS"),
+        SHN_COMMON => String::from("COM"),
+        SHN_XINDEX => String::from("XINDEX"),
+        x => format!("{}", x),
+    }
+}
+
+fn x86_64_reltype_name(rtype: u32) -> Option<&'static str> {
+    Some(match rtype {
+        0 => "R_X86_64_NONE",
+        1 => "R_X86_64_64",
+        2 => "R_X86_64_PC32",
+        3 => "R_X86_64_GOT32",
+        4 => "R_X86_64_PLT32",
+        5 => "R_X86_64_COPY",
+        6 => "R_X86_64_GLOB_DAT",
+        7 => "R_X86_64_JUMP_SLOT",
+        8 => "R_X86_64_RELATIVE",
+        9 => "R_X86_64_GOTPCREL",
+        10 => "R_X86_64_32",
+        11 => "R_X86_64_32S",
+        12 => "R_X86_64_16",
+        13 => "R_X86_64_PC16",
+        14 => "R_X86_64_8",
+        15 => "R_X86_64_PC8",
+        16 => "R_X86_64_DTPMOD64",
+        17 => "R_X86_64_DTPOFF64",
+        18 => "R_X86_64_TPOFF64",
+        19 => "R_X86_64_TLSGD",
+        20 => "R_X86_64_TLSLD",
+        21 => "R_X86_64_DTPOFF32",
+        22 => "R_X86_64_GOTTPOFF",
+        23 => "R_X86_64_TPOFF32",
+        24 => "R_X86_64_PC64",
+        25 => "R_X86_64_GOTOFF64",
+        26 => "R_X86_64_GOTPC32",
+        27 => "R_X86_64_GOT64",
+        28 => "R_X86_64_GOTPCREL64",
+        29 => "R_X86_64_GOTPC64",
+        30 => "R_X86_64_GOTPLT64",
+        31 => "R_X86_64_PLTOFF64",
+        32 => "R_X86_64_SIZE32",
+        33 => "R_X86_64_SIZE64",
+        34 => "R_X86_64_GOTPC32_TLSDESC",
+        35 => "R_X86_64_TLSDESC_CALL",
+        36 => "R_X86_64_TLSDESC",
+        37 => "R_X86_64_IRELATIVE",
+        38 => "R_X86_64_RELATIVE64",
+        41 => "R_X86_64_GOTPCRELX",
+        42 => "R_X86_64_REX_GOTPCRELX",
+        _ => return None,
+    })
+}
+
+fn i386_reltype_name(rtype: u32) -> Option<&'static str> {
+    Some(match rtype {
+        0 => "R_386_NONE",
+        1 => "R_386_32",
+        2 => "R_386_PC32",
+        3 => "R_386_GOT32",
+        4 => "R_386_PLT32",
+        5 => "R_386_COPY",
+        6 => "R_386_GLOB_DAT",
+        7 => "R_386_JMP_SLOT",
+        8 => "R_386_RELATIVE",
+        9 => "R_386_GOTOFF",
+        10 => "R_386_GOTPC",
+        11 => "R_386_32PLT",
+        14 => "R_386_TLS_TPOFF",
+        15 => "R_386_TLS_IE",
+        16 => "R_386_TLS_GOTIE",
+        17 => "R_386_TLS_LE",
+        18 => "R_386_TLS_GD",
+        19 => "R_386_TLS_LDM",
+        20 => "R_386_16",
+        21 => "R_386_PC16",
+        22 => "R_386_8",
+        23 => "R_386_PC8",
+        24 => "R_386_TLS_GD_32",
+        25 => "R_386_TLS_GD_PUSH",
+        26 => "R_386_TLS_GD_CALL",
+        27 => "R_386_TLS_GD_POP",
+        28 => "R_386_TLS_LDM_32",
+        29 => "R_386_TLS_LDM_PUSH",
+        30 => "R_386_TLS_LDM_CALL",
+        31 => "R_386_TLS_LDM_POP",
+        32 => "R_386_TLS_LDO_32",
+        33 => "R_386_TLS_IE_32",
+        34 => "R_386_TLS_LE_32",
+        35 => "R_386_TLS_DTPMOD32",
+        36 => "R_386_TLS_DTPOFF32",
+        37 => "R_386_TLS_TPOFF32",
+        38 => "R_386_SIZE32",
+        39 => "R_386_TLS_GOTDESC",
+        40 => "R_386_TLS_DESC_CALL",
+        41 => "R_386_TLS_DESC",
+        42 => "R_386_IRELATIVE",
+        43 => "R_386_GOT32X",
+        _ => return None,
+    })
+}
+
+fn aarch64_reltype_name(rtype: u32) -> Option<&'static str> {
+    Some(match rtype {
+        0 => "R_AARCH64_NONE",
+        257 => "R_AARCH64_ABS64",
+        258 => "R_AARCH64_ABS32",
+        259 => "R_AARCH64_ABS16",
+        260 => "R_AARCH64_PREL64",
+        261 => "R_AARCH64_PREL32",
+        262 => "R_AARCH64_PREL16",
+        263 => "R_AARCH64_MOVW_UABS_G0",
+        264 => "R_AARCH64_MOVW_UABS_G0_NC",
+        265 => "R_AARCH64_MOVW_UABS_G1",
+        266 => "R_AARCH64_MOVW_UABS_G1_NC",
+        267 => "R_AARCH64_MOVW_UABS_G2",
+        268 => "R_AARCH64_MOVW_UABS_G2_NC",
+        269 => "R_AARCH64_MOVW_UABS_G3",
+        270 => "R_AARCH64_MOVW_SABS_G0",
+        271 => "R_AARCH64_MOVW_SABS_G1",
+        272 => "R_AARCH64_MOVW_SABS_G2",
+        273 => "R_AARCH64_LD_PREL_LO19",
+        274 => "R_AARCH64_ADR_PREL_LO21",
+        275 => "R_AARCH64_ADR_PREL_PG_HI21",
+        276 => "R_AARCH64_ADR_PREL_PG_HI21_NC",
+        277 => "R_AARCH64_ADD_ABS_LO12_NC",
+        278 => "R_AARCH64_LDST8_ABS_LO12_NC",
+        279 => "R_AARCH64_TSTBR14",
+        280 => "R_AARCH64_CONDBR19",
+        282 => "R_AARCH64_JUMP26",
+        283 => "R_AARCH64_CALL26",
+        284 => "R_AARCH64_LDST16_ABS_LO12_NC",
+        285 => "R_AARCH64_LDST32_ABS_LO12_NC",
+        286 => "R_AARCH64_LDST64_ABS_LO12_NC",
+        299 => "R_AARCH64_LDST128_ABS_LO12_NC",
+        309 => "R_AARCH64_GOT_LD_PREL19",
+        311 => "R_AARCH64_ADR_GOT_PAGE",
+        312 => "R_AARCH64_LD64_GOT_LO12_NC",
+        513 => "R_AARCH64_TLSGD_ADR_PAGE21",
+        514 => "R_AARCH64_TLSGD_ADD_LO12_NC",
+        541 => "R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21",
+        542 => "R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC",
+        549 => "R_AARCH64_TLSLE_ADD_TPREL_HI12",
+        550 => "R_AARCH64_TLSLE_ADD_TPREL_LO12",
+        551 => "R_AARCH64_TLSLE_ADD_TPREL_LO12_NC",
+        560 => "R_AARCH64_TLSDESC_ADR_PAGE21",
+        561 => "R_AARCH64_TLSDESC_LD64_LO12",
+        562 => "R_AARCH64_TLSDESC_ADD_LO12",
+        569 => "R_AARCH64_TLSDESC_CALL",
+        1024 => "R_AARCH64_COPY",
+        1025 => "R_AARCH64_GLOB_DAT",
+        1026 => "R_AARCH64_JUMP_SLOT",
+        1027 => "R_AARCH64_RELATIVE",
+        1028 => "R_AARCH64_TLS_DTPMOD64",
+        1029 => "R_AARCH64_TLS_DTPREL64",
+        1030 => "R_AARCH64_TLS_TPREL64",
+        1031 => "R_AARCH64_TLSDESC",
+        1032 => "R_AARCH64_IRELATIVE",
+        _ => return None,
+    })
+}
+
+fn arm_reltype_name(rtype: u32) -> Option<&'static str> {
+    Some(match rtype {
+        0 => "R_ARM_NONE",
+        1 => "R_ARM_PC24",
+        2 => "R_ARM_ABS32",
+        3 => "R_ARM_REL32",
+        4 => "R_ARM_LDR_PC_G0",
+        5 => "R_ARM_ABS16",
+        6 => "R_ARM_ABS12",
+        7 => "R_ARM_THM_ABS5",
+        8 => "R_ARM_ABS8",
+        9 => "R_ARM_SBREL32",
+        10 => "R_ARM_THM_CALL",
+        11 => "R_ARM_THM_PC8",
+        17 => "R_ARM_TLS_DTPMOD32",
+        18 => "R_ARM_TLS_DTPOFF32",
+        19 => "R_ARM_TLS_TPOFF32",
+        20 => "R_ARM_COPY",
+        21 => "R_ARM_GLOB_DAT",
+        22 => "R_ARM_JUMP_SLOT",
+        23 => "R_ARM_RELATIVE",
+        24 => "R_ARM_GOTOFF32",
+        25 => "R_ARM_BASE_PREL",
+        26 => "R_ARM_GOT_BREL",
+        27 => "R_ARM_PLT32",
+        28 => "R_ARM_CALL",
+        29 => "R_ARM_JUMP24",
+        30 => "R_ARM_THM_JUMP24",
+        40 => "R_ARM_V4BX",
+        42 => "R_ARM_PREL31",
+        43 => "R_ARM_MOVW_ABS_NC",
+        44 => "R_ARM_MOVT_ABS",
+        45 => "R_ARM_MOVW_PREL_NC",
+        46 => "R_ARM_MOVT_PREL",
+        47 => "R_ARM_THM_MOVW_ABS_NC",
+        48 => "R_ARM_THM_MOVT_ABS",
+        49 => "R_ARM_THM_MOVW_PREL_NC",
+        50 => "R_ARM_THM_MOVT_PREL",
+        51 => "R_ARM_THM_JUMP19",
+        102 => "R_ARM_THM_JUMP11",
+        103 => "R_ARM_THM_JUMP8",
+        104 => "R_ARM_TLS_GD32",
+        105 => "R_ARM_TLS_LDM32",
+        106 => "R_ARM_TLS_LDO32",
+        107 => "R_ARM_TLS_IE32",
+        108 => "R_ARM_TLS_LE32",
+        160 => "R_ARM_IRELATIVE",
+        _ => return None,
+    })
+}
+
+fn riscv_reltype_name(rtype: u32) -> Option<&'static str> {
+    Some(match rtype {
+        0 => "R_RISCV_NONE",
+        1 => "R_RISCV_32",
+        2 => "R_RISCV_64",
+        3 => "R_RISCV_RELATIVE",
+        4 => "R_RISCV_COPY",
+        5 => "R_RISCV_JUMP_SLOT",
+        6 => "R_RISCV_TLS_DTPMOD32",
+        7 => "R_RISCV_TLS_DTPMOD64",
+        8 => "R_RISCV_TLS_DTPREL32",
+        9 => "R_RISCV_TLS_DTPREL64",
+        10 => "R_RISCV_TLS_TPREL32",
+        11 => "R_RISCV_TLS_TPREL64",
+        12 => "R_RISCV_TLSDESC",
+        16 => "R_RISCV_BRANCH",
+        17 => "R_RISCV_JAL",
+        18 => "R_RISCV_CALL",
+        19 => "R_RISCV_CALL_PLT",
+        20 => "R_RISCV_GOT_HI20",
+        21 => "R_RISCV_TLS_GOT_HI20",
+        22 => "R_RISCV_TLS_GD_HI20",
+        23 => "R_RISCV_PCREL_HI20",
+        24 => "R_RISCV_PCREL_LO12_I",
+        25 => "R_RISCV_PCREL_LO12_S",
+        26 => "R_RISCV_HI20",
+        27 => "R_RISCV_LO12_I",
+        28 => "R_RISCV_LO12_S",
+        29 => "R_RISCV_TPREL_HI20",
+        30 => "R_RISCV_TPREL_LO12_I",
+        31 => "R_RISCV_TPREL_LO12_S",
+        32 => "R_RISCV_TPREL_ADD",
+        33 => "R_RISCV_ADD8",
+        34 => "R_RISCV_ADD16",
+        35 => "R_RISCV_ADD32",
+        36 => "R_RISCV_ADD64",
+        37 => "R_RISCV_SUB8",
+        38 => "R_RISCV_SUB16",
+        39 => "R_RISCV_SUB32",
+        40 => "R_RISCV_SUB64",
+        43 => "R_RISCV_ALIGN",
+        44 => "R_RISCV_RVC_BRANCH",
+        45 => "R_RISCV_RVC_JUMP",
+        51 => "R_RISCV_RELAX",
+        52 => "R_RISCV_SUB6",
+        53 => "R_RISCV_SET6",
+        54 => "R_RISCV_SET8",
+        55 => "R_RISCV_SET16",
+        56 => "R_RISCV_SET32",
+        57 => "R_RISCV_32_PCREL",
+        58 => "R_RISCV_IRELATIVE",
+        59 => "R_RISCV_PLT32",
+        60 => "R_RISCV_SET_ULEB128",
+        61 => "R_RISCV_SUB_ULEB128",
+        _ => return None,
+    })
+}
+
+pub fn reltype_to_string(machine: u16, rtype: u32) -> String {
+    let name = match machine {
+        EM_X86_64 => x86_64_reltype_name(rtype),
+        EM_386 => i386_reltype_name(rtype),
+        EM_AARCH64 => aarch64_reltype_name(rtype),
+        EM_ARM => arm_reltype_name(rtype),
+        EM_RISCV => riscv_reltype_name(rtype),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => String::from(name),
+        None => format!("Unknown: {}", rtype),
+    }
+}
+
+pub fn dtag_to_string(tag: i64) -> String {
+    match tag {
+        DT_NULL => String::from("NULL"),
+        DT_NEEDED => String::from("NEEDED"),
+        DT_PLTRELSZ => String::from("PLTRELSZ"),
+        DT_PLTGOT => String::from("PLTGOT"),
+        DT_HASH => String::from("HASH"),
+        DT_STRTAB => String::from("STRTAB"),
+        DT_SYMTAB => String::from("SYMTAB"),
+        DT_RELA => String::from("RELA"),
+        DT_RELASZ => String::from("RELASZ"),
+        DT_RELAENT => String::from("RELAENT"),
+        DT_STRSZ => String::from("STRSZ"),
+        DT_SYMENT => String::from("SYMENT"),
+        DT_INIT => String::from("INIT"),
+        DT_FINI => String::from("FINI"),
+        DT_SONAME => String::from("SONAME"),
+        DT_RPATH => String::from("RPATH"),
+        DT_SYMBOLIC => String::from("SYMBOLIC"),
+        DT_REL => String::from("REL"),
+        DT_RELSZ => String::from("RELSZ"),
+        DT_RELENT => String::from("RELENT"),
+        DT_PLTREL => String::from("PLTREL"),
+        DT_DEBUG => String::from("DEBUG"),
+        DT_TEXTREL => String::from("TEXTREL"),
+        DT_JMPREL => String::from("JMPREL"),
+        DT_BIND_NOW => String::from("BIND_NOW"),
+        DT_INIT_ARRAY => String::from("INIT_ARRAY"),
+        DT_FINI_ARRAY => String::from("FINI_ARRAY"),
+        DT_INIT_ARRAYSZ => String::from("INIT_ARRAYSZ"),
+        DT_FINI_ARRAYSZ => String::from("FINI_ARRAYSZ"),
+        DT_RUNPATH => String::from("RUNPATH"),
+        DT_FLAGS => String::from("FLAGS"),
+        DT_PREINIT_ARRAY => String::from("PREINIT_ARRAY"),
+        DT_PREINIT_ARRAYSZ => String::from("PREINIT_ARRAYSZ"),
+        DT_SYMTAB_SHNDX => String::from("SYMTAB_SHNDX"),
+        DT_RELRSZ => String::from("RELRSZ"),
+        DT_RELR => String::from("RELR"),
+        DT_RELRENT => String::from("RELRENT"),
+        DT_GNU_PRELINKED => String::from("GNU_PRELINKED"),
+        DT_GNU_CONFLICTSZ => String::from("GNU_CONFLICTSZ"),
+        DT_GNU_LIBLISTSZ => String::from("GNU_LIBLISTSZ"),
+        DT_CHECKSUM => String::from("CHECKSUM"),
+        DT_PLTPADSZ => String::from("PLTPADSZ"),
+        DT_MOVEENT => String::from("MOVEENT"),
+        DT_MOVESZ => String::from("MOVESZ"),
+        DT_FEATURE_1 => String::from("FEATURE_1"),
+        DT_POSFLAG_1 => String::from("POSFLAG_1"),
+        DT_SYMINSZ => String::from("SYMINSZ"),
+        DT_SYMINENT => String::from("SYMINENT"),
+        DT_GNU_HASH => String::from("GNU_HASH"),
+        DT_TLSDESC_PLT => String::from("TLSDESC_PLT"),
+        DT_TLSDESC_GOT => String::from("TLSDESC_GOT"),
+        DT_GNU_CONFLICT => String::from("GNU_CONFLICT"),
+        DT_GNU_LIBLIST => String::from("GNU_LIBLIST"),
+        DT_CONFIG => String::from("CONFIG"),
+        DT_DEPAUDIT => String::from("DEPAUDIT"),
+        DT_AUDIT => String::from("AUDIT"),
+        DT_PLTPAD => String::from("PLTPAD"),
+        DT_MOVETAB => String::from("MOVETAB"),
+        DT_SYMINFO => String::from("SYMINFO"),
+        DT_VERSYM => String::from("VERSYM"),
+        DT_RELACOUNT => String::from("RELACOUNT"),
+        DT_RELCOUNT => String::from("RELCOUNT"),
+        DT_FLAGS_1 => String::from("FLAGS_1"),
+        DT_VERDEF => String::from("VERDEF"),
+        DT_VERDEFNUM => String::from("VERDEFNUM"),
+        DT_VERNEED => String::from("VERNEED"),
+        DT_VERNEEDNUM => String::from("VERNEEDNUM"),
+        DT_AUXILIARY => String::from("AUXILIARY"),
+        DT_FILTER => String::from("FILTER"),
+        x => format!("Unknown: {:#x}", x),
+    }
+}
+
+fn flags_to_string(flags: u64, names: &[(u64, &str)]) -> String {
+    let mut s = String::new();
+    let mut rest = flags;
+
+    for (flag, name) in names.iter() {
+        if flags & flag != 0 {
+            if !s.is_empty() {
+                s.push(' ');
+            }
+
+            s.push_str(name);
+            rest &= !flag;
+        }
+    }
+
+    if rest != 0 {
+        if !s.is_empty() {
+            s.push(' ');
+        }
+
+        s += &format!("{:#x}", rest);
+    }
+
+    s
+}
+
+pub fn dflags_to_string(flags: u64) -> String {
+    flags_to_string(
+        flags,
+        &[
+            (DF_ORIGIN, "ORIGIN"),
+            (DF_SYMBOLIC, "SYMBOLIC"),
+            (DF_TEXTREL, "TEXTREL"),
+            (DF_BIND_NOW, "BIND_NOW"),
+            (DF_STATIC_TLS, "STATIC_TLS"),
+        ],
+    )
+}
+
+pub fn dflags1_to_string(flags: u64) -> String {
+    flags_to_string(
+        flags,
+        &[
+            (DF_1_NOW, "NOW"),
+            (DF_1_GLOBAL, "GLOBAL"),
+            (DF_1_GROUP, "GROUP"),
+            (DF_1_NODELETE, "NODELETE"),
+            (DF_1_LOADFLTR, "LOADFLTR"),
+            (DF_1_INITFIRST, "INITFIRST"),
+            (DF_1_NOOPEN, "NOOPEN"),
+            (DF_1_ORIGIN, "ORIGIN"),
+            (DF_1_DIRECT, "DIRECT"),
+            (DF_1_TRANS, "TRANS"),
+            (DF_1_INTERPOSE, "INTERPOSE"),
+            (DF_1_NODEFLIB, "NODEFLIB"),
+            (DF_1_NODUMP, "NODUMP"),
+            (DF_1_CONFALT, "CONFALT"),
+            (DF_1_ENDFILTEE, "ENDFILTEE"),
+            (DF_1_DISPRELDNE, "DISPRELDNE"),
+            (DF_1_DISPRELPND, "DISPRELPND"),
+            (DF_1_NODIRECT, "NODIRECT"),
+            (DF_1_IGNMULDEF, "IGNMULDEF"),
+            (DF_1_NOKSYMS, "NOKSYMS"),
+            (DF_1_NOHDR, "NOHDR"),
+            (DF_1_EDITED, "EDITED"),
+            (DF_1_NORELOC, "NORELOC"),
+            (DF_1_SYMINTPOSE, "SYMINTPOSE"),
+            (DF_1_GLOBAUDIT, "GLOBAUDIT"),
+            (DF_1_SINGLETON, "SINGLETON"),
+            (DF_1_STUB, "STUB"),
+            (DF_1_PIE, "PIE"),
+        ],
+    )
+}
+
+// note types are only unique together with the owner's name
+pub fn ntype_to_string(name: &[u8], ntype: u32) -> String {
+    let known = match (name, ntype) {
+        (b"GNU", NT_GNU_ABI_TAG) => Some("NT_GNU_ABI_TAG"),
+        (b"GNU", NT_GNU_HWCAP) => Some("NT_GNU_HWCAP"),
+        (b"GNU", NT_GNU_BUILD_ID) => Some("NT_GNU_BUILD_ID"),
+        (b"GNU", NT_GNU_GOLD_VERSION) => Some("NT_GNU_GOLD_VERSION"),
+        (b"GNU", NT_GNU_PROPERTY_TYPE_0) => Some("NT_GNU_PROPERTY_TYPE_0"),
+        (b"Go", NT_GO_BUILD_ID) => Some("NT_GO_BUILD_ID"),
+        (b"FDO", NT_FDO_PACKAGING_METADATA) => Some("NT_FDO_PACKAGING_METADATA"),
+        (b"Android", NT_ANDROID_TYPE_IDENT) => Some("NT_ANDROID_TYPE_IDENT"),
+        (b"FreeBSD", NT_FREEBSD_ABI_TAG) => Some("NT_FREEBSD_ABI_TAG"),
+        (b"FreeBSD", NT_FREEBSD_NOINIT_TAG) => Some("NT_FREEBSD_NOINIT_TAG"),
+        (b"FreeBSD", NT_FREEBSD_ARCH_TAG) => Some("NT_FREEBSD_ARCH_TAG"),
+        (b"FreeBSD", NT_FREEBSD_FEATURE_CTL) => Some("NT_FREEBSD_FEATURE_CTL"),
+        (b"NetBSD", NT_NETBSD_IDENT) => Some("NT_NETBSD_IDENT"),
+        (b"CORE", NT_PRSTATUS) => Some("NT_PRSTATUS"),
+        (b"CORE", NT_FPREGSET) => Some("NT_FPREGSET"),
+        (b"CORE", NT_PRPSINFO) => Some("NT_PRPSINFO"),
+        (b"CORE", NT_TASKSTRUCT) => Some("NT_TASKSTRUCT"),
+        (b"CORE", NT_AUXV) => Some("NT_AUXV"),
+        (b"CORE", NT_FILE) => Some("NT_FILE"),
+        (b"CORE", NT_SIGINFO) => Some("NT_SIGINFO"),
+        (b"LINUX", NT_PRXFPREG) => Some("NT_PRXFPREG"),
+        (b"LINUX", NT_386_TLS) => Some("NT_386_TLS"),
+        (b"LINUX", NT_X86_XSTATE) => Some("NT_X86_XSTATE"),
+        (b"LINUX", NT_ARM_TLS) => Some("NT_ARM_TLS"),
+        (b"LINUX", NT_ARM_HW_BREAK) => Some("NT_ARM_HW_BREAK"),
+        (b"LINUX", NT_ARM_HW_WATCH) => Some("NT_ARM_HW_WATCH"),
+        (b"LINUX", NT_ARM_SYSTEM_CALL) => Some("NT_ARM_SYSTEM_CALL"),
+        (b"LINUX", NT_ARM_SVE) => Some("NT_ARM_SVE"),
+        (b"LINUX", NT_ARM_PAC_MASK) => Some("NT_ARM_PAC_MASK"),
+        (b"LINUX", NT_ARM_TAGGED_ADDR_CTRL) => Some("NT_ARM_TAGGED_ADDR_CTRL"),
+        _ => None,
+    };
+
+    match known {
+        Some(known) => format!("{} ({:#x})", known, ntype),
+        None => format!("{:#x}", ntype),
+    }
+}
+
+pub fn abi_tag_os_to_string(os: u32) -> String {
+    match os {
+        GNU_ABI_TAG_LINUX => String::from("Linux"),
+        GNU_ABI_TAG_HURD => String::from("Hurd"),
+        GNU_ABI_TAG_SOLARIS => String::from("Solaris"),
+        GNU_ABI_TAG_FREEBSD => String::from("FreeBSD"),
+        GNU_ABI_TAG_NETBSD => String::from("NetBSD"),
+        GNU_ABI_TAG_SYLLABLE => String::from("Syllable"),
+        GNU_ABI_TAG_NACL => String::from("NaCl"),
+        x => format!("Unknown: {}", x),
+    }
+}
+
+// AArch64 and x86 properties share the processor-specific range
+pub fn gnu_property_to_string(machine: u16, ptype: u32) -> String {
+    match (machine, ptype) {
+        (_, GNU_PROPERTY_STACK_SIZE) => String::from("Stack size"),
+        (_, GNU_PROPERTY_NO_COPY_ON_PROTECTED) => String::from("No copy on protected"),
+        (_, GNU_PROPERTY_1_NEEDED) => String::from("Needed"),
+        (EM_AARCH64, GNU_PROPERTY_AARCH64_FEATURE_1_AND) => String::from("AArch64 feature"),
+        (EM_386, x) | (EM_X86_64, x) => match x {
+            GNU_PROPERTY_X86_FEATURE_1_AND => String::from("x86 feature"),
+            GNU_PROPERTY_X86_FEATURE_2_NEEDED => String::from("x86 feature needed"),
+            GNU_PROPERTY_X86_FEATURE_2_USED => String::from("x86 feature used"),
+            GNU_PROPERTY_X86_ISA_1_NEEDED => String::from("x86 ISA needed"),
+            GNU_PROPERTY_X86_ISA_1_USED => String::from("x86 ISA used"),
+            x => format!("Property {:#x}", x),
+        },
+        (_, x) => format!("Property {:#x}", x),
+    }
+}
+
+pub fn gnu_property_1_needed_to_string(flags: u32) -> String {
+    flags_to_string(
+        flags as u64,
+        &[(
+            GNU_PROPERTY_1_NEEDED_INDIRECT_EXTERN_ACCESS as u64,
+            "indirect external access",
+        )],
+    )
+}
+
+pub fn x86_feature_1_to_string(flags: u32) -> String {
+    flags_to_string(
+        flags as u64,
+        &[
+            (GNU_PROPERTY_X86_FEATURE_1_IBT as u64, "IBT"),
+            (GNU_PROPERTY_X86_FEATURE_1_SHSTK as u64, "SHSTK"),
+            (GNU_PROPERTY_X86_FEATURE_1_LAM_U48 as u64, "LAM_U48"),
+            (GNU_PROPERTY_X86_FEATURE_1_LAM_U57 as u64, "LAM_U57"),
+        ],
+    )
+}
+
+pub fn x86_feature_2_to_string(flags: u32) -> String {
+    flags_to_string(
+        flags as u64,
+        &[
+            (GNU_PROPERTY_X86_FEATURE_2_X86 as u64, "x86"),
+            (GNU_PROPERTY_X86_FEATURE_2_X87 as u64, "x87"),
+            (GNU_PROPERTY_X86_FEATURE_2_MMX as u64, "MMX"),
+            (GNU_PROPERTY_X86_FEATURE_2_XMM as u64, "XMM"),
+            (GNU_PROPERTY_X86_FEATURE_2_YMM as u64, "YMM"),
+            (GNU_PROPERTY_X86_FEATURE_2_ZMM as u64, "ZMM"),
+            (GNU_PROPERTY_X86_FEATURE_2_FXSR as u64, "FXSR"),
+            (GNU_PROPERTY_X86_FEATURE_2_XSAVE as u64, "XSAVE"),
+            (GNU_PROPERTY_X86_FEATURE_2_XSAVEOPT as u64, "XSAVEOPT"),
+            (GNU_PROPERTY_X86_FEATURE_2_XSAVEC as u64, "XSAVEC"),
+            (GNU_PROPERTY_X86_FEATURE_2_TMM as u64, "TMM"),
+            (GNU_PROPERTY_X86_FEATURE_2_MASK as u64, "MASK"),
+        ],
+    )
+}
+
+pub fn x86_isa_1_to_string(flags: u32) -> String {
+    flags_to_string(
+        flags as u64,
+        &[
+            (GNU_PROPERTY_X86_ISA_1_BASELINE as u64, "x86-64-baseline"),
+            (GNU_PROPERTY_X86_ISA_1_V2 as u64, "x86-64-v2"),
+            (GNU_PROPERTY_X86_ISA_1_V3 as u64, "x86-64-v3"),
+            (GNU_PROPERTY_X86_ISA_1_V4 as u64, "x86-64-v4"),
+        ],
+    )
+}
+
+pub fn aarch64_feature_1_to_string(flags: u32) -> String {
+    flags_to_string(
+        flags as u64,
+        &[
+            (GNU_PROPERTY_AARCH64_FEATURE_1_BTI as u64, "BTI"),
+            (GNU_PROPERTY_AARCH64_FEATURE_1_PAC as u64, "PAC"),
+            (GNU_PROPERTY_AARCH64_FEATURE_1_GCS as u64, "GCS"),
+        ],
+    )
+}
+
+pub fn auxv_type_to_string(atype: u64) -> String {
+    match atype {
+        AT_NULL => String::from("AT_NULL"),
+        AT_IGNORE => String::from("AT_IGNORE"),
+        AT_EXECFD => String::from("AT_EXECFD"),
+        AT_PHDR => String::from("AT_PHDR"),
+        AT_PHENT => String::from("AT_PHENT"),
+        AT_PHNUM => String::from("AT_PHNUM"),
+        AT_PAGESZ => String::from("AT_PAGESZ"),
+        AT_BASE => String::from("AT_BASE"),
+        AT_FLAGS => String::from("AT_FLAGS"),
+        AT_ENTRY => String::from("AT_ENTRY"),
+        AT_NOTELF => String::from("AT_NOTELF"),
+        AT_UID => String::from("AT_UID"),
+        AT_EUID => String::from("AT_EUID"),
+        AT_GID => String::from("AT_GID"),
+        AT_EGID => String::from("AT_EGID"),
+        AT_PLATFORM => String::from("AT_PLATFORM"),
+        AT_HWCAP => String::from("AT_HWCAP"),
+        AT_CLKTCK => String::from("AT_CLKTCK"),
+        AT_SECURE => String::from("AT_SECURE"),
+        AT_BASE_PLATFORM => String::from("AT_BASE_PLATFORM"),
+        AT_RANDOM => String::from("AT_RANDOM"),
+        AT_HWCAP2 => String::from("AT_HWCAP2"),
+        AT_RSEQ_FEATURE_SIZE => String::from("AT_RSEQ_FEATURE_SIZE"),
+        AT_RSEQ_ALIGN => String::from("AT_RSEQ_ALIGN"),
+        AT_HWCAP3 => String::from("AT_HWCAP3"),
+        AT_HWCAP4 => String::from("AT_HWCAP4"),
+        AT_EXECFN => String::from("AT_EXECFN"),
+        AT_SYSINFO => String::from("AT_SYSINFO"),
+        AT_SYSINFO_EHDR => String::from("AT_SYSINFO_EHDR"),
+        AT_MINSIGSTKSZ => String::from("AT_MINSIGSTKSZ"),
+        x => format!("Unknown: {}", x),
+    }
+}
+
+// numbers shared by x86 and the generic (AArch64, RISC-V) Linux ABIs
+pub fn signal_to_string(signal: u32) -> String {
+    let name = match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        7 => "SIGBUS",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        10 => "SIGUSR1",
+        11 => "SIGSEGV",
+        12 => "SIGUSR2",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        16 => "SIGSTKFLT",
+        17 => "SIGCHLD",
+        18 => "SIGCONT",
+        19 => "SIGSTOP",
+        20 => "SIGTSTP",
+        21 => "SIGTTIN",
+        22 => "SIGTTOU",
+        23 => "SIGURG",
+        24 => "SIGXCPU",
+        25 => "SIGXFSZ",
+        26 => "SIGVTALRM",
+        27 => "SIGPROF",
+        28 => "SIGWINCH",
+        29 => "SIGIO",
+        30 => "SIGPWR",
+        31 => "SIGSYS",
+        x => return format!("{}", x),
+    };
+
+    format!("{} ({})", name, signal)
+}
+
+pub const X86_64_REGS: [&str; 27] = [
+    "r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8", "rax", "rcx", "rdx", "rsi",
+    "rdi", "orig_rax", "rip", "cs", "eflags", "rsp", "ss", "fs_base", "gs_base", "ds", "es", "fs",
+    "gs",
+];
+
+pub const AARCH64_REGS: [&str; 34] = [
+    "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13", "x14",
+    "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26", "x27",
+    "x28", "x29", "x30", "sp", "pc", "pstate",
+];
+
+pub fn verflags_to_string(flags: u16) -> String {
+    flags_to_string(
+        flags as u64,
+        &[
+            (VER_FLG_BASE as u64, "BASE"),
+            (VER_FLG_WEAK as u64, "WEAK"),
+            (VER_FLG_INFO as u64, "INFO"),
+        ],
+    )
+}
+
+pub fn versym_to_string(versym: u16) -> String {
+    match versym & VERSYM_VERSION {
+        VER_NDX_LOCAL => String::from("LOCAL"),
+        VER_NDX_GLOBAL => String::from("GLOBAL"),
+        x if versym & VERSYM_HIDDEN != 0 => format!("{} (hidden)", x),
+        x => format!("{}", x),
+    }
+}
+
+// DWARF
+pub const DW_UT_COMPILE: u8 = 0x01;
+pub const DW_UT_TYPE: u8 = 0x02;
+pub const DW_UT_PARTIAL: u8 = 0x03;
+pub const DW_UT_SKELETON: u8 = 0x04;
+pub const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+pub const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+pub const DW_AT_NAME: u64 = 0x03;
+pub const DW_AT_HIGH_PC: u64 = 0x12;
+pub const DW_AT_LANGUAGE: u64 = 0x13;
+pub const DW_AT_ENCODING: u64 = 0x3e;
+pub const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+pub const DW_AT_ADDR_BASE: u64 = 0x73;
+pub const DW_AT_GNU_ADDR_BASE: u64 = 0x2133;
+
+pub const DW_FORM_ADDR: u64 = 0x01;
+pub const DW_FORM_BLOCK2: u64 = 0x03;
+pub const DW_FORM_BLOCK4: u64 = 0x04;
+pub const DW_FORM_DATA2: u64 = 0x05;
+pub const DW_FORM_DATA4: u64 = 0x06;
+pub const DW_FORM_DATA8: u64 = 0x07;
+pub const DW_FORM_STRING: u64 = 0x08;
+pub const DW_FORM_BLOCK: u64 = 0x09;
+pub const DW_FORM_BLOCK1: u64 = 0x0a;
+pub const DW_FORM_DATA1: u64 = 0x0b;
+pub const DW_FORM_FLAG: u64 = 0x0c;
+pub const DW_FORM_SDATA: u64 = 0x0d;
+pub const DW_FORM_STRP: u64 = 0x0e;
+pub const DW_FORM_UDATA: u64 = 0x0f;
+pub const DW_FORM_REF_ADDR: u64 = 0x10;
+pub const DW_FORM_REF1: u64 = 0x11;
+pub const DW_FORM_REF2: u64 = 0x12;
+pub const DW_FORM_REF4: u64 = 0x13;
+pub const DW_FORM_REF8: u64 = 0x14;
+pub const DW_FORM_REF_UDATA: u64 = 0x15;
+pub const DW_FORM_INDIRECT: u64 = 0x16;
+pub const DW_FORM_SEC_OFFSET: u64 = 0x17;
+pub const DW_FORM_EXPRLOC: u64 = 0x18;
+pub const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+pub const DW_FORM_STRX: u64 = 0x1a;
+pub const DW_FORM_ADDRX: u64 = 0x1b;
+pub const DW_FORM_REF_SUP4: u64 = 0x1c;
+pub const DW_FORM_STRP_SUP: u64 = 0x1d;
+pub const DW_FORM_DATA16: u64 = 0x1e;
+pub const DW_FORM_LINE_STRP: u64 = 0x1f;
+pub const DW_FORM_REF_SIG8: u64 = 0x20;
+pub const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+pub const DW_FORM_LOCLISTX: u64 = 0x22;
+pub const DW_FORM_RNGLISTX: u64 = 0x23;
+pub const DW_FORM_REF_SUP8: u64 = 0x24;
+pub const DW_FORM_STRX1: u64 = 0x25;
+pub const DW_FORM_STRX2: u64 = 0x26;
+pub const DW_FORM_STRX3: u64 = 0x27;
+pub const DW_FORM_STRX4: u64 = 0x28;
+pub const DW_FORM_ADDRX1: u64 = 0x29;
+pub const DW_FORM_ADDRX2: u64 = 0x2a;
+pub const DW_FORM_ADDRX3: u64 = 0x2b;
+pub const DW_FORM_ADDRX4: u64 = 0x2c;
+pub const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1f01;
+pub const DW_FORM_GNU_STR_INDEX: u64 = 0x1f02;
+pub const DW_FORM_GNU_REF_ALT: u64 = 0x1f20;
+pub const DW_FORM_GNU_STRP_ALT: u64 = 0x1f21;
+
+pub const DW_LNS_COPY: u8 = 0x01;
+pub const DW_LNS_ADVANCE_PC: u8 = 0x02;
+pub const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+pub const DW_LNS_SET_FILE: u8 = 0x04;
+pub const DW_LNS_SET_COLUMN: u8 = 0x05;
+pub const DW_LNS_NEGATE_STMT: u8 = 0x06;
+pub const DW_LNS_SET_BASIC_BLOCK: u8 = 0x07;
+pub const DW_LNS_CONST_ADD_PC: u8 = 0x08;
+pub const DW_LNS_FIXED_ADVANCE_PC: u8 = 0x09;
+pub const DW_LNS_SET_PROLOGUE_END: u8 = 0x0a;
+pub const DW_LNS_SET_EPILOGUE_BEGIN: u8 = 0x0b;
+pub const DW_LNS_SET_ISA: u8 = 0x0c;
+
+pub const DW_LNE_END_SEQUENCE: u8 = 0x01;
+pub const DW_LNE_SET_ADDRESS: u8 = 0x02;
+pub const DW_LNE_DEFINE_FILE: u8 = 0x03;
+pub const DW_LNE_SET_DISCRIMINATOR: u8 = 0x04;
+
+pub const DW_LNCT_PATH: u64 = 0x01;
+pub const DW_LNCT_DIRECTORY_INDEX: u64 = 0x02;
+
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0a;
+pub const DW_EH_PE_SDATA4: u8 = 0x0b;
+pub const DW_EH_PE_SDATA8: u8 = 0x0c;
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_TEXTREL: u8 = 0x20;
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+pub const DW_EH_PE_FUNCREL: u8 = 0x40;
+pub const DW_EH_PE_ALIGNED: u8 = 0x50;
+pub const DW_EH_PE_INDIRECT: u8 = 0x80;
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+
+pub const DW_CFA_ADVANCE_LOC: u8 = 0x40;
+pub const DW_CFA_OFFSET: u8 = 0x80;
+pub const DW_CFA_RESTORE: u8 = 0xc0;
+pub const DW_CFA_NOP: u8 = 0x00;
+pub const DW_CFA_SET_LOC: u8 = 0x01;
+pub const DW_CFA_ADVANCE_LOC1: u8 = 0x02;
+pub const DW_CFA_ADVANCE_LOC2: u8 = 0x03;
+pub const DW_CFA_ADVANCE_LOC4: u8 = 0x04;
+pub const DW_CFA_OFFSET_EXTENDED: u8 = 0x05;
+pub const DW_CFA_RESTORE_EXTENDED: u8 = 0x06;
+pub const DW_CFA_UNDEFINED: u8 = 0x07;
+pub const DW_CFA_SAME_VALUE: u8 = 0x08;
+pub const DW_CFA_REGISTER: u8 = 0x09;
+pub const DW_CFA_REMEMBER_STATE: u8 = 0x0a;
+pub const DW_CFA_RESTORE_STATE: u8 = 0x0b;
+pub const DW_CFA_DEF_CFA: u8 = 0x0c;
+pub const DW_CFA_DEF_CFA_REGISTER: u8 = 0x0d;
+pub const DW_CFA_DEF_CFA_OFFSET: u8 = 0x0e;
+pub const DW_CFA_DEF_CFA_EXPRESSION: u8 = 0x0f;
+pub const DW_CFA_EXPRESSION: u8 = 0x10;
+pub const DW_CFA_OFFSET_EXTENDED_SF: u8 = 0x11;
+pub const DW_CFA_DEF_CFA_SF: u8 = 0x12;
+pub const DW_CFA_DEF_CFA_OFFSET_SF: u8 = 0x13;
+pub const DW_CFA_VAL_OFFSET: u8 = 0x14;
+pub const DW_CFA_VAL_OFFSET_SF: u8 = 0x15;
+pub const DW_CFA_VAL_EXPRESSION: u8 = 0x16;
+pub const DW_CFA_GNU_WINDOW_SAVE: u8 = 0x2d;
+pub const DW_CFA_GNU_ARGS_SIZE: u8 = 0x2e;
+pub const DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED: u8 = 0x2f;
+
+pub fn dwarf_ut_to_string(unit_type: u8) -> String {
+    match unit_type {
+        DW_UT_COMPILE => String::from("DW_UT_compile"),
+        DW_UT_TYPE => String::from("DW_UT_type"),
+        DW_UT_PARTIAL => String::from("DW_UT_partial"),
+        DW_UT_SKELETON => String::from("DW_UT_skeleton"),
+        DW_UT_SPLIT_COMPILE => String::from("DW_UT_split_compile"),
+        DW_UT_SPLIT_TYPE => String::from("DW_UT_split_type"),
+        x => format!("DW_UT_{:#x}", x),
+    }
+}
+
+pub fn dwarf_tag_to_string(tag: u64) -> String {
+    let name = match tag {
+        0x01 => "array_type",
+        0x02 => "class_type",
+        0x03 => "entry_point",
+        0x04 => "enumeration_type",
+        0x05 => "formal_parameter",
+        0x08 => "imported_declaration",
+        0x0a => "label",
+        0x0b => "lexical_block",
+        0x0d => "member",
+        0x0f => "pointer_type",
+        0x10 => "reference_type",
+        0x11 => "compile_unit",
+        0x12 => "string_type",
+        0x13 => "structure_type",
+        0x15 => "subroutine_type",
+        0x16 => "typedef",
+        0x17 => "union_type",
+        0x18 => "unspecified_parameters",
+        0x19 => "variant",
+        0x1a => "common_block",
+        0x1b => "common_inclusion",
+        0x1c => "inheritance",
+        0x1d => "inlined_subroutine",
+        0x1e => "module",
+        0x1f => "ptr_to_member_type",
+        0x20 => "set_type",
+        0x21 => "subrange_type",
+        0x22 => "with_stmt",
+        0x23 => "access_declaration",
+        0x24 => "base_type",
+        0x25 => "catch_block",
+        0x26 => "const_type",
+        0x27 => "constant",
+        0x28 => "enumerator",
+        0x29 => "file_type",
+        0x2a => "friend",
+        0x2b => "namelist",
+        0x2c => "namelist_item",
+        0x2d => "packed_type",
+        0x2e => "subprogram",
+        0x2f => "template_type_parameter",
+        0x30 => "template_value_parameter",
+        0x31 => "thrown_type",
+        0x32 => "try_block",
+        0x33 => "variant_part",
+        0x34 => "variable",
+        0x35 => "volatile_type",
+        0x36 => "dwarf_procedure",
+        0x37 => "restrict_type",
+        0x38 => "interface_type",
+        0x39 => "namespace",
+        0x3a => "imported_module",
+        0x3b => "unspecified_type",
+        0x3c => "partial_unit",
+        0x3d => "imported_unit",
+        0x3f => "condition",
+        0x40 => "shared_type",
+        0x41 => "type_unit",
+        0x42 => "rvalue_reference_type",
+        0x43 => "template_alias",
+        0x44 => "coarray_type",
+        0x45 => "generic_subrange",
+        0x46 => "dynamic_type",
+        0x47 => "atomic_type",
+        0x48 => "call_site",
+        0x49 => "call_site_parameter",
+        0x4a => "skeleton_unit",
+        0x4b => "immutable_type",
+        0x4106 => "GNU_template_template_param",
+        0x4107 => "GNU_template_parameter_pack",
+        0x4108 => "GNU_formal_parameter_pack",
+        0x4109 => "GNU_call_site",
+        0x410a => "GNU_call_site_parameter",
+        x => return format!("DW_TAG_{:#x}", x),
+    };
+
+    format!("DW_TAG_{}", name)
+}
+
+pub fn dwarf_at_to_string(at: u64) -> String {
+    let name = match at {
+        0x01 => "sibling",
+        0x02 => "location",
+        0x03 => "name",
+        0x09 => "ordering",
+        0x0b => "byte_size",
+        0x0c => "bit_offset",
+        0x0d => "bit_size",
+        0x10 => "stmt_list",
+        0x11 => "low_pc",
+        0x12 => "high_pc",
+        0x13 => "language",
+        0x15 => "discr",
+        0x16 => "discr_value",
+        0x17 => "visibility",
+        0x18 => "import",
+        0x19 => "string_length",
+        0x1a => "common_reference",
+        0x1b => "comp_dir",
+        0x1c => "const_value",
+        0x1d => "containing_type",
+        0x1e => "default_value",
+        0x20 => "inline",
+        0x21 => "is_optional",
+        0x22 => "lower_bound",
+        0x25 => "producer",
+        0x27 => "prototyped",
+        0x2a => "return_addr",
+        0x2c => "start_scope",
+        0x2e => "bit_stride",
+        0x2f => "upper_bound",
+        0x31 => "abstract_origin",
+        0x32 => "accessibility",
+        0x33 => "address_class",
+        0x34 => "artificial",
+        0x35 => "base_types",
+        0x36 => "calling_convention",
+        0x37 => "count",
+        0x38 => "data_member_location",
+        0x39 => "decl_column",
+        0x3a => "decl_file",
+        0x3b => "decl_line",
+        0x3c => "declaration",
+        0x3d => "discr_list",
+        0x3e => "encoding",
+        0x3f => "external",
+        0x40 => "frame_base",
+        0x41 => "friend",
+        0x42 => "identifier_case",
+        0x43 => "macro_info",
+        0x44 => "namelist_item",
+        0x45 => "priority",
+        0x46 => "segment",
+        0x47 => "specification",
+        0x48 => "static_link",
+        0x49 => "type",
+        0x4a => "use_location",
+        0x4b => "variable_parameter",
+        0x4c => "virtuality",
+        0x4d => "vtable_elem_location",
+        0x4e => "allocated",
+        0x4f => "associated",
+        0x50 => "data_location",
+        0x51 => "byte_stride",
+        0x52 => "entry_pc",
+        0x53 => "use_UTF8",
+        0x54 => "extension",
+        0x55 => "ranges",
+        0x56 => "trampoline",
+        0x57 => "call_column",
+        0x58 => "call_file",
+        0x59 => "call_line",
+        0x5a => "description",
+        0x5b => "binary_scale",
+        0x5c => "decimal_scale",
+        0x5d => "small",
+        0x5e => "decimal_sign",
+        0x5f => "digit_count",
+        0x60 => "picture_string",
+        0x61 => "mutable",
+        0x62 => "threads_scaled",
+        0x63 => "explicit",
+        0x64 => "object_pointer",
+        0x65 => "endianity",
+        0x66 => "elemental",
+        0x67 => "pure",
+        0x68 => "recursive",
+        0x69 => "signature",
+        0x6a => "main_subprogram",
+        0x6b => "data_bit_offset",
+        0x6c => "const_expr",
+        0x6d => "enum_class",
+        0x6e => "linkage_name",
+        0x6f => "string_length_bit_size",
+        0x70 => "string_length_byte_size",
+        0x71 => "rank",
+        0x72 => "str_offsets_base",
+        0x73 => "addr_base",
+        0x74 => "rnglists_base",
+        0x76 => "dwo_name",
+        0x77 => "reference",
+        0x78 => "rvalue_reference",
+        0x79 => "macros",
+        0x7a => "call_all_calls",
+        0x7b => "call_all_source_calls",
+        0x7c => "call_all_tail_calls",
+        0x7d => "call_return_pc",
+        0x7e => "call_value",
+        0x7f => "call_origin",
+        0x80 => "call_parameter",
+        0x81 => "call_pc",
+        0x82 => "call_tail_call",
+        0x83 => "call_target",
+        0x84 => "call_target_clobbered",
+        0x85 => "call_data_location",
+        0x86 => "call_data_value",
+        0x87 => "noreturn",
+        0x88 => "alignment",
+        0x89 => "export_symbols",
+        0x8a => "deleted",
+        0x8b => "defaulted",
+        0x8c => "loclists_base",
+        0x2007 => "MIPS_linkage_name",
+        0x2107 => "GNU_vector",
+        0x2111 => "GNU_call_site_value",
+        0x2113 => "GNU_call_site_target",
+        0x2115 => "GNU_tail_call",
+        0x2116 => "GNU_all_tail_call_sites",
+        0x2117 => "GNU_all_call_sites",
+        0x2119 => "GNU_macros",
+        0x211a => "GNU_deleted",
+        0x2130 => "GNU_dwo_name",
+        0x2131 => "GNU_dwo_id",
+        0x2132 => "GNU_ranges_base",
+        0x2133 => "GNU_addr_base",
+        0x2134 => "GNU_pubnames",
+        0x2136 => "GNU_discriminator",
+        0x2137 => "GNU_locviews",
+        0x2138 => "GNU_entry_view",
+        x => return format!("DW_AT_{:#x}", x),
+    };
+
+    format!("DW_AT_{}", name)
+}
+
+pub fn dwarf_form_to_string(form: u64) -> String {
+    let name = match form {
+        DW_FORM_ADDR => "addr",
+        DW_FORM_BLOCK2 => "block2",
+        DW_FORM_BLOCK4 => "block4",
+        DW_FORM_DATA2 => "data2",
+        DW_FORM_DATA4 => "data4",
+        DW_FORM_DATA8 => "data8",
+        DW_FORM_STRING => "string",
+        DW_FORM_BLOCK => "block",
+        DW_FORM_BLOCK1 => "block1",
+        DW_FORM_DATA1 => "data1",
+        DW_FORM_FLAG => "flag",
+        DW_FORM_SDATA => "sdata",
+        DW_FORM_STRP => "strp",
+        DW_FORM_UDATA => "udata",
+        DW_FORM_REF_ADDR => "ref_addr",
+        DW_FORM_REF1 => "ref1",
+        DW_FORM_REF2 => "ref2",
+        DW_FORM_REF4 => "ref4",
+        DW_FORM_REF8 => "ref8",
+        DW_FORM_REF_UDATA => "ref_udata",
+        DW_FORM_INDIRECT => "indirect",
+        DW_FORM_SEC_OFFSET => "sec_offset",
+        DW_FORM_EXPRLOC => "exprloc",
+        DW_FORM_FLAG_PRESENT => "flag_present",
+        DW_FORM_STRX => "strx",
+        DW_FORM_ADDRX => "addrx",
+        DW_FORM_REF_SUP4 => "ref_sup4",
+        DW_FORM_STRP_SUP => "strp_sup",
+        DW_FORM_DATA16 => "data16",
+        DW_FORM_LINE_STRP => "line_strp",
+        DW_FORM_REF_SIG8 => "ref_sig8",
+        DW_FORM_IMPLICIT_CONST => "implicit_const",
+        DW_FORM_LOCLISTX => "loclistx",
+        DW_FORM_RNGLISTX => "rnglistx",
+        DW_FORM_REF_SUP8 => "ref_sup8",
+        DW_FORM_STRX1 => "strx1",
+        DW_FORM_STRX2 => "strx2",
+        DW_FORM_STRX3 => "strx3",
+        DW_FORM_STRX4 => "strx4",
+        DW_FORM_ADDRX1 => "addrx1",
+        DW_FORM_ADDRX2 => "addrx2",
+        DW_FORM_ADDRX3 => "addrx3",
+        DW_FORM_ADDRX4 => "addrx4",
+        DW_FORM_GNU_ADDR_INDEX => "GNU_addr_index",
+        DW_FORM_GNU_STR_INDEX => "GNU_str_index",
+        DW_FORM_GNU_REF_ALT => "GNU_ref_alt",
+        DW_FORM_GNU_STRP_ALT => "GNU_strp_alt",
+        x => return format!("DW_FORM_{:#x}", x),
+    };
+
+    format!("DW_FORM_{}", name)
+}
+
+pub fn dwarf_lang_to_string(lang: u64) -> String {
+    let name = match lang {
+        0x01 => "C89",
+        0x02 => "C",
+        0x03 => "Ada83",
+        0x04 => "C_plus_plus",
+        0x05 => "Cobol74",
+        0x06 => "Cobol85",
+        0x07 => "Fortran77",
+        0x08 => "Fortran90",
+        0x09 => "Pascal83",
+        0x0a => "Modula2",
+        0x0b => "Java",
+        0x0c => "C99",
+        0x0d => "Ada95",
+        0x0e => "Fortran95",
+        0x0f => "PLI",
+        0x10 => "ObjC",
+        0x11 => "ObjC_plus_plus",
+        0x12 => "UPC",
+        0x13 => "D",
+        0x14 => "Python",
+        0x15 => "OpenCL",
+        0x16 => "Go",
+        0x17 => "Modula3",
+        0x18 => "Haskell",
+        0x19 => "C_plus_plus_03",
+        0x1a => "C_plus_plus_11",
+        0x1b => "OCaml",
+        0x1c => "Rust",
+        0x1d => "C11",
+        0x1e => "Swift",
+        0x1f => "Julia",
+        0x20 => "Dylan",
+        0x21 => "C_plus_plus_14",
+        0x22 => "Fortran03",
+        0x23 => "Fortran08",
+        0x24 => "RenderScript",
+        0x25 => "BLISS",
+        0x8001 => "Mips_Assembler",
+        x => return format!("{:#x}", x),
+    };
+
+    format!("DW_LANG_{}", name)
+}
+
+pub fn dwarf_ate_to_string(encoding: u64) -> String {
+    let name = match encoding {
+        0x01 => "address",
+        0x02 => "boolean",
+        0x03 => "complex_float",
+        0x04 => "float",
+        0x05 => "signed",
+        0x06 => "signed_char",
+        0x07 => "unsigned",
+        0x08 => "unsigned_char",
+        0x09 => "imaginary_float",
+        0x0a => "packed_decimal",
+        0x0b => "numeric_string",
+        0x0c => "edited",
+        0x0d => "signed_fixed",
+        0x0e => "unsigned_fixed",
+        0x0f => "decimal_float",
+        0x10 => "UTF",
+        0x11 => "UCS",
+        0x12 => "ASCII",
+        x => return format!("{:#x}", x),
+    };
+
+    format!("DW_ATE_{}", name)
+}
+
+pub fn dwarf_lns_to_string(opcode: u8) -> String {
+    let name = match opcode {
+        DW_LNS_COPY => "copy",
+        DW_LNS_ADVANCE_PC => "advance_pc",
+        DW_LNS_ADVANCE_LINE => "advance_line",
+        DW_LNS_SET_FILE => "set_file",
+        DW_LNS_SET_COLUMN => "set_column",
+        DW_LNS_NEGATE_STMT => "negate_stmt",
+        DW_LNS_SET_BASIC_BLOCK => "set_basic_block",
+        DW_LNS_CONST_ADD_PC => "const_add_pc",
+        DW_LNS_FIXED_ADVANCE_PC => "fixed_advance_pc",
+        DW_LNS_SET_PROLOGUE_END => "set_prologue_end",
+        DW_LNS_SET_EPILOGUE_BEGIN => "set_epilogue_begin",
+        DW_LNS_SET_ISA => "set_isa",
+        x => return format!("DW_LNS_{:#x}", x),
+    };
+
+    format!("DW_LNS_{}", name)
+}
+
+pub fn dwarf_lne_to_string(opcode: u8) -> String {
+    let name = match opcode {
+        DW_LNE_END_SEQUENCE => "end_sequence",
+        DW_LNE_SET_ADDRESS => "set_address",
+        DW_LNE_DEFINE_FILE => "define_file",
+        DW_LNE_SET_DISCRIMINATOR => "set_discriminator",
+        0x80 => "lo_user",
+        0xff => "hi_user",
+        x => return format!("DW_LNE_{:#x}", x),
+    };
+
+    format!("DW_LNE_{}", name)
+}
+
+// e.g. "pcrel sdata4"
+pub fn eh_pe_to_string(encoding: u8) -> String {
+    if encoding == DW_EH_PE_OMIT {
+        return String::from("omit");
+    }
+
+    let format = match encoding & 0x0f {
+        DW_EH_PE_ABSPTR => String::from("absptr"),
+        DW_EH_PE_ULEB128 => String::from("uleb128"),
+        DW_EH_PE_UDATA2 => String::from("udata2"),
+        DW_EH_PE_UDATA4 => String::from("udata4"),
+        DW_EH_PE_UDATA8 => String::from("udata8"),
+        DW_EH_PE_SLEB128 => String::from("sleb128"),
+        DW_EH_PE_SDATA2 => String::from("sdata2"),
+        DW_EH_PE_SDATA4 => String::from("sdata4"),
+        DW_EH_PE_SDATA8 => String::from("sdata8"),
+        x => format!("{:#x}", x),
+    };
+    let application = match encoding & 0x70 {
+        0 => None,
+        DW_EH_PE_PCREL => Some(String::from("pcrel")),
+        DW_EH_PE_TEXTREL => Some(String::from("textrel")),
+        DW_EH_PE_DATAREL => Some(String::from("datarel")),
+        DW_EH_PE_FUNCREL => Some(String::from("funcrel")),
+        DW_EH_PE_ALIGNED => Some(String::from("aligned")),
+        x => Some(format!("{:#x}", x)),
+    };
+    let indirect = if encoding & DW_EH_PE_INDIRECT != 0 {
+        "indirect "
+    } else {
+        ""
+    };
+
+    match application {
+        Some(application) => format!("{}{} {}", indirect, application, format),
+        None => format!("{}{}", indirect, format),
+    }
+}
+
+pub fn dwarf_cfa_to_string(opcode: u8) -> String {
+    let name = match opcode {
+        DW_CFA_NOP => "nop",
+        DW_CFA_SET_LOC => "set_loc",
+        DW_CFA_ADVANCE_LOC1 => "advance_loc1",
+        DW_CFA_ADVANCE_LOC2 => "advance_loc2",
+        DW_CFA_ADVANCE_LOC4 => "advance_loc4",
+        DW_CFA_OFFSET_EXTENDED => "offset_extended",
+        DW_CFA_RESTORE_EXTENDED => "restore_extended",
+        DW_CFA_UNDEFINED => "undefined",
+        DW_CFA_SAME_VALUE => "same_value",
+        DW_CFA_REGISTER => "register",
+        DW_CFA_REMEMBER_STATE => "remember_state",
+        DW_CFA_RESTORE_STATE => "restore_state",
+        DW_CFA_DEF_CFA => "def_cfa",
+        DW_CFA_DEF_CFA_REGISTER => "def_cfa_register",
+        DW_CFA_DEF_CFA_OFFSET => "def_cfa_offset",
+        DW_CFA_DEF_CFA_EXPRESSION => "def_cfa_expression",
+        DW_CFA_EXPRESSION => "expression",
+        DW_CFA_OFFSET_EXTENDED_SF => "offset_extended_sf",
+        DW_CFA_DEF_CFA_SF => "def_cfa_sf",
+        DW_CFA_DEF_CFA_OFFSET_SF => "def_cfa_offset_sf",
+        DW_CFA_VAL_OFFSET => "val_offset",
+        DW_CFA_VAL_OFFSET_SF => "val_offset_sf",
+        DW_CFA_VAL_EXPRESSION => "val_expression",
+        DW_CFA_GNU_WINDOW_SAVE => "GNU_window_save",
+        DW_CFA_GNU_ARGS_SIZE => "GNU_args_size",
+        DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => "GNU_negative_offset_extended",
+        x => match x & 0xc0 {
+            DW_CFA_ADVANCE_LOC => "advance_loc",
+            DW_CFA_OFFSET => "offset",
+            DW_CFA_RESTORE => "restore",
+            _ => return format!("DW_CFA_{:#x}", x),
+        },
+    };
+
+    format!("DW_CFA_{}", name)
+}
+
+// DWARF register numbers of the psABIs, e.g. "r6 (rbp)"
+pub fn dwarf_register_to_string(machine: u16, register: u64) -> String {
+    const X86_64: [&str; 17] = [
+        "rax", "rdx", "rcx", "rbx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12",
+        "r13", "r14", "r15", "rip",
+    ];
+    const I386: [&str; 9] = [
+        "eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "eip",
+    ];
+
+    let name = match (machine, register) {
+        (EM_X86_64, r) if r < 17 => Some(String::from(X86_64[r as usize])),
+        (EM_386, r) if r < 9 => Some(String::from(I386[r as usize])),
+        (EM_AARCH64, 31) => Some(String::from("sp")),
+        (EM_AARCH64, r) if r < 31 => Some(format!("x{}", r)),
+        (EM_RISCV, r) if r < 32 => Some(format!("x{}", r)),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => format!("r{} ({})", register, name),
+        None => format!("r{}", register),
+    }
+}
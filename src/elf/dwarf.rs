@@ -0,0 +1,959 @@
+use super::defs::*;
+use super::error::{ElfError, Structure};
+use super::notes::Reader;
+use super::parser::{
+    Die, DieAttribute, DwarfUnit, ParsedElf, ParsedIdent, RangeType, SectionContents,
+};
+use std::collections::HashMap;
+
+// longer blocks are cut in the displayed value
+const BLOCK_BYTES: usize = 16;
+
+struct AttributeSpec {
+    name: u64,
+    form: u64,
+    // of DW_FORM_implicit_const, which has no bytes in the DIE
+    implicit: i64,
+}
+
+struct Abbrev {
+    tag: u64,
+    children: bool,
+    specs: Vec<AttributeSpec>,
+}
+
+pub(super) enum Value<'a> {
+    Unsigned(u64),
+    Signed(i64),
+    Address(u64),
+    SecOffset(u64),
+    Flag(bool),
+    Str(String),
+    Strx(u64),
+    Addrx(u64),
+    Ref(usize),
+    Block(&'a [u8]),
+    Other(String),
+}
+
+pub(super) struct UnitHeader {
+    pub(super) offset: usize,
+    pub(super) end: usize,
+    pub(super) format64: bool,
+    pub(super) version: u16,
+    pub(super) unit_type: u8,
+    pub(super) address_size: u8,
+    pub(super) abbrev_offset: u64,
+    pub(super) dies_start: usize,
+}
+
+impl UnitHeader {
+    pub(super) fn offset_size(&self) -> usize {
+        if self.format64 {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+// The section being decoded, .debug_info or .debug_line, and the sections
+// its values refer to. Values of relocatable objects are read with their
+// relocations applied, they're mostly 0 otherwise.
+pub(super) struct Context<'a> {
+    pub(super) reader: Reader,
+    pub(super) data: &'a [u8],
+    pub(super) strings: Option<&'a [u8]>,
+    pub(super) line_strings: Option<&'a [u8]>,
+    pub(super) str_offsets: Option<&'a [u8]>,
+    pub(super) addresses: Option<&'a [u8]>,
+    pub(super) relocations: HashMap<usize, u64>,
+}
+
+pub(super) fn cstr_at(section: Option<&[u8]>, offset: u64) -> Option<String> {
+    let rest = section?.get(offset as usize..)?;
+    let len = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+
+    Some(String::from_utf8_lossy(&rest[..len]).into_owned())
+}
+
+pub(super) fn uleb(buf: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = *buf.get(*pos)?;
+
+        *pos += 1;
+
+        if shift < 64 {
+            value |= ((byte & 0x7f) as u64) << shift;
+        }
+
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+}
+
+pub(super) fn sleb(buf: &[u8], pos: &mut usize) -> Option<i64> {
+    let mut value = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = *buf.get(*pos)?;
+
+        *pos += 1;
+
+        if shift < 64 {
+            value |= ((byte & 0x7f) as i64) << shift;
+        }
+
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1 << shift;
+            }
+
+            return Some(value);
+        }
+    }
+}
+
+// the abbreviation table at `offset`, by code
+fn parse_abbrevs(abbrev: &[u8], offset: u64) -> HashMap<u64, Abbrev> {
+    let mut abbrevs = HashMap::new();
+    let mut pos = offset as usize;
+
+    while let Some(code) = uleb(abbrev, &mut pos) {
+        if code == 0 {
+            break;
+        }
+
+        let (tag, children) = match (uleb(abbrev, &mut pos), abbrev.get(pos)) {
+            (Some(tag), Some(children)) => (tag, *children != 0),
+            _ => break,
+        };
+        let mut specs = vec![];
+
+        pos += 1;
+
+        while let (Some(name), Some(form)) = (uleb(abbrev, &mut pos), uleb(abbrev, &mut pos)) {
+            if name == 0 && form == 0 {
+                break;
+            }
+
+            let implicit = if form == DW_FORM_IMPLICIT_CONST {
+                sleb(abbrev, &mut pos).unwrap_or(0)
+            } else {
+                0
+            };
+
+            specs.push(AttributeSpec {
+                name,
+                form,
+                implicit,
+            });
+        }
+
+        abbrevs.insert(
+            code,
+            Abbrev {
+                tag,
+                children,
+                specs,
+            },
+        );
+    }
+
+    abbrevs
+}
+
+// every form from DW_FORM_addr to DW_FORM_addrx4 except the unused 0x02
+pub(super) fn is_known_form(form: u64) -> bool {
+    let standard = form == DW_FORM_ADDR || (DW_FORM_BLOCK2..=DW_FORM_ADDRX4).contains(&form);
+
+    standard
+        || matches!(
+            form,
+            DW_FORM_GNU_ADDR_INDEX
+                | DW_FORM_GNU_STR_INDEX
+                | DW_FORM_GNU_REF_ALT
+                | DW_FORM_GNU_STRP_ALT
+        )
+}
+
+pub(super) fn format_block(block: &[u8]) -> String {
+    let bytes: Vec<String> = block
+        .iter()
+        .take(BLOCK_BYTES)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let more = if block.len() > BLOCK_BYTES {
+        " ..."
+    } else {
+        ""
+    };
+
+    format!("{} bytes: {}{}", block.len(), bytes.join(" "), more)
+}
+
+impl<'a> Context<'a> {
+    pub(super) fn read(&self, pos: &mut usize, size: usize) -> Option<u64> {
+        let value = self.reader.uint(self.data, *pos, size)?;
+        let value = self.relocations.get(pos).copied().unwrap_or(value);
+
+        *pos += size;
+
+        Some(value)
+    }
+
+    fn bytes(&self, pos: &mut usize, size: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(*pos..pos.checked_add(size)?)?;
+
+        *pos += size;
+
+        Some(bytes)
+    }
+
+    fn string(&self, section: Option<&[u8]>, offset: u64) -> String {
+        cstr_at(section, offset).unwrap_or_else(|| format!("(bad string offset {:#x})", offset))
+    }
+
+    // None for unknown forms and values going past the section
+    pub(super) fn value(
+        &self,
+        pos: &mut usize,
+        form: u64,
+        implicit: i64,
+        header: &UnitHeader,
+    ) -> Option<Value<'a>> {
+        let offset_size = header.offset_size();
+        let address_size = header.address_size as usize;
+        let unit_ref = |offset: u64| Value::Ref(header.offset.saturating_add(offset as usize));
+
+        Some(match form {
+            DW_FORM_ADDR => Value::Address(self.read(pos, address_size)?),
+            DW_FORM_DATA1 => Value::Unsigned(self.read(pos, 1)?),
+            DW_FORM_DATA2 => Value::Unsigned(self.read(pos, 2)?),
+            DW_FORM_DATA4 => Value::Unsigned(self.read(pos, 4)?),
+            DW_FORM_DATA8 => Value::Unsigned(self.read(pos, 8)?),
+            DW_FORM_DATA16 => Value::Block(self.bytes(pos, 16)?),
+            DW_FORM_UDATA => Value::Unsigned(uleb(self.data, pos)?),
+            DW_FORM_SDATA => Value::Signed(sleb(self.data, pos)?),
+            DW_FORM_IMPLICIT_CONST => Value::Signed(implicit),
+            DW_FORM_FLAG => Value::Flag(self.read(pos, 1)? != 0),
+            DW_FORM_FLAG_PRESENT => Value::Flag(true),
+            DW_FORM_STRING => {
+                let start = *pos;
+                let len = self.data.get(start..)?.iter().position(|&b| b == 0)?;
+
+                *pos += len + 1;
+
+                Value::Str(String::from_utf8_lossy(&self.data[start..start + len]).into_owned())
+            }
+            DW_FORM_STRP => Value::Str(self.string(self.strings, self.read(pos, offset_size)?)),
+            DW_FORM_LINE_STRP => {
+                Value::Str(self.string(self.line_strings, self.read(pos, offset_size)?))
+            }
+            DW_FORM_STRP_SUP | DW_FORM_GNU_STRP_ALT => Value::Other(format!(
+                "(supplementary string {:#x})",
+                self.read(pos, offset_size)?
+            )),
+            DW_FORM_STRX | DW_FORM_GNU_STR_INDEX => Value::Strx(uleb(self.data, pos)?),
+            DW_FORM_STRX1 => Value::Strx(self.read(pos, 1)?),
+            DW_FORM_STRX2 => Value::Strx(self.read(pos, 2)?),
+            DW_FORM_STRX3 => Value::Strx(self.read(pos, 3)?),
+            DW_FORM_STRX4 => Value::Strx(self.read(pos, 4)?),
+            DW_FORM_ADDRX | DW_FORM_GNU_ADDR_INDEX => Value::Addrx(uleb(self.data, pos)?),
+            DW_FORM_ADDRX1 => Value::Addrx(self.read(pos, 1)?),
+            DW_FORM_ADDRX2 => Value::Addrx(self.read(pos, 2)?),
+            DW_FORM_ADDRX3 => Value::Addrx(self.read(pos, 3)?),
+            DW_FORM_ADDRX4 => Value::Addrx(self.read(pos, 4)?),
+            DW_FORM_REF1 => unit_ref(self.read(pos, 1)?),
+            DW_FORM_REF2 => unit_ref(self.read(pos, 2)?),
+            DW_FORM_REF4 => unit_ref(self.read(pos, 4)?),
+            DW_FORM_REF8 => unit_ref(self.read(pos, 8)?),
+            DW_FORM_REF_UDATA => unit_ref(uleb(self.data, pos)?),
+            // addresses and offsets had the same size before DWARF 3
+            DW_FORM_REF_ADDR if header.version == 2 => {
+                Value::Ref(self.read(pos, address_size)? as usize)
+            }
+            DW_FORM_REF_ADDR => Value::Ref(self.read(pos, offset_size)? as usize),
+            DW_FORM_REF_SIG8 => Value::Other(format!("signature {:#018x}", self.read(pos, 8)?)),
+            DW_FORM_REF_SUP4 => {
+                Value::Other(format!("(supplementary <{:#x}>)", self.read(pos, 4)?))
+            }
+            DW_FORM_REF_SUP8 => {
+                Value::Other(format!("(supplementary <{:#x}>)", self.read(pos, 8)?))
+            }
+            DW_FORM_GNU_REF_ALT => Value::Other(format!(
+                "(supplementary <{:#x}>)",
+                self.read(pos, offset_size)?
+            )),
+            DW_FORM_SEC_OFFSET => Value::SecOffset(self.read(pos, offset_size)?),
+            DW_FORM_LOCLISTX => Value::Other(format!("location list {}", uleb(self.data, pos)?)),
+            DW_FORM_RNGLISTX => Value::Other(format!("range list {}", uleb(self.data, pos)?)),
+            DW_FORM_BLOCK1 => {
+                let len = self.read(pos, 1)? as usize;
+
+                Value::Block(self.bytes(pos, len)?)
+            }
+            DW_FORM_BLOCK2 => {
+                let len = self.read(pos, 2)? as usize;
+
+                Value::Block(self.bytes(pos, len)?)
+            }
+            DW_FORM_BLOCK4 => {
+                let len = self.read(pos, 4)? as usize;
+
+                Value::Block(self.bytes(pos, len)?)
+            }
+            DW_FORM_BLOCK | DW_FORM_EXPRLOC => {
+                let len = uleb(self.data, pos)? as usize;
+
+                Value::Block(self.bytes(pos, len)?)
+            }
+            DW_FORM_INDIRECT => {
+                let form = uleb(self.data, pos)?;
+
+                // an indirect form can't be DW_FORM_indirect again
+                if form == DW_FORM_INDIRECT {
+                    return None;
+                }
+
+                return self.value(pos, form, implicit, header);
+            }
+            _ => return None,
+        })
+    }
+
+    // `bases` are DW_AT_str_offsets_base and DW_AT_addr_base of the unit
+    pub(super) fn render(
+        &self,
+        name: u64,
+        value: Value,
+        header: &UnitHeader,
+        bases: (Option<u64>, Option<u64>),
+    ) -> (String, Option<usize>) {
+        let offset_size = header.offset_size();
+        // without the attribute, the base is right after the section header
+        let str_offsets_base = bases.0.unwrap_or(2 * offset_size as u64);
+
+        let value = match value {
+            Value::Unsigned(value) => match name {
+                DW_AT_LANGUAGE => dwarf_lang_to_string(value),
+                DW_AT_ENCODING => dwarf_ate_to_string(value),
+                DW_AT_HIGH_PC => format!("low_pc + {:#x}", value),
+                _ => format!("{}", value),
+            },
+            Value::Signed(value) => format!("{}", value),
+            Value::Address(value) | Value::SecOffset(value) => format!("{:#x}", value),
+            Value::Flag(value) => format!("{}", value),
+            Value::Str(value) | Value::Other(value) => value,
+            Value::Strx(index) => {
+                let entry = index
+                    .checked_mul(offset_size as u64)
+                    .and_then(|entry| entry.checked_add(str_offsets_base));
+                let offset = self.str_offsets.zip(entry).and_then(|(section, entry)| {
+                    self.reader.uint(section, entry as usize, offset_size)
+                });
+
+                match offset.and_then(|offset| cstr_at(self.strings, offset)) {
+                    Some(string) => string,
+                    None => format!("(indexed string {})", index),
+                }
+            }
+            Value::Addrx(index) => {
+                let size = header.address_size as usize;
+                let entry = index
+                    .checked_mul(size as u64)
+                    .zip(bases.1)
+                    .and_then(|(entry, base)| entry.checked_add(base));
+                let address = self
+                    .addresses
+                    .zip(entry)
+                    .and_then(|(section, entry)| self.reader.uint(section, entry as usize, size));
+
+                match address {
+                    Some(address) => format!("{:#x}", address),
+                    None => format!("(indexed address {})", index),
+                }
+            }
+            Value::Ref(offset) => return (format!("<{:#x}>", offset), Some(offset)),
+            Value::Block(block) => format_block(block),
+        };
+
+        (value, None)
+    }
+}
+
+impl<'a> ParsedElf<'a> {
+    // whether section `idx` is the debug section `name`, .zdebug_info is a
+    // legacy compressed .debug_info
+    pub(super) fn is_debug_section(&self, idx: usize, name: &str) -> bool {
+        let section = self.shnstrtab.get(self.shdrs[idx].name);
+
+        section == name || section.strip_prefix(".z") == name.strip_prefix('.')
+    }
+
+    // contents of the first section named `name`
+    pub(super) fn section_by_name(&self, name: &str) -> Option<(usize, &'a [u8])> {
+        let idx = self
+            .shdrs
+            .iter()
+            .position(|shdr| self.shnstrtab.get(shdr.name) == name)?;
+
+        Some((idx, self.section_data(&self.shdrs[idx])?))
+    }
+
+    // decompressed contents of the first debug section named `name`
+    pub(super) fn debug_section(&self, name: &str) -> Option<SectionContents<'a>> {
+        let idx = (0..self.shdrs.len()).find(|&idx| self.is_debug_section(idx, name))?;
+
+        self.section_contents(idx)
+    }
+
+    // values to read instead of the ones in section `idx` of a relocatable
+    // object, by offset in the section
+    pub(super) fn debug_relocations(&self, idx: usize) -> HashMap<usize, u64> {
+        let mut relocated = HashMap::new();
+
+        for (section, relocations) in self.relocations.iter() {
+            let shdr = &self.shdrs[*section as usize];
+
+            if shdr.info != idx || shdr.shtype != SHT_RELA {
+                continue;
+            }
+
+            let symbols = self.symtabs.get(&(shdr.link as u16));
+
+            for rel in relocations.iter() {
+                let base = symbols
+                    .and_then(|symbols| symbols.get(rel.sym as usize))
+                    .map_or(0, |sym| sym.value as u64);
+
+                relocated.insert(
+                    rel.offset,
+                    base.wrapping_add(rel.addend.unwrap_or(0) as u64),
+                );
+            }
+        }
+
+        relocated
+    }
+
+    pub(super) fn parse_debug_info(&mut self, ident: &ParsedIdent) {
+        let abbrev = self.debug_section(".debug_abbrev");
+        let strings = self.debug_section(".debug_str");
+        let line_strings = self.debug_section(".debug_line_str");
+        let str_offsets = self.debug_section(".debug_str_offsets");
+        let addresses = self.debug_section(".debug_addr");
+        let sections: Vec<usize> = (0..self.shdrs.len())
+            .filter(|&idx| self.is_debug_section(idx, ".debug_info"))
+            .collect();
+        let mut abbrev_tables = HashMap::new();
+
+        for idx in sections {
+            let info = match self.section_contents(idx) {
+                Some(info) => info,
+                None => continue,
+            };
+            let context = Context {
+                reader: Reader::new(ident.endianness, ident.class),
+                data: &info,
+                strings: strings.as_deref(),
+                line_strings: line_strings.as_deref(),
+                str_offsets: str_offsets.as_deref(),
+                addresses: addresses.as_deref(),
+                relocations: self.debug_relocations(idx),
+            };
+
+            self.decode_contents(idx, |elf| {
+                let mut offset = 0;
+
+                while offset < info.len() {
+                    let header = match elf.parse_unit_header(idx, &context, offset) {
+                        Some(header) => header,
+                        None => break,
+                    };
+                    let abbrevs = abbrev_tables
+                        .entry(header.abbrev_offset)
+                        .or_insert_with(|| {
+                            parse_abbrevs(abbrev.as_deref().unwrap_or(&[]), header.abbrev_offset)
+                        });
+
+                    if (2..=5).contains(&header.version) {
+                        elf.parse_dies(idx, &context, &header, abbrevs);
+                    }
+
+                    offset = header.end;
+                }
+            });
+        }
+    }
+
+    // adds the ranges of the unit and its header, None if there's no header
+    fn parse_unit_header(
+        &mut self,
+        idx: usize,
+        context: &Context,
+        offset: usize,
+    ) -> Option<UnitHeader> {
+        let unit = self.dwarf_units.len() as u32;
+        let start = self.contents_offset(idx);
+        let available = context.data.len() - offset;
+        let truncated = |size| ElfError::Truncated {
+            offset: start + offset,
+            structure: Structure::DwarfUnit(unit),
+            size,
+            available,
+        };
+        let mut pos = offset;
+        let mut fields = vec![];
+        let mut field = |name, pos: &mut usize, size| {
+            let value = context.read(pos, size);
+
+            if value.is_some() {
+                fields.push((name, *pos - size, size));
+            }
+
+            value
+        };
+
+        let (format64, length) = match field("unit_length", &mut pos, 4) {
+            Some(0xffff_ffff) => (true, field("unit_len64", &mut pos, 8)),
+            length => (false, length),
+        };
+        // unit_length counts the bytes after itself
+        let length_end = pos;
+        let version = field("cu_version", &mut pos, 2);
+        let offset_size = if format64 { 8 } else { 4 };
+        let (length, version) = match (length, version) {
+            (Some(length), Some(version)) => (length as usize, version as u16),
+            _ => {
+                self.diagnose(truncated(pos - offset), Some((start + offset, available)));
+
+                return None;
+            }
+        };
+        let end = length_end.saturating_add(length);
+
+        let (unit_type, address_size, abbrev_offset) = if version >= 5 {
+            let unit_type = field("unit_type", &mut pos, 1);
+            let address_size = field("address_size", &mut pos, 1);
+
+            (
+                unit_type,
+                address_size,
+                field("abbrev_off", &mut pos, offset_size),
+            )
+        } else {
+            let abbrev_offset = field("abbrev_off", &mut pos, offset_size);
+
+            (
+                Some(DW_UT_COMPILE as u64),
+                field("address_size", &mut pos, 1),
+                abbrev_offset,
+            )
+        };
+        let unit_type = unit_type.unwrap_or(0) as u8;
+
+        if version >= 5 {
+            match unit_type {
+                DW_UT_TYPE | DW_UT_SPLIT_TYPE => {
+                    field("type_sig", &mut pos, 8);
+                    field("type_offset", &mut pos, offset_size);
+                }
+                DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => {
+                    field("dwo_id", &mut pos, 8);
+                }
+                _ => {}
+            }
+        }
+
+        let size = end.min(context.data.len()) - offset;
+
+        if end > context.data.len() || pos > end {
+            let needed = (end - offset).max(pos - offset);
+
+            self.diagnose(truncated(needed), Some((start + offset, size)));
+        }
+
+        self.ranges
+            .add_range(start + offset, size, RangeType::DwarfUnit(unit));
+
+        for (name, field_offset, field_size) in fields.into_iter() {
+            if field_offset + field_size <= offset + size {
+                self.ranges.add_range(
+                    start + field_offset,
+                    field_size,
+                    RangeType::DwarfField(name),
+                );
+            }
+        }
+
+        if !(2..=5).contains(&version) {
+            let error = ElfError::UnknownValue {
+                offset: start + offset,
+                structure: Structure::DwarfUnit(unit),
+                field: "cu_version",
+                value: version as u64,
+            };
+
+            self.diagnose_field(error, start + offset, size, "cu_version");
+        }
+
+        let header = UnitHeader {
+            offset,
+            end: offset + size,
+            format64,
+            version,
+            unit_type,
+            address_size: address_size.unwrap_or(0) as u8,
+            abbrev_offset: abbrev_offset.unwrap_or(0),
+            dies_start: pos,
+        };
+
+        self.dwarf_units.push(DwarfUnit {
+            section: idx as u16,
+            offset,
+            size,
+            format64,
+            version,
+            unit_type: header.unit_type,
+            address_size: header.address_size,
+            abbrev_offset: header.abbrev_offset,
+            dies: vec![],
+        });
+
+        Some(header)
+    }
+
+    fn parse_dies(
+        &mut self,
+        idx: usize,
+        context: &Context,
+        header: &UnitHeader,
+        abbrevs: &HashMap<u64, Abbrev>,
+    ) {
+        let unit = self.dwarf_units.len() - 1;
+        let start = self.contents_offset(idx);
+        let info = &context.data[..header.end];
+        let mut pos = header.dies_start;
+        let mut depth: usize = 0;
+        let mut values = vec![];
+        let mut bases = (None, None);
+
+        while pos < header.end {
+            let die_start = pos;
+            let code = match uleb(info, &mut pos) {
+                Some(code) => code,
+                None => break,
+            };
+
+            // ends the children of the last DIE which has some
+            if code == 0 {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+
+            let abbrev = match abbrevs.get(&code) {
+                Some(abbrev) => abbrev,
+                None => {
+                    let error = ElfError::UnknownValue {
+                        offset: start + header.offset,
+                        structure: Structure::DwarfUnit(unit as u32),
+                        field: "abbrev_code",
+                        value: code,
+                    };
+
+                    self.diagnose(error, Some((start + die_start, pos - die_start)));
+                    break;
+                }
+            };
+            let code_size = pos - die_start;
+            let mut attributes = vec![];
+            let mut complete = true;
+            let mut unknown_form = None;
+
+            for spec in abbrev.specs.iter() {
+                let attr_start = pos;
+                let value = match context.value(&mut pos, spec.form, spec.implicit, header) {
+                    Some(value) => value,
+                    None => {
+                        complete = false;
+                        unknown_form = Some(spec.form).filter(|&form| !is_known_form(form));
+                        break;
+                    }
+                };
+
+                match (spec.name, &value) {
+                    (DW_AT_STR_OFFSETS_BASE, Value::SecOffset(base)) => bases.0 = Some(*base),
+                    (DW_AT_ADDR_BASE, Value::SecOffset(base))
+                    | (DW_AT_GNU_ADDR_BASE, Value::SecOffset(base)) => bases.1 = Some(*base),
+                    _ => {}
+                }
+
+                values.push(value);
+                attributes.push(DieAttribute {
+                    name: spec.name,
+                    form: spec.form,
+                    offset: attr_start,
+                    size: pos - attr_start,
+                    value: String::new(),
+                    reference: None,
+                });
+            }
+
+            if !complete || pos > header.end {
+                let error = match unknown_form {
+                    Some(form) => ElfError::UnknownValue {
+                        offset: start + header.offset,
+                        structure: Structure::DwarfUnit(unit as u32),
+                        field: "form",
+                        value: form,
+                    },
+                    None => ElfError::Truncated {
+                        offset: start + die_start,
+                        structure: Structure::DwarfUnit(unit as u32),
+                        size: pos.max(header.end + 1) - die_start,
+                        available: header.end - die_start,
+                    },
+                };
+
+                self.diagnose(error, Some((start + die_start, header.end - die_start)));
+                values.truncate(values.len() - attributes.len());
+                break;
+            }
+
+            let die = self.dwarf_units[unit].dies.len() as u32;
+
+            self.ranges.add_range(
+                start + die_start,
+                pos - die_start,
+                RangeType::Die(unit as u32, die),
+            );
+            self.ranges.add_range(
+                start + die_start,
+                code_size,
+                RangeType::DwarfField("abbrev_code"),
+            );
+
+            for (i, attribute) in attributes.iter().enumerate() {
+                if attribute.size > 0 {
+                    self.ranges.add_range(
+                        start + attribute.offset,
+                        attribute.size,
+                        RangeType::DieAttribute(unit as u32, die, i as u16),
+                    );
+                }
+            }
+
+            self.dwarf_units[unit].dies.push(Die {
+                offset: die_start,
+                size: pos - die_start,
+                depth,
+                tag: abbrev.tag,
+                children: abbrev.children,
+                attributes,
+            });
+
+            depth += abbrev.children as usize;
+        }
+
+        // strx and addrx values need the bases, which come after them
+        let attributes = self.dwarf_units[unit]
+            .dies
+            .iter_mut()
+            .flat_map(|die| die.attributes.iter_mut());
+
+        for (attribute, value) in attributes.zip(values) {
+            let (value, reference) = context.render(attribute.name, value, header, bases);
+
+            attribute.value = value;
+            attribute.reference = reference;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_elf::{build, section, Bytes};
+    use super::*;
+
+    fn debug_sections(abbrev: Bytes, info: Bytes, strings: Bytes) -> Vec<u8> {
+        build(
+            &[
+                section(".debug_abbrev", SHT_PROGBITS, abbrev),
+                section(".debug_info", SHT_PROGBITS, info),
+                section(".debug_str", SHT_PROGBITS, strings),
+            ],
+            &[],
+        )
+    }
+
+    #[test]
+    fn dwarf4_unit() {
+        // compile_unit (producer strp, language data2, name string, low_pc
+        // addr) with a base_type (name string, byte_size data1) and a
+        // variable (name string, type ref4) as children
+        let abbrev = Bytes::default()
+            .raw(&[
+                1, 0x11, 1, 0x25, 0x0e, 0x13, 0x05, 0x03, 0x08, 0x11, 0x01, 0, 0,
+            ])
+            .raw(&[2, 0x24, 0, 0x03, 0x08, 0x0b, 0x0b, 0, 0])
+            .raw(&[3, 0x34, 0, 0x03, 0x08, 0x49, 0x13, 0, 0])
+            .u8(0);
+        let info = Bytes::default()
+            .u32(40)
+            .u16(4)
+            .u32(0)
+            .u8(8)
+            .uleb(1)
+            .u32(0)
+            .u16(0x1d)
+            .cstr("a.c")
+            .u64(0x1000)
+            .uleb(2)
+            .cstr("int")
+            .u8(4)
+            .uleb(3)
+            .cstr("x")
+            .u32(30)
+            .u8(0);
+        let buf = debug_sections(abbrev, info, Bytes::default().cstr("GNU C"));
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+
+        assert_eq!(elf.dwarf_units.len(), 1);
+
+        let unit = &elf.dwarf_units[0];
+
+        assert_eq!(
+            (
+                unit.section,
+                unit.offset,
+                unit.size,
+                unit.format64,
+                unit.version
+            ),
+            (2, 0, 44, false, 4)
+        );
+        assert_eq!(
+            (unit.unit_type, unit.address_size, unit.abbrev_offset),
+            (DW_UT_COMPILE, 8, 0)
+        );
+
+        let dies: Vec<_> = unit
+            .dies
+            .iter()
+            .map(|die| (die.offset, die.size, die.depth, die.tag, die.children))
+            .collect();
+
+        assert_eq!(
+            dies,
+            [
+                (11, 19, 0, 0x11, true),
+                (30, 6, 1, 0x24, false),
+                (36, 7, 1, 0x34, false)
+            ]
+        );
+
+        let values: Vec<_> = unit
+            .dies
+            .iter()
+            .flat_map(|die| die.attributes.iter())
+            .map(|attr| (attr.name, attr.offset, attr.size, attr.value.as_str()))
+            .collect();
+
+        assert_eq!(
+            values,
+            [
+                (0x25, 12, 4, "GNU C"),
+                (DW_AT_LANGUAGE, 16, 2, "DW_LANG_C11"),
+                (DW_AT_NAME, 18, 4, "a.c"),
+                (0x11, 22, 8, "0x1000"),
+                (DW_AT_NAME, 31, 4, "int"),
+                (0x0b, 35, 1, "4"),
+                (DW_AT_NAME, 37, 2, "x"),
+                (0x49, 39, 4, "<0x1e>")
+            ]
+        );
+        assert_eq!(unit.dies[2].attributes[1].reference, Some(30));
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn malformed_units() {
+        // an unknown abbreviation code, an unknown version and a unit_length
+        // going past the section
+        let abbrev = Bytes::default().raw(&[1, 0x11, 0, 0, 0, 0]);
+        let info = Bytes::default()
+            .u32(8)
+            .u16(4)
+            .u32(0)
+            .u8(8)
+            .uleb(9)
+            .u32(8)
+            .u16(7)
+            .u8(DW_UT_COMPILE)
+            .u8(8)
+            .u32(0)
+            .u32(100)
+            .u16(4)
+            .u32(0)
+            .u8(8);
+        let buf = debug_sections(abbrev, info, Bytes::default());
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[2].file_offset;
+        let units: Vec<_> = elf
+            .dwarf_units
+            .iter()
+            .map(|unit| (unit.offset, unit.size, unit.version, unit.dies.len()))
+            .collect();
+
+        assert_eq!(units, [(0, 12, 4, 0), (12, 12, 7, 0), (24, 11, 4, 0)]);
+
+        let diagnostics: Vec<_> = elf
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (&diagnostic.error, diagnostic.location))
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            [
+                (
+                    &ElfError::UnknownValue {
+                        offset: start,
+                        structure: Structure::DwarfUnit(0),
+                        field: "abbrev_code",
+                        value: 9,
+                    },
+                    Some((start + 11, 1))
+                ),
+                (
+                    &ElfError::UnknownValue {
+                        offset: start + 12,
+                        structure: Structure::DwarfUnit(1),
+                        field: "cu_version",
+                        value: 7,
+                    },
+                    Some((start + 16, 2))
+                ),
+                (
+                    &ElfError::Truncated {
+                        offset: start + 24,
+                        structure: Structure::DwarfUnit(2),
+                        size: 104,
+                        available: 11,
+                    },
+                    Some((start + 24, 11))
+                )
+            ]
+        );
+    }
+}
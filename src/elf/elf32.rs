@@ -1,12 +1,16 @@
 use super::elfxx::*;
 use super::parser::*;
+use super::ranges::Ranges;
+use std::array::TryFromSliceError;
 use std::convert::TryInto;
 
 type Elf32Addr = u32;
 type Elf32Half = u16;
 type Elf32Off = u32;
 type Elf32Word = u32;
+type Elf32Sword = i32;
 
+#[allow(dead_code)]
 pub struct Elf32Ehdr {
     e_ident: [u8; 16],
     e_type: Elf32Half,
@@ -24,6 +28,7 @@ pub struct Elf32Ehdr {
     e_shstrndx: Elf32Half,
 }
 
+#[allow(dead_code)]
 pub struct Elf32Phdr {
     p_type: Elf32Word,
     p_offset: Elf32Off,
@@ -48,14 +53,36 @@ pub struct Elf32Shdr {
     sh_entsize: Elf32Word,
 }
 
+pub struct Elf32Sym {
+    st_name: Elf32Word,
+    st_value: Elf32Addr,
+    st_size: Elf32Word,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: Elf32Half,
+}
+
+pub struct Elf32Rel {
+    r_offset: Elf32Addr,
+    r_info: Elf32Word,
+}
+
+pub struct Elf32Rela {
+    r_offset: Elf32Addr,
+    r_info: Elf32Word,
+    r_addend: Elf32Sword,
+}
+
+pub struct Elf32Dyn {
+    d_tag: Elf32Sword,
+    d_val: Elf32Word,
+}
+
 pub struct Elf32;
 
 #[rustfmt::skip]
 impl ElfHeader for Elf32Ehdr {
-    fn describe() -> String {
-        String::from("file header")
-    }
-    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Ehdr, ReadErr> {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Ehdr, TryFromSliceError> {
         Ok(Elf32Ehdr {
             e_ident:     buf[0..16].try_into()?,
             e_type:      Elf32Half::from_le_bytes(buf[16..18].try_into()?),
@@ -73,7 +100,7 @@ impl ElfHeader for Elf32Ehdr {
             e_shstrndx:  Elf32Half::from_le_bytes(buf[50..52].try_into()?),
         })
     }
-    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Ehdr, ReadErr> {
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Ehdr, TryFromSliceError> {
         Ok(Elf32Ehdr {
             e_ident:     buf[0..16].try_into()?,
             e_type:      Elf32Half::from_be_bytes(buf[16..18].try_into()?),
@@ -95,10 +122,7 @@ impl ElfHeader for Elf32Ehdr {
 
 #[rustfmt::skip]
 impl ElfHeader for Elf32Phdr {
-    fn describe() -> String {
-        String::from("program header")
-    }
-    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Phdr, ReadErr> {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Phdr, TryFromSliceError> {
         Ok(Elf32Phdr {
             p_type:   Elf32Word::from_le_bytes(buf[ 0.. 4].try_into()?),
             p_offset: Elf32Off:: from_le_bytes(buf[ 4.. 8].try_into()?),
@@ -110,7 +134,7 @@ impl ElfHeader for Elf32Phdr {
             p_align:  Elf32Word::from_le_bytes(buf[28..32].try_into()?),
         })
     }
-    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Phdr, ReadErr> {
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Phdr, TryFromSliceError> {
         Ok(Elf32Phdr {
             p_type:   Elf32Word::from_be_bytes(buf[ 0.. 4].try_into()?),
             p_offset: Elf32Off:: from_be_bytes(buf[ 4.. 8].try_into()?),
@@ -126,10 +150,7 @@ impl ElfHeader for Elf32Phdr {
 
 #[rustfmt::skip]
 impl ElfHeader for Elf32Shdr {
-    fn describe() -> String {
-        String::from("section header")
-    }
-    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Shdr, ReadErr> {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Shdr, TryFromSliceError> {
         Ok(Elf32Shdr {
             sh_name:      Elf32Word::from_le_bytes(buf[ 0.. 4].try_into()?),
             sh_type:      Elf32Word::from_le_bytes(buf[ 4.. 8].try_into()?),
@@ -143,7 +164,7 @@ impl ElfHeader for Elf32Shdr {
             sh_entsize:   Elf32Word::from_le_bytes(buf[36..40].try_into()?),
         })
     }
-    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Shdr, ReadErr> {
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Shdr, TryFromSliceError> {
         Ok(Elf32Shdr {
             sh_name:      Elf32Word::from_be_bytes(buf[ 0.. 4].try_into()?),
             sh_type:      Elf32Word::from_be_bytes(buf[ 4.. 8].try_into()?),
@@ -159,6 +180,80 @@ impl ElfHeader for Elf32Shdr {
     }
 }
 
+#[rustfmt::skip]
+impl ElfHeader for Elf32Sym {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Sym, TryFromSliceError> {
+        Ok(Elf32Sym {
+            st_name:  Elf32Word::from_le_bytes(buf[ 0.. 4].try_into()?),
+            st_value: Elf32Addr::from_le_bytes(buf[ 4.. 8].try_into()?),
+            st_size:  Elf32Word::from_le_bytes(buf[ 8..12].try_into()?),
+            st_info:  buf[12],
+            st_other: buf[13],
+            st_shndx: Elf32Half::from_le_bytes(buf[14..16].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Sym, TryFromSliceError> {
+        Ok(Elf32Sym {
+            st_name:  Elf32Word::from_be_bytes(buf[ 0.. 4].try_into()?),
+            st_value: Elf32Addr::from_be_bytes(buf[ 4.. 8].try_into()?),
+            st_size:  Elf32Word::from_be_bytes(buf[ 8..12].try_into()?),
+            st_info:  buf[12],
+            st_other: buf[13],
+            st_shndx: Elf32Half::from_be_bytes(buf[14..16].try_into()?),
+        })
+    }
+}
+
+#[rustfmt::skip]
+impl ElfHeader for Elf32Rel {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Rel, TryFromSliceError> {
+        Ok(Elf32Rel {
+            r_offset: Elf32Addr:: from_le_bytes(buf[ 0.. 4].try_into()?),
+            r_info:   Elf32Word:: from_le_bytes(buf[ 4.. 8].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Rel, TryFromSliceError> {
+        Ok(Elf32Rel {
+            r_offset: Elf32Addr:: from_be_bytes(buf[ 0.. 4].try_into()?),
+            r_info:   Elf32Word:: from_be_bytes(buf[ 4.. 8].try_into()?),
+        })
+    }
+}
+
+#[rustfmt::skip]
+impl ElfHeader for Elf32Rela {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Rela, TryFromSliceError> {
+        Ok(Elf32Rela {
+            r_offset: Elf32Addr:: from_le_bytes(buf[ 0.. 4].try_into()?),
+            r_info:   Elf32Word:: from_le_bytes(buf[ 4.. 8].try_into()?),
+            r_addend: Elf32Sword::from_le_bytes(buf[ 8..12].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Rela, TryFromSliceError> {
+        Ok(Elf32Rela {
+            r_offset: Elf32Addr:: from_be_bytes(buf[ 0.. 4].try_into()?),
+            r_info:   Elf32Word:: from_be_bytes(buf[ 4.. 8].try_into()?),
+            r_addend: Elf32Sword::from_be_bytes(buf[ 8..12].try_into()?),
+        })
+    }
+}
+
+#[rustfmt::skip]
+impl ElfHeader for Elf32Dyn {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf32Dyn, TryFromSliceError> {
+        Ok(Elf32Dyn {
+            d_tag: Elf32Sword::from_le_bytes(buf[ 0.. 4].try_into()?),
+            d_val: Elf32Word:: from_le_bytes(buf[ 4.. 8].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf32Dyn, TryFromSliceError> {
+        Ok(Elf32Dyn {
+            d_tag: Elf32Sword::from_be_bytes(buf[ 0.. 4].try_into()?),
+            d_val: Elf32Word:: from_be_bytes(buf[ 4.. 8].try_into()?),
+        })
+    }
+}
+
 #[rustfmt::skip]
 impl ElfXXEhdr<Elf32Addr, Elf32Half, Elf32Word, Elf32Off> for Elf32Ehdr {
     fn e_ident(&self)     -> [u8; 16]  { self.e_ident     }
@@ -204,9 +299,40 @@ impl ElfXXShdr<Elf32Addr, Elf32Word, Elf32Off, Elf32Word> for Elf32Shdr {
 }
 
 #[rustfmt::skip]
-impl ElfXX<Elf32Ehdr, Elf32Phdr, Elf32Shdr, Elf32Addr, Elf32Half, Elf32Word, Elf32Off, Elf32Word>
-    for Elf32
-{
+impl ElfXXSym<Elf32Addr, Elf32Half, Elf32Word, Elf32Word> for Elf32Sym {
+    fn st_name(&self)  -> Elf32Word  { self.st_name  }
+    fn st_value(&self) -> Elf32Addr  { self.st_value }
+    fn st_size(&self)  -> Elf32Word  { self.st_size  }
+    fn st_info(&self)  -> u8         { self.st_info  }
+    fn st_other(&self) -> u8         { self.st_other }
+    fn st_shndx(&self) -> Elf32Half  { self.st_shndx }
+}
+
+#[rustfmt::skip]
+impl ElfXXRel<Elf32Addr, Elf32Word> for Elf32Rel {
+    fn r_offset(&self) -> Elf32Addr  { self.r_offset }
+    fn r_info(&self)   -> Elf32Word  { self.r_info   }
+}
+
+#[rustfmt::skip]
+impl ElfXXRela<Elf32Addr, Elf32Word, Elf32Sword> for Elf32Rela {
+    fn r_offset(&self) -> Elf32Addr  { self.r_offset }
+    fn r_info(&self)   -> Elf32Word  { self.r_info   }
+    fn r_addend(&self) -> Elf32Sword { self.r_addend }
+}
+
+#[rustfmt::skip]
+impl ElfXXDyn<Elf32Word, Elf32Sword> for Elf32Dyn {
+    fn d_tag(&self) -> Elf32Sword { self.d_tag }
+    fn d_val(&self) -> Elf32Word  { self.d_val }
+}
+
+#[rustfmt::skip]
+#[allow(clippy::identity_op)]
+impl ElfXX<
+    Elf32Ehdr, Elf32Phdr, Elf32Shdr, Elf32Sym, Elf32Rel, Elf32Rela, Elf32Dyn,
+    Elf32Addr, Elf32Half, Elf32Word, Elf32Off, Elf32Word, Elf32Sword,
+> for Elf32 {
     fn add_ehdr_ranges(ehdr: &Elf32Ehdr, ranges: &mut Ranges) {
         ranges.add_range(0,  ehdr.e_ehsize as usize, RangeType::FileHeader);
         ranges.add_range(16, 2, RangeType::HeaderField("e_type"));
@@ -247,4 +373,35 @@ impl ElfXX<Elf32Ehdr, Elf32Phdr, Elf32Shdr, Elf32Addr, Elf32Half, Elf32Word, Elf
         ranges.add_range(start + 32, 4, RangeType::ShdrField("sh_addralign"));
         ranges.add_range(start + 36, 4, RangeType::ShdrField("sh_entsize"));
     }
+
+    fn add_sym_ranges(start: usize, ranges: &mut Ranges) {
+        ranges.add_range(start +  0, 4, RangeType::SymbolField("st_name"));
+        ranges.add_range(start +  4, 4, RangeType::SymbolField("st_value"));
+        ranges.add_range(start +  8, 4, RangeType::SymbolField("st_size"));
+        ranges.add_range(start + 12, 1, RangeType::SymbolField("st_info"));
+        ranges.add_range(start + 13, 1, RangeType::SymbolField("st_other"));
+        ranges.add_range(start + 14, 2, RangeType::SymbolField("st_shndx"));
+    }
+
+    fn r_sym(info: u64) -> u32 {
+        (info >> 8) as u32
+    }
+
+    fn r_type(info: u64) -> u32 {
+        (info & 0xff) as u32
+    }
+
+    fn add_rel_ranges(start: usize, with_addend: bool, ranges: &mut Ranges) {
+        ranges.add_range(start + 0, 4, RangeType::RelocationField("r_offset"));
+        ranges.add_range(start + 4, 4, RangeType::RelocationField("r_info"));
+
+        if with_addend {
+            ranges.add_range(start + 8, 4, RangeType::RelocationField("r_addend"));
+        }
+    }
+
+    fn add_dyn_ranges(start: usize, ranges: &mut Ranges) {
+        ranges.add_range(start + 0, 4, RangeType::DynamicField("d_tag"));
+        ranges.add_range(start + 4, 4, RangeType::DynamicField("d_val"));
+    }
 }
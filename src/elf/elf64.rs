@@ -1,5 +1,7 @@
 use super::elfxx::*;
 use super::parser::*;
+use super::ranges::Ranges;
+use std::array::TryFromSliceError;
 use std::convert::TryInto;
 
 type Elf64Addr = u64;
@@ -7,7 +9,9 @@ type Elf64Off = u64;
 type Elf64Half = u16;
 type Elf64Word = u32;
 type Elf64Xword = u64;
+type Elf64Sxword = i64;
 
+#[allow(dead_code)]
 pub struct Elf64Ehdr {
     e_ident: [u8; 16],
     e_type: Elf64Half,
@@ -25,6 +29,7 @@ pub struct Elf64Ehdr {
     e_shstrndx: Elf64Half,
 }
 
+#[allow(dead_code)]
 pub struct Elf64Phdr {
     p_type: Elf64Word,
     p_flags: Elf64Word,
@@ -49,15 +54,37 @@ pub struct Elf64Shdr {
     sh_entsize: Elf64Xword,
 }
 
+pub struct Elf64Sym {
+    st_name: Elf64Word,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: Elf64Half,
+    st_value: Elf64Addr,
+    st_size: Elf64Xword,
+}
+
+pub struct Elf64Rel {
+    r_offset: Elf64Addr,
+    r_info: Elf64Xword,
+}
+
+pub struct Elf64Rela {
+    r_offset: Elf64Addr,
+    r_info: Elf64Xword,
+    r_addend: Elf64Sxword,
+}
+
+pub struct Elf64Dyn {
+    d_tag: Elf64Sxword,
+    d_val: Elf64Xword,
+}
+
 pub struct Elf64;
 
 // All this just to avoid unsafe. This should be improved.
 #[rustfmt::skip]
 impl ElfHeader for Elf64Ehdr {
-    fn describe() -> String {
-        String::from("file header")
-    }
-    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Ehdr, ReadErr> {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Ehdr, TryFromSliceError> {
         Ok(Elf64Ehdr {
             e_ident:     buf[0..16].try_into()?,
             e_type:      Elf64Half::from_le_bytes(buf[16..18].try_into()?),
@@ -75,7 +102,7 @@ impl ElfHeader for Elf64Ehdr {
             e_shstrndx:  Elf64Half::from_le_bytes(buf[62..64].try_into()?),
         })
     }
-    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Ehdr, ReadErr> {
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Ehdr, TryFromSliceError> {
         Ok(Elf64Ehdr {
             e_ident:     buf[0..16].try_into()?,
             e_type:      Elf64Half::from_be_bytes(buf[16..18].try_into()?),
@@ -97,10 +124,7 @@ impl ElfHeader for Elf64Ehdr {
 
 #[rustfmt::skip]
 impl ElfHeader for Elf64Phdr {
-    fn describe() -> String {
-        String::from("program header")
-    }
-    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Phdr, ReadErr> {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Phdr, TryFromSliceError> {
         Ok(Elf64Phdr {
             p_type:   Elf64Word:: from_le_bytes(buf[ 0.. 4].try_into()?),
             p_flags:  Elf64Word:: from_le_bytes(buf[ 4.. 8].try_into()?),
@@ -112,7 +136,7 @@ impl ElfHeader for Elf64Phdr {
             p_align:  Elf64Xword::from_le_bytes(buf[48..56].try_into()?),
         })
     }
-    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Phdr, ReadErr> {
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Phdr, TryFromSliceError> {
         Ok(Elf64Phdr {
             p_type:   Elf64Word:: from_be_bytes(buf[ 0.. 4].try_into()?),
             p_flags:  Elf64Word:: from_be_bytes(buf[ 4.. 8].try_into()?),
@@ -128,10 +152,7 @@ impl ElfHeader for Elf64Phdr {
 
 #[rustfmt::skip]
 impl ElfHeader for Elf64Shdr {
-    fn describe() -> String {
-        String::from("section header")
-    }
-    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Shdr, ReadErr> {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Shdr, TryFromSliceError> {
         Ok(Elf64Shdr {
             sh_name:      Elf64Word:: from_le_bytes(buf[ 0.. 4].try_into()?),
             sh_type:      Elf64Word:: from_le_bytes(buf[ 4.. 8].try_into()?),
@@ -145,7 +166,7 @@ impl ElfHeader for Elf64Shdr {
             sh_entsize:   Elf64Xword::from_le_bytes(buf[56..64].try_into()?),
         })
     }
-    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Shdr, ReadErr> {
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Shdr, TryFromSliceError> {
         Ok(Elf64Shdr {
             sh_name:      Elf64Word:: from_be_bytes(buf[ 0.. 4].try_into()?),
             sh_type:      Elf64Word:: from_be_bytes(buf[ 4.. 8].try_into()?),
@@ -161,6 +182,80 @@ impl ElfHeader for Elf64Shdr {
     }
 }
 
+#[rustfmt::skip]
+impl ElfHeader for Elf64Sym {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Sym, TryFromSliceError> {
+        Ok(Elf64Sym {
+            st_name:  Elf64Word:: from_le_bytes(buf[ 0.. 4].try_into()?),
+            st_info:  buf[4],
+            st_other: buf[5],
+            st_shndx: Elf64Half:: from_le_bytes(buf[ 6.. 8].try_into()?),
+            st_value: Elf64Addr:: from_le_bytes(buf[ 8..16].try_into()?),
+            st_size:  Elf64Xword::from_le_bytes(buf[16..24].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Sym, TryFromSliceError> {
+        Ok(Elf64Sym {
+            st_name:  Elf64Word:: from_be_bytes(buf[ 0.. 4].try_into()?),
+            st_info:  buf[4],
+            st_other: buf[5],
+            st_shndx: Elf64Half:: from_be_bytes(buf[ 6.. 8].try_into()?),
+            st_value: Elf64Addr:: from_be_bytes(buf[ 8..16].try_into()?),
+            st_size:  Elf64Xword::from_be_bytes(buf[16..24].try_into()?),
+        })
+    }
+}
+
+#[rustfmt::skip]
+impl ElfHeader for Elf64Rel {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Rel, TryFromSliceError> {
+        Ok(Elf64Rel {
+            r_offset: Elf64Addr::  from_le_bytes(buf[ 0.. 8].try_into()?),
+            r_info:   Elf64Xword:: from_le_bytes(buf[ 8..16].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Rel, TryFromSliceError> {
+        Ok(Elf64Rel {
+            r_offset: Elf64Addr::  from_be_bytes(buf[ 0.. 8].try_into()?),
+            r_info:   Elf64Xword:: from_be_bytes(buf[ 8..16].try_into()?),
+        })
+    }
+}
+
+#[rustfmt::skip]
+impl ElfHeader for Elf64Rela {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Rela, TryFromSliceError> {
+        Ok(Elf64Rela {
+            r_offset: Elf64Addr::  from_le_bytes(buf[ 0.. 8].try_into()?),
+            r_info:   Elf64Xword:: from_le_bytes(buf[ 8..16].try_into()?),
+            r_addend: Elf64Sxword::from_le_bytes(buf[16..24].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Rela, TryFromSliceError> {
+        Ok(Elf64Rela {
+            r_offset: Elf64Addr::  from_be_bytes(buf[ 0.. 8].try_into()?),
+            r_info:   Elf64Xword:: from_be_bytes(buf[ 8..16].try_into()?),
+            r_addend: Elf64Sxword::from_be_bytes(buf[16..24].try_into()?),
+        })
+    }
+}
+
+#[rustfmt::skip]
+impl ElfHeader for Elf64Dyn {
+    fn from_le_bytes(buf: &[u8]) -> Result<Elf64Dyn, TryFromSliceError> {
+        Ok(Elf64Dyn {
+            d_tag: Elf64Sxword::from_le_bytes(buf[ 0.. 8].try_into()?),
+            d_val: Elf64Xword:: from_le_bytes(buf[ 8..16].try_into()?),
+        })
+    }
+    fn from_be_bytes(buf: &[u8]) -> Result<Elf64Dyn, TryFromSliceError> {
+        Ok(Elf64Dyn {
+            d_tag: Elf64Sxword::from_be_bytes(buf[ 0.. 8].try_into()?),
+            d_val: Elf64Xword:: from_be_bytes(buf[ 8..16].try_into()?),
+        })
+    }
+}
+
 #[rustfmt::skip]
 impl ElfXXEhdr<Elf64Addr, Elf64Half, Elf64Word, Elf64Off> for Elf64Ehdr {
     fn e_ident(&self)     -> [u8; 16]  { self.e_ident     }
@@ -206,9 +301,40 @@ impl ElfXXShdr<Elf64Addr, Elf64Word, Elf64Off, Elf64Xword> for Elf64Shdr {
 }
 
 #[rustfmt::skip]
-impl ElfXX<Elf64Ehdr, Elf64Phdr, Elf64Shdr, Elf64Addr, Elf64Half, Elf64Word, Elf64Off, Elf64Xword>
-    for Elf64
-{
+impl ElfXXSym<Elf64Addr, Elf64Half, Elf64Word, Elf64Xword> for Elf64Sym {
+    fn st_name(&self)  -> Elf64Word  { self.st_name  }
+    fn st_value(&self) -> Elf64Addr  { self.st_value }
+    fn st_size(&self)  -> Elf64Xword { self.st_size  }
+    fn st_info(&self)  -> u8         { self.st_info  }
+    fn st_other(&self) -> u8         { self.st_other }
+    fn st_shndx(&self) -> Elf64Half  { self.st_shndx }
+}
+
+#[rustfmt::skip]
+impl ElfXXRel<Elf64Addr, Elf64Xword> for Elf64Rel {
+    fn r_offset(&self) -> Elf64Addr   { self.r_offset }
+    fn r_info(&self)   -> Elf64Xword  { self.r_info   }
+}
+
+#[rustfmt::skip]
+impl ElfXXRela<Elf64Addr, Elf64Xword, Elf64Sxword> for Elf64Rela {
+    fn r_offset(&self) -> Elf64Addr   { self.r_offset }
+    fn r_info(&self)   -> Elf64Xword  { self.r_info   }
+    fn r_addend(&self) -> Elf64Sxword { self.r_addend }
+}
+
+#[rustfmt::skip]
+impl ElfXXDyn<Elf64Xword, Elf64Sxword> for Elf64Dyn {
+    fn d_tag(&self) -> Elf64Sxword { self.d_tag }
+    fn d_val(&self) -> Elf64Xword  { self.d_val }
+}
+
+#[rustfmt::skip]
+#[allow(clippy::identity_op)]
+impl ElfXX<
+    Elf64Ehdr, Elf64Phdr, Elf64Shdr, Elf64Sym, Elf64Rel, Elf64Rela, Elf64Dyn,
+    Elf64Addr, Elf64Half, Elf64Word, Elf64Off, Elf64Xword, Elf64Sxword,
+> for Elf64 {
     fn add_ehdr_ranges(ehdr: &Elf64Ehdr, ranges: &mut Ranges) {
         ranges.add_range(0,  ehdr.e_ehsize as usize, RangeType::FileHeader);
         ranges.add_range(16, 2, RangeType::HeaderField("e_type"));
@@ -249,4 +375,35 @@ impl ElfXX<Elf64Ehdr, Elf64Phdr, Elf64Shdr, Elf64Addr, Elf64Half, Elf64Word, Elf
         ranges.add_range(start + 48, 8, RangeType::ShdrField("sh_addralign"));
         ranges.add_range(start + 56, 8, RangeType::ShdrField("sh_entsize"));
     }
+
+    fn add_sym_ranges(start: usize, ranges: &mut Ranges) {
+        ranges.add_range(start +  0, 4, RangeType::SymbolField("st_name"));
+        ranges.add_range(start +  4, 1, RangeType::SymbolField("st_info"));
+        ranges.add_range(start +  5, 1, RangeType::SymbolField("st_other"));
+        ranges.add_range(start +  6, 2, RangeType::SymbolField("st_shndx"));
+        ranges.add_range(start +  8, 8, RangeType::SymbolField("st_value"));
+        ranges.add_range(start + 16, 8, RangeType::SymbolField("st_size"));
+    }
+
+    fn r_sym(info: u64) -> u32 {
+        (info >> 32) as u32
+    }
+
+    fn r_type(info: u64) -> u32 {
+        (info & 0xffff_ffff) as u32
+    }
+
+    fn add_rel_ranges(start: usize, with_addend: bool, ranges: &mut Ranges) {
+        ranges.add_range(start +  0, 8, RangeType::RelocationField("r_offset"));
+        ranges.add_range(start +  8, 8, RangeType::RelocationField("r_info"));
+
+        if with_addend {
+            ranges.add_range(start + 16, 8, RangeType::RelocationField("r_addend"));
+        }
+    }
+
+    fn add_dyn_ranges(start: usize, ranges: &mut Ranges) {
+        ranges.add_range(start + 0, 8, RangeType::DynamicField("d_tag"));
+        ranges.add_range(start + 8, 8, RangeType::DynamicField("d_val"));
+    }
 }
@@ -1,30 +1,50 @@
 use super::defs::*;
+use super::error::{ElfError, Structure};
 use super::parser::*;
+use super::ranges::Ranges;
+use std::array::TryFromSliceError;
 use std::mem::size_of;
 
 pub trait ElfHeader {
-    fn from_le_bytes(buf: &[u8]) -> Result<Self, ReadErr>
+    fn from_le_bytes(buf: &[u8]) -> Result<Self, TryFromSliceError>
     where
         Self: Sized;
-    fn from_be_bytes(buf: &[u8]) -> Result<Self, ReadErr>
+    fn from_be_bytes(buf: &[u8]) -> Result<Self, TryFromSliceError>
     where
         Self: Sized;
-    fn describe() -> String;
 
-    fn from_bytes(buf: &[u8], endianness: u8) -> Result<Self, String>
+    // `offset` and `structure` describe where `buf` comes from for errors
+    fn from_bytes(
+        buf: &[u8],
+        endianness: u8,
+        offset: usize,
+        structure: Structure,
+    ) -> Result<Self, ElfError>
     where
         Self: Sized,
     {
+        let truncated = ElfError::Truncated {
+            offset,
+            structure,
+            size: size_of::<Self>(),
+            available: buf.len(),
+        };
+
+        if buf.len() < size_of::<Self>() {
+            return Err(truncated);
+        }
+
         if endianness == ELF_DATA2LSB {
             Self::from_le_bytes(buf)
         } else {
             Self::from_be_bytes(buf)
         }
-        .map_err(|a| format!("failed to read {}: {}", Self::describe(), a))
+        .map_err(|_| truncated)
     }
 }
 
 // We do this because we can't access struct fields of a generic type
+#[allow(dead_code)]
 pub trait ElfXXEhdr<ElfXXAddr, ElfXXHalf, ElfXXWord, ElfXXOff> {
     fn e_ident(&self) -> [u8; 16];
     fn e_type(&self) -> ElfXXHalf;
@@ -42,6 +62,7 @@ pub trait ElfXXEhdr<ElfXXAddr, ElfXXHalf, ElfXXWord, ElfXXOff> {
     fn e_shstrndx(&self) -> ElfXXHalf;
 }
 
+#[allow(dead_code)]
 pub trait ElfXXPhdr<ElfXXAddr, ElfXXWord, ElfXXOff, ElfXXXword> {
     fn p_type(&self) -> ElfXXWord;
     fn p_flags(&self) -> ElfXXWord;
@@ -66,20 +87,66 @@ pub trait ElfXXShdr<ElfXXAddr, ElfXXWord, ElfXXOff, ElfXXXword> {
     fn sh_entsize(&self) -> ElfXXXword;
 }
 
+pub trait ElfXXSym<ElfXXAddr, ElfXXHalf, ElfXXWord, ElfXXXword> {
+    fn st_name(&self) -> ElfXXWord;
+    fn st_value(&self) -> ElfXXAddr;
+    fn st_size(&self) -> ElfXXXword;
+    fn st_info(&self) -> u8;
+    fn st_other(&self) -> u8;
+    fn st_shndx(&self) -> ElfXXHalf;
+}
+
+pub trait ElfXXRel<ElfXXAddr, ElfXXXword> {
+    fn r_offset(&self) -> ElfXXAddr;
+    fn r_info(&self) -> ElfXXXword;
+}
+
+pub trait ElfXXRela<ElfXXAddr, ElfXXXword, ElfXXSxword> {
+    fn r_offset(&self) -> ElfXXAddr;
+    fn r_info(&self) -> ElfXXXword;
+    fn r_addend(&self) -> ElfXXSxword;
+}
+
+pub trait ElfXXDyn<ElfXXXword, ElfXXSxword> {
+    fn d_tag(&self) -> ElfXXSxword;
+    fn d_val(&self) -> ElfXXXword;
+}
+
 macro_rules! read_field {
-    ($name:ident, $field:ident) => {
+    ($name:ident, $field:ident, $offset:expr, $structure:expr) => {
         $name
             .$field()
             .try_into()
-            .map_err(|_| format!("failed to read {}", stringify!($field)))
+            .map_err(|_| ElfError::FieldOverflow {
+                offset: $offset,
+                structure: $structure,
+                field: stringify!($field),
+            })
     };
 }
 
-pub trait ElfXX<EhdrT, PhdrT, ShdrT, ElfXXAddr, ElfXXHalf, ElfXXWord, ElfXXOff, ElfXXXword>
-where
+pub trait ElfXX<
+    EhdrT,
+    PhdrT,
+    ShdrT,
+    SymT,
+    RelT,
+    RelaT,
+    DynT,
+    ElfXXAddr,
+    ElfXXHalf,
+    ElfXXWord,
+    ElfXXOff,
+    ElfXXXword,
+    ElfXXSxword,
+> where
     EhdrT: ElfHeader + ElfXXEhdr<ElfXXAddr, ElfXXHalf, ElfXXWord, ElfXXOff>,
     PhdrT: ElfHeader + ElfXXPhdr<ElfXXAddr, ElfXXWord, ElfXXOff, ElfXXXword>,
     ShdrT: ElfHeader + ElfXXShdr<ElfXXAddr, ElfXXWord, ElfXXOff, ElfXXXword>,
+    SymT: ElfHeader + ElfXXSym<ElfXXAddr, ElfXXHalf, ElfXXWord, ElfXXXword>,
+    RelT: ElfHeader + ElfXXRel<ElfXXAddr, ElfXXXword>,
+    RelaT: ElfHeader + ElfXXRela<ElfXXAddr, ElfXXXword, ElfXXSxword>,
+    DynT: ElfHeader + ElfXXDyn<ElfXXXword, ElfXXSxword>,
     u32: From<ElfXXWord>,
     u64: From<ElfXXXword>,
     ElfXXAddr: std::convert::TryInto<usize> + std::fmt::LowerHex,
@@ -87,17 +154,25 @@ where
     ElfXXWord: std::convert::TryInto<usize> + std::fmt::LowerHex,
     ElfXXOff: std::convert::TryInto<usize> + std::fmt::Display,
     ElfXXXword: std::convert::TryInto<usize>,
+    ElfXXSxword: std::convert::Into<i64>,
 {
-    fn parse(buf: &[u8], ident: &ParsedIdent, elf: &mut ParsedElf) -> Result<(), String> {
+    fn parse(buf: &[u8], ident: &ParsedIdent, elf: &mut ParsedElf) -> Result<(), ElfError> {
         let ehdr_size = size_of::<EhdrT>();
 
-        if buf.len() < ehdr_size {
-            return Err(String::from("file is smaller than ELF file header"));
-        }
+        let ehdr = match EhdrT::from_bytes(buf, ident.endianness, 0, Structure::FileHeader) {
+            Ok(ehdr) => ehdr,
+            Err(e) => {
+                elf.diagnose(e, Some((0, buf.len())));
 
-        let ehdr = EhdrT::from_bytes(&buf[0..ehdr_size], ident.endianness)?;
+                return Ok(());
+            }
+        };
 
         elf.shstrndx = ehdr.e_shstrndx().into();
+        elf.machine = ehdr.e_machine().into();
+        elf.ehdr_size = ehdr_size;
+        elf.shoff = read_field!(ehdr, e_shoff, 0, Structure::FileHeader)?;
+        elf.shdr_size = size_of::<ShdrT>();
 
         Self::parse_ehdr(&ehdr, elf);
 
@@ -105,6 +180,12 @@ where
 
         Self::parse_shdrs(buf, ident.endianness, &ehdr, elf)?;
 
+        Self::parse_symtabs(ident.endianness, elf)?;
+
+        Self::parse_relocations(ident.endianness, elf)?;
+
+        Self::parse_dynamic(ident.endianness, elf)?;
+
         Ok(())
     }
 
@@ -163,13 +244,27 @@ where
         endianness: u8,
         ehdr: &EhdrT,
         elf: &mut ParsedElf,
-    ) -> Result<(), String> {
-        let mut start = read_field!(ehdr, e_phoff)?;
+    ) -> Result<(), ElfError> {
+        let phoff = read_field!(ehdr, e_phoff, 0, Structure::FileHeader)?;
+        let phnum = ehdr.e_phnum().into();
         let phsize = size_of::<PhdrT>();
+        let ehdr_size = size_of::<EhdrT>();
 
-        for i in 0..ehdr.e_phnum().into() {
-            let phdr = PhdrT::from_bytes(&buf[start..start + phsize], endianness)?;
-            let parsed = Self::parse_phdr(&phdr)?;
+        Self::check_entsize(elf, ehdr.e_phentsize().into(), phnum, phsize, "e_phentsize");
+
+        for i in 0..phnum {
+            let start = phoff.saturating_add(i as usize * phsize);
+            let bytes = buf.get(start..).unwrap_or(&[]);
+            let phdr =
+                match PhdrT::from_bytes(bytes, endianness, start, Structure::ProgramHeader(i)) {
+                    Ok(phdr) => phdr,
+                    Err(e) => {
+                        elf.diagnose_field(e, 0, ehdr_size, "e_phnum");
+
+                        break;
+                    }
+                };
+            let parsed = Self::parse_phdr(&phdr, start, i)?;
             let ranges = &mut elf.ranges;
 
             if parsed.file_offset != 0 && parsed.file_size != 0 {
@@ -180,20 +275,83 @@ where
 
             Self::add_phdr_ranges(start, ranges);
 
-            elf.phdrs.push(parsed);
+            let area = (parsed.file_offset, parsed.file_size);
+            let structure = Structure::ProgramHeader(i);
+
+            Self::check_area(
+                elf,
+                area,
+                structure,
+                (start, phsize),
+                ("p_offset", "p_filesz"),
+            );
 
-            start += phsize;
+            elf.phdrs.push(parsed);
         }
 
         Ok(())
     }
 
-    fn parse_phdr(phdr: &PhdrT) -> Result<ParsedPhdr, String> {
-        let file_offset = read_field!(phdr, p_offset)?;
-        let file_size = read_field!(phdr, p_filesz)?;
-        let vaddr = read_field!(phdr, p_vaddr)?;
-        let memsz = read_field!(phdr, p_memsz)?;
-        let alignment = read_field!(phdr, p_align)?;
+    fn check_entsize(
+        elf: &mut ParsedElf,
+        entsize: u16,
+        count: u16,
+        expected: usize,
+        field: &'static str,
+    ) {
+        if count != 0 && entsize as usize != expected {
+            let error = ElfError::BadEntrySize {
+                offset: 0,
+                structure: Structure::FileHeader,
+                field,
+                size: entsize as usize,
+                expected,
+            };
+
+            elf.diagnose_field(error, 0, size_of::<EhdrT>(), field);
+        }
+    }
+
+    // reports an area of a segment or section that doesn't fit in the file,
+    // `fields` are the names of its offset and size fields in the header at `header`
+    fn check_area(
+        elf: &mut ParsedElf,
+        area: (usize, usize),
+        structure: Structure,
+        header: (usize, usize),
+        fields: (&'static str, &'static str),
+    ) {
+        let (offset, size) = area;
+        let file_size = elf.contents.len();
+
+        if size == 0 || offset.checked_add(size).is_some_and(|end| end <= file_size) {
+            return;
+        }
+
+        let (field, value, limit) = if offset >= file_size {
+            (fields.0, offset, file_size)
+        } else {
+            (fields.1, size, file_size - offset)
+        };
+
+        let error = ElfError::OutOfBounds {
+            offset: header.0,
+            structure,
+            field,
+            value: value as u64,
+            limit: limit as u64,
+        };
+
+        elf.diagnose_field(error, header.0, header.1, field);
+    }
+
+    fn parse_phdr(phdr: &PhdrT, offset: usize, idx: u16) -> Result<ParsedPhdr, ElfError> {
+        let structure = Structure::ProgramHeader(idx);
+        let file_offset = read_field!(phdr, p_offset, offset, structure)?;
+        let file_size = read_field!(phdr, p_filesz, offset, structure)?;
+        let vaddr = read_field!(phdr, p_vaddr, offset, structure)?;
+        let memsz = read_field!(phdr, p_memsz, offset, structure)?;
+        let alignment = read_field!(phdr, p_align, offset, structure)?;
 
         Ok(ParsedPhdr {
             ptype: phdr.p_type().into(),
@@ -213,13 +371,27 @@ where
         endianness: u8,
         ehdr: &EhdrT,
         elf: &mut ParsedElf,
-    ) -> Result<(), String> {
-        let mut start = read_field!(ehdr, e_shoff)?;
+    ) -> Result<(), ElfError> {
+        let shoff = read_field!(ehdr, e_shoff, 0, Structure::FileHeader)?;
+        let shnum = ehdr.e_shnum().into();
         let shsize = size_of::<ShdrT>();
+        let ehdr_size = size_of::<EhdrT>();
 
-        for i in 0..ehdr.e_shnum().into() {
-            let shdr = ShdrT::from_bytes(&buf[start..start + shsize], endianness)?;
-            let parsed = Self::parse_shdr(buf, endianness, &shdr)?;
+        Self::check_entsize(elf, ehdr.e_shentsize().into(), shnum, shsize, "e_shentsize");
+
+        for i in 0..shnum {
+            let start = shoff.saturating_add(i as usize * shsize);
+            let bytes = buf.get(start..).unwrap_or(&[]);
+            let shdr =
+                match ShdrT::from_bytes(bytes, endianness, start, Structure::SectionHeader(i)) {
+                    Ok(shdr) => shdr,
+                    Err(e) => {
+                        elf.diagnose_field(e, 0, ehdr_size, "e_shnum");
+
+                        break;
+                    }
+                };
+            let parsed = Self::parse_shdr(&shdr, start, i)?;
             let ranges = &mut elf.ranges;
 
             if parsed.file_offset != 0 && parsed.size != 0 && parsed.shtype != SHT_NOBITS {
@@ -230,23 +402,67 @@ where
 
             Self::add_shdr_ranges(start, ranges);
 
-            elf.shdrs.push(parsed);
+            if parsed.shtype != SHT_NOBITS {
+                let area = (parsed.file_offset, parsed.size);
+                let structure = Structure::SectionHeader(i);
+
+                Self::check_area(
+                    elf,
+                    area,
+                    structure,
+                    (start, shsize),
+                    ("sh_offset", "sh_size"),
+                );
+            }
 
-            start += shsize;
+            elf.shdrs.push(parsed);
         }
 
+        Self::check_links(elf);
+
         Ok(())
     }
 
-    fn parse_shdr(_buf: &[u8], _endianness: u8, shdr: &ShdrT) -> Result<ParsedShdr, String> {
-        let name = read_field!(shdr, sh_name)?;
-        let addr = read_field!(shdr, sh_addr)?;
-        let file_offset = read_field!(shdr, sh_offset)?;
-        let size = read_field!(shdr, sh_size)?;
-        let link = read_field!(shdr, sh_link)?;
-        let info = read_field!(shdr, sh_info)?;
-        let addralign = read_field!(shdr, sh_addralign)?;
-        let entsize = read_field!(shdr, sh_entsize)?;
+    // sh_link of these refers to another section
+    fn check_links(elf: &mut ParsedElf) {
+        for i in 0..elf.shdrs.len() {
+            let shdr = &elf.shdrs[i];
+            let has_link = matches!(
+                shdr.shtype,
+                SHT_SYMTAB
+                    | SHT_DYNSYM
+                    | SHT_REL
+                    | SHT_RELA
+                    | SHT_DYNAMIC
+                    | SHT_HASH
+                    | SHT_GNU_HASH
+            );
+
+            if has_link && shdr.link >= elf.shdrs.len() {
+                let location = elf.shdr_location(i);
+                let error = ElfError::OutOfBounds {
+                    offset: location.0,
+                    structure: Structure::SectionHeader(i as u16),
+                    field: "sh_link",
+                    value: shdr.link as u64,
+                    limit: elf.shdrs.len() as u64,
+                };
+
+                elf.diagnose_field(error, location.0, location.1, "sh_link");
+            }
+        }
+    }
+
+    fn parse_shdr(shdr: &ShdrT, offset: usize, idx: u16) -> Result<ParsedShdr, ElfError> {
+        let structure = Structure::SectionHeader(idx);
+        let name = read_field!(shdr, sh_name, offset, structure)?;
+        let addr = read_field!(shdr, sh_addr, offset, structure)?;
+        let file_offset = read_field!(shdr, sh_offset, offset, structure)?;
+        let size = read_field!(shdr, sh_size, offset, structure)?;
+        let link = read_field!(shdr, sh_link, offset, structure)?;
+        let info = read_field!(shdr, sh_info, offset, structure)?;
+        let addralign = read_field!(shdr, sh_addralign, offset, structure)?;
+        let entsize = read_field!(shdr, sh_entsize, offset, structure)?;
 
         Ok(ParsedShdr {
             name,
@@ -263,4 +479,227 @@ where
     }
 
     fn add_shdr_ranges(start: usize, ranges: &mut Ranges);
+
+    fn parse_symtabs(endianness: u8, elf: &mut ParsedElf) -> Result<(), ElfError> {
+        let symsize = size_of::<SymT>();
+
+        for i in 0..elf.shdrs.len() {
+            let shdr = &elf.shdrs[i];
+
+            if shdr.shtype != SHT_SYMTAB && shdr.shtype != SHT_DYNSYM {
+                continue;
+            }
+
+            let section = match elf.section_data(shdr) {
+                Some(section) => section,
+                None => continue,
+            };
+            let strtab = elf.linked_strtab(shdr);
+            let start = shdr.file_offset;
+            let mut symbols = vec![];
+
+            for j in 0..section.len() / symsize {
+                let offset = j * symsize;
+                let structure = Structure::Symbol(i as u16, j as u32);
+                let entry = &section[offset..offset + symsize];
+                let sym = SymT::from_bytes(entry, endianness, start + offset, structure)?;
+                let name = read_field!(sym, st_name, start + offset, structure)?;
+
+                symbols.push(Self::parse_sym(&sym, &strtab, start + offset, structure)?);
+
+                let ranges = &mut elf.ranges;
+
+                ranges.add_range(
+                    start + offset,
+                    symsize,
+                    RangeType::Symbol(i as u16, j as u32),
+                );
+
+                Self::add_sym_ranges(start + offset, ranges);
+
+                if name >= strtab.size() {
+                    let error = ElfError::OutOfBounds {
+                        offset: start + offset,
+                        structure,
+                        field: "st_name",
+                        value: name as u64,
+                        limit: strtab.size() as u64,
+                    };
+
+                    elf.diagnose_field(error, start + offset, symsize, "st_name");
+                }
+            }
+
+            elf.symtabs.insert(i as u16, symbols);
+        }
+
+        Ok(())
+    }
+
+    fn parse_sym(
+        sym: &SymT,
+        strtab: &StrTab,
+        offset: usize,
+        structure: Structure,
+    ) -> Result<Symbol, ElfError> {
+        let name = read_field!(sym, st_name, offset, structure)?;
+        let value = read_field!(sym, st_value, offset, structure)?;
+        let size = read_field!(sym, st_size, offset, structure)?;
+
+        Ok(Symbol {
+            name: strtab.get(name).to_string(),
+            value,
+            size,
+            binding: sym.st_info() >> 4,
+            stype: sym.st_info() & 0xf,
+            visibility: sym.st_other() & 0x3,
+            shndx: sym.st_shndx().into(),
+        })
+    }
+
+    fn add_sym_ranges(start: usize, ranges: &mut Ranges);
+
+    fn parse_relocations(endianness: u8, elf: &mut ParsedElf) -> Result<(), ElfError> {
+        for i in 0..elf.shdrs.len() {
+            let shdr = &elf.shdrs[i];
+            let with_addend = shdr.shtype == SHT_RELA;
+            let entsize = match shdr.shtype {
+                SHT_REL => size_of::<RelT>(),
+                SHT_RELA => size_of::<RelaT>(),
+                _ => continue,
+            };
+
+            let section = match elf.section_data(shdr) {
+                Some(section) => section,
+                None => continue,
+            };
+            let start = shdr.file_offset;
+            let symbol_count = elf.symtabs.get(&(shdr.link as u16)).map(|syms| syms.len());
+            let mut relocations = vec![];
+
+            for j in 0..section.len() / entsize {
+                let offset = j * entsize;
+                let entry = &section[offset..offset + entsize];
+                let structure = Structure::Relocation(i as u16, j as u32);
+                let at = (start + offset, structure);
+
+                let relocation = if with_addend {
+                    Self::parse_rela(&RelaT::from_bytes(entry, endianness, at.0, at.1)?, at)?
+                } else {
+                    Self::parse_rel(&RelT::from_bytes(entry, endianness, at.0, at.1)?, at)?
+                };
+                let sym = relocation.sym as usize;
+
+                relocations.push(relocation);
+
+                let ranges = &mut elf.ranges;
+
+                ranges.add_range(
+                    start + offset,
+                    entsize,
+                    RangeType::Relocation(i as u16, j as u32),
+                );
+
+                Self::add_rel_ranges(start + offset, with_addend, ranges);
+
+                if let Some(count) = symbol_count.filter(|&count| sym >= count) {
+                    let error = ElfError::OutOfBounds {
+                        offset: start + offset,
+                        structure,
+                        field: "r_info",
+                        value: sym as u64,
+                        limit: count as u64,
+                    };
+
+                    elf.diagnose_field(error, start + offset, entsize, "r_info");
+                }
+            }
+
+            elf.relocations.insert(i as u16, relocations);
+        }
+
+        Ok(())
+    }
+
+    // `at` is the file offset and structure of the entry
+    fn parse_rel(rel: &RelT, at: (usize, Structure)) -> Result<Relocation, ElfError> {
+        let offset = read_field!(rel, r_offset, at.0, at.1)?;
+        let info = u64::from(rel.r_info());
+
+        Ok(Relocation {
+            offset,
+            sym: Self::r_sym(info),
+            rtype: Self::r_type(info),
+            addend: None,
+        })
+    }
+
+    fn parse_rela(rela: &RelaT, at: (usize, Structure)) -> Result<Relocation, ElfError> {
+        let offset = read_field!(rela, r_offset, at.0, at.1)?;
+        let info = u64::from(rela.r_info());
+
+        Ok(Relocation {
+            offset,
+            sym: Self::r_sym(info),
+            rtype: Self::r_type(info),
+            addend: Some(rela.r_addend().into()),
+        })
+    }
+
+    fn r_sym(info: u64) -> u32;
+
+    fn r_type(info: u64) -> u32;
+
+    fn add_rel_ranges(start: usize, with_addend: bool, ranges: &mut Ranges);
+
+    // prefer the segment since it's what the dynamic linker uses
+    fn find_dynamic_area(elf: &ParsedElf) -> Option<(usize, usize)> {
+        if let Some(phdr) = elf.phdrs.iter().find(|phdr| phdr.ptype == PT_DYNAMIC) {
+            return Some((phdr.file_offset, phdr.file_size));
+        }
+
+        elf.shdrs
+            .iter()
+            .find(|shdr| shdr.shtype == SHT_DYNAMIC)
+            .map(|shdr| (shdr.file_offset, shdr.size))
+    }
+
+    fn parse_dynamic(endianness: u8, elf: &mut ParsedElf) -> Result<(), ElfError> {
+        let (start, size) = match Self::find_dynamic_area(elf) {
+            Some(area) => area,
+            None => return Ok(()),
+        };
+
+        let area = match elf.contents.get(start..start.saturating_add(size)) {
+            Some(area) => area,
+            None => return Ok(()),
+        };
+        let entsize = size_of::<DynT>();
+
+        for i in 0..area.len() / entsize {
+            let offset = i * entsize;
+            let entry = &area[offset..offset + entsize];
+            let structure = Structure::DynamicEntry(i as u32);
+            let dyn_entry = DynT::from_bytes(entry, endianness, start + offset, structure)?;
+            let tag = dyn_entry.d_tag().into();
+            let ranges = &mut elf.ranges;
+
+            ranges.add_range(start + offset, entsize, RangeType::Dynamic(i as u32));
+
+            Self::add_dyn_ranges(start + offset, ranges);
+
+            elf.dynamic.push(DynamicEntry {
+                tag,
+                val: dyn_entry.d_val().into(),
+            });
+
+            if tag == DT_NULL {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_dyn_ranges(start: usize, ranges: &mut Ranges);
 }
@@ -0,0 +1,259 @@
+use std::fmt;
+
+// What an error refers to. Indices are the ones used throughout the report,
+// e.g. Symbol(section index, symbol index).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Structure {
+    Ident,
+    FileHeader,
+    ProgramHeader(u16),
+    SectionHeader(u16),
+    Symbol(u16, u32),
+    Relocation(u16, u32),
+    DynamicEntry(u32),
+    Note,
+    VersionRecord(u16, u32),
+    HashTable(u16),
+    DwarfUnit(u32),
+    LineProgram(u32),
+    FrameRecord(u32),
+    EhFrameHdr,
+    CompressedSection(u16),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ElfError {
+    // structure needs `size` bytes but only `available` are left in the file
+    Truncated {
+        offset: usize,
+        structure: Structure,
+        size: usize,
+        available: usize,
+    },
+    BadMagic {
+        magic: [u8; 4],
+    },
+    BadClass {
+        offset: usize,
+        class: u8,
+    },
+    BadEncoding {
+        offset: usize,
+        encoding: u8,
+    },
+    // value of a field doesn't fit in usize
+    FieldOverflow {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+    },
+    // `value` of `field` points outside of something which has size `limit`:
+    // the file, a table, a string table, etc.
+    OutOfBounds {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+        value: u64,
+        limit: u64,
+    },
+    BadEntrySize {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+        size: usize,
+        expected: usize,
+    },
+    // `value` of `field` isn't one this parser knows how to interpret
+    UnknownValue {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+        value: u64,
+    },
+    // `value` of `field` disagrees with what another structure says
+    Mismatch {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+        value: u64,
+        expected: u64,
+    },
+    // `value` of `field` doesn't point at a `target`
+    BadReference {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+        value: u64,
+        target: &'static str,
+    },
+    // entries of a table which should be sorted by `field` aren't
+    Unsorted {
+        offset: usize,
+        structure: Structure,
+        field: &'static str,
+    },
+    // compressed data which can't be decompressed, `reason` comes from the
+    // decompressor or is a size mismatch
+    Decompression {
+        offset: usize,
+        structure: Structure,
+        reason: String,
+    },
+}
+
+impl ElfError {
+    // file offset of the structure the error is about
+    pub fn offset(&self) -> usize {
+        match self {
+            ElfError::Truncated { offset, .. }
+            | ElfError::BadClass { offset, .. }
+            | ElfError::BadEncoding { offset, .. }
+            | ElfError::FieldOverflow { offset, .. }
+            | ElfError::OutOfBounds { offset, .. }
+            | ElfError::BadEntrySize { offset, .. }
+            | ElfError::UnknownValue { offset, .. }
+            | ElfError::Mismatch { offset, .. }
+            | ElfError::BadReference { offset, .. }
+            | ElfError::Unsorted { offset, .. }
+            | ElfError::Decompression { offset, .. } => *offset,
+            ElfError::BadMagic { .. } => 0,
+        }
+    }
+}
+
+impl fmt::Display for Structure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Structure::Ident => write!(f, "e_ident"),
+            Structure::FileHeader => write!(f, "file header"),
+            Structure::ProgramHeader(idx) => write!(f, "program header {}", idx),
+            Structure::SectionHeader(idx) => write!(f, "section header {}", idx),
+            Structure::Symbol(section, idx) => write!(f, "symbol {} of section {}", idx, section),
+            Structure::Relocation(section, idx) => {
+                write!(f, "relocation {} of section {}", idx, section)
+            }
+            Structure::DynamicEntry(idx) => write!(f, "dynamic entry {}", idx),
+            Structure::Note => write!(f, "note"),
+            Structure::VersionRecord(section, idx) => {
+                write!(f, "version record {} of section {}", idx, section)
+            }
+            Structure::HashTable(section) => write!(f, "hash table of section {}", section),
+            Structure::DwarfUnit(idx) => write!(f, "DWARF unit {}", idx),
+            Structure::LineProgram(idx) => write!(f, "line-number program {}", idx),
+            Structure::FrameRecord(idx) => write!(f, "call frame record {}", idx),
+            Structure::EhFrameHdr => write!(f, ".eh_frame_hdr"),
+            Structure::CompressedSection(idx) => write!(f, "compressed section {}", idx),
+        }
+    }
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElfError::Truncated {
+                offset,
+                structure,
+                size,
+                available,
+            } => write!(
+                f,
+                "{} at {:#x} is truncated: needs {} bytes, {} available",
+                structure, offset, size, available
+            ),
+            ElfError::BadMagic { magic } => write!(
+                f,
+                "not an ELF file: magic is {:02x} {:02x} {:02x} {:02x}",
+                magic[0], magic[1], magic[2], magic[3]
+            ),
+            ElfError::BadClass { offset, class } => {
+                write!(f, "unknown class {} at {:#x}", class, offset)
+            }
+            ElfError::BadEncoding { offset, encoding } => {
+                write!(f, "unknown data encoding {} at {:#x}", encoding, offset)
+            }
+            ElfError::FieldOverflow {
+                offset,
+                structure,
+                field,
+            } => write!(
+                f,
+                "{} of {} at {:#x} is too large for this platform",
+                field, structure, offset
+            ),
+            ElfError::OutOfBounds {
+                offset,
+                structure,
+                field,
+                value,
+                limit,
+            } => write!(
+                f,
+                "{} of {} at {:#x} is out of bounds: {:#x} (limit {:#x})",
+                field, structure, offset, value, limit
+            ),
+            ElfError::BadEntrySize {
+                offset,
+                structure,
+                field,
+                size,
+                expected,
+            } => write!(
+                f,
+                "{} of {} at {:#x} is {} instead of {}",
+                field, structure, offset, size, expected
+            ),
+            ElfError::UnknownValue {
+                offset,
+                structure,
+                field,
+                value,
+            } => write!(
+                f,
+                "{} of {} at {:#x} has an unknown value {:#x}",
+                field, structure, offset, value
+            ),
+            ElfError::Mismatch {
+                offset,
+                structure,
+                field,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{} of {} at {:#x} is {:#x} instead of {:#x}",
+                field, structure, offset, value, expected
+            ),
+            ElfError::BadReference {
+                offset,
+                structure,
+                field,
+                value,
+                target,
+            } => write!(
+                f,
+                "{} of {} at {:#x} doesn't point at {}: {:#x}",
+                field, structure, offset, target, value
+            ),
+            ElfError::Unsorted {
+                offset,
+                structure,
+                field,
+            } => write!(
+                f,
+                "{} at {:#x} isn't sorted by {}",
+                structure, offset, field
+            ),
+            ElfError::Decompression {
+                offset,
+                structure,
+                reason,
+            } => write!(
+                f,
+                "{} at {:#x} can't be decompressed: {}",
+                structure, offset, reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {}
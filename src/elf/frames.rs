@@ -0,0 +1,1236 @@
+use super::defs::*;
+use super::dwarf::{cstr_at, format_block, sleb, uleb};
+use super::error::{ElfError, Structure};
+use super::notes::Reader;
+use super::parser::{
+    CfaOp, Cie, EhFrameHdr, Fde, FrameEntry, FrameRecord, ParsedElf, ParsedIdent, RangeType,
+};
+use std::collections::HashMap;
+
+// name (also the class of its range), offset in the area and size of a field
+type Part = (&'static str, usize, usize);
+
+// contents of .eh_frame or .eh_frame_hdr, from their section or found
+// through the program headers of a file without them
+struct Area<'a> {
+    reader: Reader,
+    data: &'a [u8],
+    offset: usize,
+    // of the first byte, pc-relative pointers are relative to their own one
+    address: u64,
+    // values of a relocatable object to read instead, by offset in `data`
+    relocations: HashMap<usize, u64>,
+}
+
+// why a record couldn't be read to its end
+enum Stop {
+    Truncated,
+    // `field` has a value this parser doesn't know how to interpret
+    Unknown(&'static str, u64),
+    // the CIE pointer of an FDE
+    BadCie(u64),
+}
+
+impl<'a> Area<'a> {
+    fn uint(&self, pos: &mut usize, end: usize, size: usize) -> Option<u64> {
+        if pos.checked_add(size)? > end {
+            return None;
+        }
+
+        let value = self.reader.uint(self.data, *pos, size)?;
+
+        *pos += size;
+
+        Some(value)
+    }
+
+    fn uleb(&self, pos: &mut usize, end: usize) -> Option<u64> {
+        uleb(self.data.get(..end)?, pos)
+    }
+
+    fn sleb(&self, pos: &mut usize, end: usize) -> Option<i64> {
+        sleb(self.data.get(..end)?, pos)
+    }
+
+    fn block(&self, pos: &mut usize, end: usize) -> Option<&'a [u8]> {
+        let len = self.uleb(pos, end)? as usize;
+        let block_end = pos.checked_add(len).filter(|&block_end| block_end <= end)?;
+        let block = &self.data[*pos..block_end];
+
+        *pos = block_end;
+
+        Some(block)
+    }
+
+    // a pointer of a known DW_EH_PE_* `encoding`, datarel ones are relative
+    // to `data_base`
+    fn pointer(&self, pos: &mut usize, end: usize, encoding: u8, data_base: u64) -> Option<u64> {
+        let start = *pos;
+        let sign_extend = |value: u64, size: u32| {
+            let shift = 64 - size * 8;
+
+            ((value << shift) as i64 >> shift) as u64
+        };
+        let value = match encoding & 0x0f {
+            DW_EH_PE_ABSPTR => self.uint(pos, end, self.reader.word)?,
+            DW_EH_PE_ULEB128 => self.uleb(pos, end)?,
+            DW_EH_PE_UDATA2 => self.uint(pos, end, 2)?,
+            DW_EH_PE_UDATA4 => self.uint(pos, end, 4)?,
+            DW_EH_PE_UDATA8 => self.uint(pos, end, 8)?,
+            DW_EH_PE_SLEB128 => self.sleb(pos, end)? as u64,
+            DW_EH_PE_SDATA2 => sign_extend(self.uint(pos, end, 2)?, 2),
+            DW_EH_PE_SDATA4 => sign_extend(self.uint(pos, end, 4)?, 4),
+            DW_EH_PE_SDATA8 => self.uint(pos, end, 8)?,
+            _ => return None,
+        };
+
+        // the relocation already resolved the pointer to an address
+        if let Some(&value) = self.relocations.get(&start) {
+            return Some(value);
+        }
+
+        let base = match encoding & 0x70 {
+            DW_EH_PE_PCREL => self.address.wrapping_add(start as u64),
+            DW_EH_PE_DATAREL => data_base,
+            _ => 0,
+        };
+        let value = base.wrapping_add(value);
+
+        if self.reader.word == 4 {
+            Some(value & 0xffff_ffff)
+        } else {
+            Some(value)
+        }
+    }
+
+    // reads a fixed-size field and records its part
+    fn field(
+        &self,
+        parts: &mut Vec<Part>,
+        name: &'static str,
+        pos: &mut usize,
+        end: usize,
+        size: usize,
+    ) -> Option<u64> {
+        let value = self.uint(pos, end, size)?;
+
+        parts.push((name, *pos - size, size));
+
+        Some(value)
+    }
+
+    fn contains(&self, address: u64) -> Option<usize> {
+        let offset = address.checked_sub(self.address)? as usize;
+
+        Some(offset).filter(|&offset| offset < self.data.len())
+    }
+}
+
+fn known_encoding(encoding: u8) -> bool {
+    let format = matches!(
+        encoding & 0x0f,
+        DW_EH_PE_ABSPTR
+            | DW_EH_PE_ULEB128
+            | DW_EH_PE_UDATA2
+            | DW_EH_PE_UDATA4
+            | DW_EH_PE_UDATA8
+            | DW_EH_PE_SLEB128
+            | DW_EH_PE_SDATA2
+            | DW_EH_PE_SDATA4
+            | DW_EH_PE_SDATA8
+    );
+
+    encoding == DW_EH_PE_OMIT || (format && encoding & 0x70 <= DW_EH_PE_ALIGNED)
+}
+
+// of pointers which can be in a binary search table
+fn encoding_size(encoding: u8, word: usize) -> Option<usize> {
+    match encoding & 0x0f {
+        DW_EH_PE_ABSPTR => Some(word),
+        DW_EH_PE_UDATA2 | DW_EH_PE_SDATA2 => Some(2),
+        DW_EH_PE_UDATA4 | DW_EH_PE_SDATA4 => Some(4),
+        DW_EH_PE_UDATA8 | DW_EH_PE_SDATA8 => Some(8),
+        _ => None,
+    }
+}
+
+fn known_cfa(opcode: u8) -> bool {
+    opcode & 0xc0 != 0
+        || opcode <= DW_CFA_VAL_EXPRESSION
+        || (DW_CFA_GNU_WINDOW_SAVE..=DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED).contains(&opcode)
+}
+
+fn cstr(area: &Area, parts: &mut Vec<Part>, pos: &mut usize, end: usize) -> Option<String> {
+    let string = cstr_at(area.data.get(..end), *pos as u64)?;
+
+    // the terminator has to be there too
+    if *pos + string.len() >= end {
+        return None;
+    }
+
+    parts.push(("augmentation", *pos, string.len() + 1));
+    *pos += string.len() + 1;
+
+    Some(string)
+}
+
+// the fields after the CIE id, returns where the instructions start
+fn read_cie(
+    area: &Area,
+    pos: &mut usize,
+    end: usize,
+    cie: &mut Cie,
+    parts: &mut Vec<Part>,
+) -> Result<usize, Stop> {
+    cie.version = area
+        .field(parts, "cie_version", pos, end, 1)
+        .ok_or(Stop::Truncated)? as u8;
+
+    if !matches!(cie.version, 1 | 3 | 4) {
+        return Err(Stop::Unknown("cie_version", cie.version as u64));
+    }
+
+    cie.augmentation = cstr(area, parts, pos, end).ok_or(Stop::Truncated)?;
+
+    if cie.augmentation.starts_with("eh") {
+        area.field(parts, "eh_data", pos, end, area.reader.word)
+            .ok_or(Stop::Truncated)?;
+    }
+
+    if cie.version >= 4 {
+        area.field(parts, "cie_addrsize", pos, end, 1)
+            .ok_or(Stop::Truncated)?;
+        area.field(parts, "cie_segsize", pos, end, 1)
+            .ok_or(Stop::Truncated)?;
+    }
+
+    let start = *pos;
+
+    cie.code_alignment = area.uleb(pos, end).ok_or(Stop::Truncated)?;
+    parts.push(("code_align", start, *pos - start));
+
+    let start = *pos;
+
+    cie.data_alignment = area.sleb(pos, end).ok_or(Stop::Truncated)?;
+    parts.push(("data_align", start, *pos - start));
+
+    let start = *pos;
+
+    cie.return_register = if cie.version == 1 {
+        area.uint(pos, end, 1)
+    } else {
+        area.uleb(pos, end)
+    }
+    .ok_or(Stop::Truncated)?;
+    parts.push(("return_reg", start, *pos - start));
+
+    if !cie.augmentation.starts_with('z') {
+        // the instructions can't be found past unknown augmentation data,
+        // there are none to decode then
+        return match cie.augmentation.as_str() {
+            "" | "eh" => Ok(*pos),
+            _ => Ok(end),
+        };
+    }
+
+    let start = *pos;
+    let aug_length = area.uleb(pos, end).ok_or(Stop::Truncated)?;
+    let aug_start = *pos;
+    let aug_end = pos
+        .checked_add(aug_length as usize)
+        .filter(|&aug_end| aug_end <= end)
+        .ok_or(Stop::Truncated)?;
+
+    parts.push(("aug_length", start, aug_start - start));
+
+    if aug_end > aug_start {
+        parts.push(("aug_data", aug_start, aug_end - aug_start));
+    }
+
+    for c in cie.augmentation.chars().skip(1) {
+        let encoding = match c {
+            'L' | 'P' | 'R' => area.uint(pos, aug_end, 1).ok_or(Stop::Truncated)? as u8,
+            _ => continue,
+        };
+
+        if !known_encoding(encoding) {
+            return Err(Stop::Unknown("aug_data", encoding as u64));
+        }
+
+        match c {
+            'L' => cie.lsda_encoding = encoding,
+            'R' => cie.fde_encoding = encoding,
+            _ => {
+                cie.personality = Some(
+                    area.pointer(pos, aug_end, encoding, 0)
+                        .ok_or(Stop::Truncated)?,
+                )
+            }
+        }
+    }
+
+    Ok(aug_end)
+}
+
+// the fields after the CIE pointer, returns where the instructions start
+fn read_fde(
+    area: &Area,
+    pos: &mut usize,
+    end: usize,
+    cie: &Cie,
+    fde: &mut Fde,
+    parts: &mut Vec<Part>,
+) -> Result<usize, Stop> {
+    let start = *pos;
+
+    fde.pc_begin = area
+        .pointer(pos, end, cie.fde_encoding, 0)
+        .ok_or(Stop::Truncated)?;
+    parts.push(("pc_begin", start, *pos - start));
+
+    let start = *pos;
+
+    // only the format applies to the range
+    fde.pc_range = area
+        .pointer(pos, end, cie.fde_encoding & 0x0f, 0)
+        .ok_or(Stop::Truncated)?;
+    parts.push(("pc_range", start, *pos - start));
+
+    if !cie.augmentation.starts_with('z') {
+        return Ok(*pos);
+    }
+
+    let start = *pos;
+    let aug_length = area.uleb(pos, end).ok_or(Stop::Truncated)?;
+    let aug_end = pos
+        .checked_add(aug_length as usize)
+        .filter(|&aug_end| aug_end <= end)
+        .ok_or(Stop::Truncated)?;
+
+    parts.push(("aug_length", start, *pos - start));
+
+    if cie.augmentation.contains('L') && cie.lsda_encoding != DW_EH_PE_OMIT {
+        let start = *pos;
+
+        fde.lsda = Some(
+            area.pointer(pos, aug_end, cie.lsda_encoding, 0)
+                .ok_or(Stop::Truncated)?,
+        );
+        parts.push(("lsda", start, *pos - start));
+    }
+
+    Ok(aug_end)
+}
+
+fn read_hdr_encodings(
+    area: &Area,
+    hdr: &mut EhFrameHdr,
+    parts: &mut Vec<Part>,
+    pos: &mut usize,
+) -> Option<()> {
+    let end = area.data.len();
+
+    hdr.version = area.field(parts, "eh_version", pos, end, 1)? as u8;
+    hdr.eh_frame_ptr_encoding = area.field(parts, "eh_ptr_enc", pos, end, 1)? as u8;
+    hdr.fde_count_encoding = area.field(parts, "count_enc", pos, end, 1)? as u8;
+    hdr.table_encoding = area.field(parts, "table_enc", pos, end, 1)? as u8;
+
+    Some(())
+}
+
+// pointers of .eh_frame_hdr are relative to its start, Some(None) if omitted
+fn hdr_pointer(
+    area: &Area,
+    parts: &mut Vec<Part>,
+    name: &'static str,
+    pos: &mut usize,
+    encoding: u8,
+) -> Option<Option<u64>> {
+    let start = *pos;
+
+    if encoding == DW_EH_PE_OMIT {
+        return Some(None);
+    }
+
+    let value = area.pointer(pos, area.data.len(), encoding, area.address)?;
+
+    parts.push((name, start, *pos - start));
+
+    Some(Some(value))
+}
+
+fn advance(location: &mut u64, delta: u64, cie: &Cie) -> String {
+    let delta = delta.wrapping_mul(cie.code_alignment);
+
+    *location = location.wrapping_add(delta);
+
+    format!("{} to {:#x}", delta, location)
+}
+
+// decodes the instruction at `pos` whose opcode is known, returns its
+// operands or None if it goes past `end`
+fn cfa_op(
+    area: &Area,
+    pos: &mut usize,
+    end: usize,
+    machine: u16,
+    cie: &Cie,
+    location: &mut u64,
+) -> Option<String> {
+    let opcode = area.uint(pos, end, 1)? as u8;
+    let register = |register| dwarf_register_to_string(machine, register);
+    let factored = |offset: i64| offset.wrapping_mul(cie.data_alignment);
+
+    let operands = match opcode & 0xc0 {
+        DW_CFA_ADVANCE_LOC => advance(location, (opcode & 0x3f) as u64, cie),
+        DW_CFA_OFFSET => {
+            let offset = factored(area.uleb(pos, end)? as i64);
+
+            format!("{} at cfa{:+}", register((opcode & 0x3f) as u64), offset)
+        }
+        DW_CFA_RESTORE => register((opcode & 0x3f) as u64),
+        _ => match opcode {
+            DW_CFA_SET_LOC => {
+                *location = area.pointer(pos, end, cie.fde_encoding, 0)?;
+
+                format!("to {:#x}", location)
+            }
+            DW_CFA_ADVANCE_LOC1 => advance(location, area.uint(pos, end, 1)?, cie),
+            DW_CFA_ADVANCE_LOC2 => advance(location, area.uint(pos, end, 2)?, cie),
+            DW_CFA_ADVANCE_LOC4 => advance(location, area.uint(pos, end, 4)?, cie),
+            DW_CFA_OFFSET_EXTENDED => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} at cfa{:+}", reg, factored(area.uleb(pos, end)? as i64))
+            }
+            DW_CFA_OFFSET_EXTENDED_SF => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} at cfa{:+}", reg, factored(area.sleb(pos, end)?))
+            }
+            DW_CFA_GNU_NEGATIVE_OFFSET_EXTENDED => {
+                let reg = register(area.uleb(pos, end)?);
+                let offset = factored((area.uleb(pos, end)? as i64).wrapping_neg());
+
+                format!("{} at cfa{:+}", reg, offset)
+            }
+            DW_CFA_VAL_OFFSET => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} is cfa{:+}", reg, factored(area.uleb(pos, end)? as i64))
+            }
+            DW_CFA_VAL_OFFSET_SF => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} is cfa{:+}", reg, factored(area.sleb(pos, end)?))
+            }
+            DW_CFA_RESTORE_EXTENDED
+            | DW_CFA_UNDEFINED
+            | DW_CFA_SAME_VALUE
+            | DW_CFA_DEF_CFA_REGISTER => register(area.uleb(pos, end)?),
+            DW_CFA_REGISTER => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} in {}", reg, register(area.uleb(pos, end)?))
+            }
+            DW_CFA_DEF_CFA => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} ofs {}", reg, area.uleb(pos, end)?)
+            }
+            DW_CFA_DEF_CFA_SF => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{} ofs {}", reg, factored(area.sleb(pos, end)?))
+            }
+            DW_CFA_DEF_CFA_OFFSET | DW_CFA_GNU_ARGS_SIZE => format!("{}", area.uleb(pos, end)?),
+            DW_CFA_DEF_CFA_OFFSET_SF => format!("{}", factored(area.sleb(pos, end)?)),
+            DW_CFA_DEF_CFA_EXPRESSION => format_block(area.block(pos, end)?),
+            DW_CFA_EXPRESSION | DW_CFA_VAL_EXPRESSION => {
+                let reg = register(area.uleb(pos, end)?);
+
+                format!("{}: {}", reg, format_block(area.block(pos, end)?))
+            }
+            _ => String::new(),
+        },
+    };
+
+    Some(operands)
+}
+
+impl<'a> ParsedElf<'a> {
+    pub(super) fn parse_eh_frame(&mut self, ident: &ParsedIdent) {
+        let reader = Reader::new(ident.endianness, ident.class);
+        let hdr_area = self.eh_frame_hdr_area(reader);
+
+        if let Some(area) = hdr_area.as_ref() {
+            self.eh_frame_hdr = self.parse_eh_frame_hdr(area);
+        }
+
+        let eh_frame_ptr = self.eh_frame_hdr.as_ref().and_then(|hdr| hdr.eh_frame_ptr);
+        let area = match self.eh_frame_area(reader, eh_frame_ptr) {
+            Some(area) => area,
+            None => return,
+        };
+        let mut cies = HashMap::new();
+        let mut offset = 0;
+
+        while offset < area.data.len() {
+            offset = match self.parse_frame_record(&area, offset, &mut cies) {
+                Some(end) => end,
+                None => break,
+            };
+        }
+
+        if let Some(hdr_area) = hdr_area {
+            self.check_eh_frame_hdr(&hdr_area, &area);
+        }
+    }
+
+    fn eh_frame_hdr_area(&self, reader: Reader) -> Option<Area<'a>> {
+        if let Some((idx, data)) = self.section_by_name(".eh_frame_hdr") {
+            return Some(Area {
+                reader,
+                data,
+                offset: self.shdrs[idx].file_offset,
+                address: self.shdrs[idx].addr as u64,
+                relocations: HashMap::new(),
+            });
+        }
+
+        let phdr = self
+            .phdrs
+            .iter()
+            .find(|phdr| phdr.ptype == PT_GNU_EH_FRAME)?;
+        let contents: &'a [u8] = self.contents;
+
+        Some(Area {
+            reader,
+            data: contents.get(phdr.file_offset..phdr.file_offset.checked_add(phdr.file_size)?)?,
+            offset: phdr.file_offset,
+            address: phdr.vaddr as u64,
+            relocations: HashMap::new(),
+        })
+    }
+
+    // without a section, .eh_frame goes from where .eh_frame_hdr points to
+    // the terminator, at the latest the end of its LOAD segment
+    fn eh_frame_area(&self, reader: Reader, eh_frame_ptr: Option<u64>) -> Option<Area<'a>> {
+        if let Some((idx, data)) = self.section_by_name(".eh_frame") {
+            return Some(Area {
+                reader,
+                data,
+                offset: self.shdrs[idx].file_offset,
+                address: self.shdrs[idx].addr as u64,
+                relocations: self.debug_relocations(idx),
+            });
+        }
+
+        let address = eh_frame_ptr? as usize;
+        let (offset, phdr) = self.vaddr_mapping(address)?;
+        let end = phdr.file_offset.checked_add(phdr.file_size)?;
+        let contents: &'a [u8] = self.contents;
+
+        Some(Area {
+            reader,
+            data: contents.get(offset..end)?,
+            offset,
+            address: address as u64,
+            relocations: HashMap::new(),
+        })
+    }
+
+    fn add_frame_parts(&mut self, area: &Area, parts: Vec<Part>, end: usize) {
+        for (name, part_offset, part_size) in parts.into_iter() {
+            if part_offset + part_size <= end {
+                self.ranges.add_range(
+                    area.offset + part_offset,
+                    part_size,
+                    RangeType::FrameField(name),
+                );
+            }
+        }
+    }
+
+    fn parse_eh_frame_hdr(&mut self, area: &Area) -> Option<EhFrameHdr> {
+        let end = area.data.len();
+        let mut parts = vec![];
+        let mut pos = 0;
+        let mut hdr = EhFrameHdr {
+            offset: area.offset,
+            size: end,
+            address: area.address,
+            version: 0,
+            eh_frame_ptr_encoding: DW_EH_PE_OMIT,
+            fde_count_encoding: DW_EH_PE_OMIT,
+            table_encoding: DW_EH_PE_OMIT,
+            eh_frame_ptr: None,
+            fde_count: None,
+            table_offset: 0,
+            table: vec![],
+        };
+        let truncated = |size| ElfError::Truncated {
+            offset: area.offset,
+            structure: Structure::EhFrameHdr,
+            size,
+            available: end,
+        };
+
+        if read_hdr_encodings(area, &mut hdr, &mut parts, &mut pos).is_none() {
+            self.add_frame_parts(area, parts, end);
+            self.diagnose(truncated(4), Some((area.offset, end)));
+
+            return None;
+        }
+
+        let unknown = if hdr.version != 1 {
+            Some(("eh_version", hdr.version))
+        } else {
+            [
+                ("eh_ptr_enc", hdr.eh_frame_ptr_encoding),
+                ("count_enc", hdr.fde_count_encoding),
+                ("table_enc", hdr.table_encoding),
+            ]
+            .iter()
+            .copied()
+            .find(|&(_, encoding)| !known_encoding(encoding))
+        };
+
+        if let Some((field, value)) = unknown {
+            let error = ElfError::UnknownValue {
+                offset: area.offset,
+                structure: Structure::EhFrameHdr,
+                field,
+                value: value as u64,
+            };
+
+            self.add_frame_parts(area, parts, end);
+            self.diagnose_field(error, area.offset, end, field);
+
+            return Some(hdr);
+        }
+
+        let encoding = hdr.eh_frame_ptr_encoding;
+        let eh_frame_ptr = hdr_pointer(area, &mut parts, "eh_frame_ptr", &mut pos, encoding);
+        let encoding = hdr.fde_count_encoding;
+        let fde_count = eh_frame_ptr
+            .and_then(|_| hdr_pointer(area, &mut parts, "fde_count", &mut pos, encoding));
+
+        self.add_frame_parts(area, parts, end);
+        hdr.eh_frame_ptr = eh_frame_ptr.flatten();
+
+        match fde_count {
+            Some(fde_count) => hdr.fde_count = fde_count,
+            None => {
+                self.diagnose(truncated(pos + 1), Some((area.offset, end)));
+
+                return Some(hdr);
+            }
+        }
+
+        let entry_size = match (hdr.fde_count, hdr.table_encoding) {
+            (_, DW_EH_PE_OMIT) | (None, _) => return Some(hdr),
+            (Some(_), encoding) => encoding_size(encoding, area.reader.word),
+        };
+        let entry_size = match entry_size {
+            Some(size) => size * 2,
+            None => {
+                let error = ElfError::UnknownValue {
+                    offset: area.offset,
+                    structure: Structure::EhFrameHdr,
+                    field: "table_enc",
+                    value: hdr.table_encoding as u64,
+                };
+
+                self.diagnose_field(error, area.offset, end, "table_enc");
+
+                return Some(hdr);
+            }
+        };
+        let count = hdr.fde_count.unwrap_or(0);
+
+        hdr.table_offset = area.offset + pos;
+
+        let available = (end - pos) / entry_size;
+
+        if count > available as u64 {
+            let needed = (count as usize).saturating_mul(entry_size);
+
+            self.diagnose(
+                truncated(pos.saturating_add(needed)),
+                Some((area.offset + pos, end - pos)),
+            );
+        }
+
+        for i in 0..(count as usize).min(available) {
+            let start = pos;
+            let initial_location = area.pointer(&mut pos, end, hdr.table_encoding, area.address);
+            let fde = area.pointer(&mut pos, end, hdr.table_encoding, area.address);
+
+            if let (Some(initial_location), Some(fde)) = (initial_location, fde) {
+                hdr.table.push((initial_location, fde));
+            }
+
+            self.ranges.add_range(
+                area.offset + start,
+                entry_size,
+                RangeType::FrameHdrEntry(i as u32),
+            );
+        }
+
+        Some(hdr)
+    }
+
+    // adds the ranges of the CIE or FDE at `offset` of .eh_frame, returns
+    // where it ends or None if it's the terminator or can't be read
+    fn parse_frame_record(
+        &mut self,
+        area: &Area,
+        offset: usize,
+        cies: &mut HashMap<usize, usize>,
+    ) -> Option<usize> {
+        let record = self.frame_records.len() as u32;
+        let structure = Structure::FrameRecord(record);
+        let start = area.offset + offset;
+        let available = area.data.len() - offset;
+        let truncated = |size| ElfError::Truncated {
+            offset: start,
+            structure,
+            size,
+            available,
+        };
+        let mut pos = offset;
+        let mut parts = vec![];
+        let limit = area.data.len();
+
+        let (format64, length) = match area.field(&mut parts, "cfi_length", &mut pos, limit, 4) {
+            Some(0xffff_ffff) => (
+                true,
+                area.field(&mut parts, "cfi_len64", &mut pos, limit, 8),
+            ),
+            length => (false, length),
+        };
+        let length = match length {
+            Some(0) => {
+                self.ranges
+                    .add_range(start, 4, RangeType::FrameField("terminator"));
+
+                return None;
+            }
+            Some(length) => length as usize,
+            None => {
+                self.diagnose(truncated(pos - offset + 1), Some((start, available)));
+
+                return None;
+            }
+        };
+        let length_end = pos;
+        let end = length_end.saturating_add(length).min(limit);
+        let size = end - offset;
+        let id_size = if format64 { 8 } else { 4 };
+        let id = area.uint(&mut pos, end, id_size);
+        let mut entry = match id {
+            Some(0) => FrameEntry::Cie(Cie {
+                version: 0,
+                augmentation: String::new(),
+                code_alignment: 1,
+                data_alignment: 1,
+                return_register: 0,
+                fde_encoding: DW_EH_PE_ABSPTR,
+                lsda_encoding: DW_EH_PE_OMIT,
+                personality: None,
+            }),
+            // the CIE pointer is relative to itself
+            _ => FrameEntry::Fde(Fde {
+                cie: id
+                    .and_then(|id| length_end.checked_sub(id as usize))
+                    .and_then(|cie| cies.get(&cie).copied()),
+                pc_begin: 0,
+                pc_range: 0,
+                lsda: None,
+            }),
+        };
+
+        if id.is_some() {
+            let name = match entry {
+                FrameEntry::Cie(_) => "cie_id",
+                FrameEntry::Fde(_) => "cie_pointer",
+            };
+
+            parts.push((name, length_end, id_size));
+        }
+
+        let result = match (id, &mut entry) {
+            (None, _) => Err(Stop::Truncated),
+            (Some(_), FrameEntry::Cie(cie)) => read_cie(area, &mut pos, end, cie, &mut parts),
+            (Some(id), FrameEntry::Fde(fde)) => match fde.cie {
+                Some(cie) => match &self.frame_records[cie].entry {
+                    FrameEntry::Cie(cie) => read_fde(area, &mut pos, end, cie, fde, &mut parts),
+                    FrameEntry::Fde(_) => Err(Stop::BadCie(id)),
+                },
+                None => Err(Stop::BadCie(id)),
+            },
+        };
+
+        if length_end.saturating_add(length) > limit {
+            let needed = (length_end - offset).saturating_add(length);
+
+            self.diagnose(truncated(needed), Some((start, size)));
+        }
+
+        self.ranges
+            .add_range(start, size, RangeType::FrameRecord(record));
+        self.add_frame_parts(area, parts, end);
+
+        let mut record_entry = FrameRecord {
+            offset: start,
+            size,
+            address: area.address.wrapping_add(offset as u64),
+            entry,
+            instructions: vec![],
+        };
+        let instructions_start = match result {
+            Ok(instructions_start) => Some(instructions_start),
+            Err(Stop::Truncated) => {
+                self.diagnose(truncated(pos - offset + 1), Some((start, size)));
+                None
+            }
+            Err(Stop::BadCie(id)) => {
+                let error = ElfError::BadReference {
+                    offset: start,
+                    structure,
+                    field: "cie_pointer",
+                    value: id,
+                    target: "a CIE",
+                };
+
+                self.diagnose_field(error, start, size, "cie_pointer");
+                None
+            }
+            Err(Stop::Unknown(field, value)) => {
+                let error = ElfError::UnknownValue {
+                    offset: start,
+                    structure,
+                    field,
+                    value,
+                };
+
+                self.diagnose_field(error, start, size, field);
+                None
+            }
+        };
+
+        if let FrameEntry::Cie(_) = record_entry.entry {
+            cies.insert(offset, record as usize);
+        }
+
+        if let Some(instructions_start) = instructions_start {
+            record_entry.instructions =
+                self.parse_cfa_ops(area, &record_entry, instructions_start, end);
+        }
+
+        self.frame_records.push(record_entry);
+
+        Some(end)
+    }
+
+    fn parse_cfa_ops(
+        &mut self,
+        area: &Area,
+        record_entry: &FrameRecord,
+        mut pos: usize,
+        end: usize,
+    ) -> Vec<CfaOp> {
+        let record = self.frame_records.len() as u32;
+        let (cie, mut location) = match &record_entry.entry {
+            FrameEntry::Cie(cie) => (cie, 0),
+            FrameEntry::Fde(fde) => match fde.cie.map(|cie| &self.frame_records[cie].entry) {
+                Some(FrameEntry::Cie(cie)) => (cie, fde.pc_begin),
+                _ => return vec![],
+            },
+        };
+        let mut ops = vec![];
+        let mut error = None;
+
+        while pos < end {
+            let op_start = pos;
+            let opcode = area.data[pos];
+
+            if !known_cfa(opcode) {
+                error = Some(ElfError::UnknownValue {
+                    offset: area.offset + op_start,
+                    structure: Structure::FrameRecord(record),
+                    field: "cfa_op",
+                    value: opcode as u64,
+                });
+                break;
+            }
+
+            let operands = match cfa_op(area, &mut pos, end, self.machine, cie, &mut location) {
+                Some(operands) => operands,
+                None => {
+                    error = Some(ElfError::Truncated {
+                        offset: area.offset + op_start,
+                        structure: Structure::FrameRecord(record),
+                        size: end - op_start + 1,
+                        available: end - op_start,
+                    });
+                    break;
+                }
+            };
+
+            ops.push(CfaOp {
+                offset: area.offset + op_start,
+                size: pos - op_start,
+                name: dwarf_cfa_to_string(opcode),
+                operands,
+            });
+        }
+
+        for (i, op) in ops.iter().enumerate() {
+            self.ranges
+                .add_range(op.offset, op.size, RangeType::CfaOp(record, i as u32));
+        }
+
+        if let Some(error) = error {
+            let offset = error.offset();
+
+            self.diagnose(error, Some((offset, area.offset + end - offset)));
+        }
+
+        ops
+    }
+
+    // the table has to be sorted and point at the FDEs of .eh_frame
+    fn check_eh_frame_hdr(&mut self, hdr_area: &Area, area: &Area) {
+        let hdr = match self.eh_frame_hdr.as_ref() {
+            Some(hdr) => hdr,
+            None => return,
+        };
+        // pc_begin isn't known for FDEs without a CIE
+        let fdes: HashMap<usize, Option<u64>> = self
+            .frame_records
+            .iter()
+            .filter_map(|record| match &record.entry {
+                FrameEntry::Fde(fde) => Some((record.offset, fde.cie.map(|_| fde.pc_begin))),
+                FrameEntry::Cie(_) => None,
+            })
+            .collect();
+        let hdr_location = (hdr_area.offset, hdr_area.data.len());
+        let field_location = |field| {
+            self.ranges
+                .lookup_field(hdr_location.0, hdr_location.1, field)
+        };
+        let entry_size = encoding_size(hdr.table_encoding, hdr_area.reader.word).unwrap_or(0) * 2;
+        let entry_location = |i| Some((hdr.table_offset + i * entry_size, entry_size));
+        let mismatch = |field, value, expected| ElfError::Mismatch {
+            offset: hdr_area.offset,
+            structure: Structure::EhFrameHdr,
+            field,
+            value,
+            expected,
+        };
+        let mut errors = vec![];
+
+        match hdr.eh_frame_ptr {
+            Some(ptr) if ptr != area.address => errors.push((
+                mismatch("eh_frame_ptr", ptr, area.address),
+                field_location("eh_frame_ptr"),
+            )),
+            _ => {}
+        }
+
+        match hdr.fde_count {
+            Some(count) if count != fdes.len() as u64 => errors.push((
+                mismatch("fde_count", count, fdes.len() as u64),
+                field_location("fde_count"),
+            )),
+            _ => {}
+        }
+
+        let unsorted = (1..hdr.table.len()).find(|&i| hdr.table[i].0 < hdr.table[i - 1].0);
+
+        if let Some(i) = unsorted {
+            let error = ElfError::Unsorted {
+                offset: hdr_area.offset,
+                structure: Structure::EhFrameHdr,
+                field: "initial location",
+            };
+
+            errors.push((error, entry_location(i)));
+        }
+
+        for (i, &(initial_location, fde)) in hdr.table.iter().enumerate() {
+            let offset = area.contains(fde).map(|offset| area.offset + offset);
+            let error = match offset.and_then(|offset| fdes.get(&offset)) {
+                Some(&Some(pc_begin)) if pc_begin != initial_location => {
+                    mismatch("initial location", initial_location, pc_begin)
+                }
+                Some(_) => continue,
+                None => ElfError::BadReference {
+                    offset: hdr_area.offset,
+                    structure: Structure::EhFrameHdr,
+                    field: "FDE address",
+                    value: fde,
+                    target: "an FDE",
+                },
+            };
+
+            errors.push((error, entry_location(i)));
+        }
+
+        for (error, location) in errors.into_iter() {
+            self.diagnose(error, location);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_elf::{build, section, Bytes, Section};
+    use super::*;
+
+    const HDR_ADDRESS: u64 = 0x2000;
+    const EH_FRAME_ADDRESS: u64 = 0x2100;
+
+    // a "zR" CIE with pc-relative sdata4 pointers, then an FDE for
+    // 0x1000..0x1020 whose CIE pointer is `cie_pointer` and the terminator
+    fn eh_frame(name: &'static str, cie_pointer: u32) -> Section {
+        let pc_begin = 0x1000 - (EH_FRAME_ADDRESS as i64 + 32);
+        let data = Bytes::default()
+            .u32(20)
+            .u32(0)
+            .u8(1)
+            .cstr("zR")
+            .uleb(1)
+            .sleb(-8)
+            .u8(16)
+            .uleb(1)
+            .u8(DW_EH_PE_PCREL | DW_EH_PE_SDATA4)
+            .raw(&[DW_CFA_DEF_CFA, 7, 8, DW_CFA_OFFSET | 16, 1, 0, 0])
+            .u32(16)
+            .u32(cie_pointer)
+            .u32(pc_begin as u32)
+            .u32(0x20)
+            .uleb(0)
+            .raw(&[DW_CFA_ADVANCE_LOC | 4, DW_CFA_DEF_CFA_OFFSET, 16])
+            .u32(0);
+
+        Section {
+            addr: EH_FRAME_ADDRESS,
+            ..section(name, SHT_PROGBITS, data)
+        }
+    }
+
+    // pointers to .eh_frame are pc-relative, the table's are relative to
+    // .eh_frame_hdr
+    fn eh_frame_hdr(name: &'static str, fde_count: u32, table: &[(u64, u64)]) -> Section {
+        let data = Bytes::default()
+            .u8(1)
+            .u8(DW_EH_PE_PCREL | DW_EH_PE_SDATA4)
+            .u8(DW_EH_PE_UDATA4)
+            .u8(DW_EH_PE_DATAREL | DW_EH_PE_SDATA4)
+            .u32((EH_FRAME_ADDRESS - HDR_ADDRESS - 4) as u32)
+            .u32(fde_count);
+        let data = table.iter().fold(data, |data, &(location, fde)| {
+            data.u32(location.wrapping_sub(HDR_ADDRESS) as u32)
+                .u32(fde.wrapping_sub(HDR_ADDRESS) as u32)
+        });
+
+        Section {
+            addr: HDR_ADDRESS,
+            ..section(name, SHT_PROGBITS, data)
+        }
+    }
+
+    fn describe(elf: &ParsedElf) -> Vec<String> {
+        elf.frame_records
+            .iter()
+            .map(|record| {
+                let entry = match &record.entry {
+                    FrameEntry::Cie(cie) => format!(
+                        "CIE v{} {} {} {} {} {:#x}",
+                        cie.version,
+                        cie.augmentation,
+                        cie.code_alignment,
+                        cie.data_alignment,
+                        cie.return_register,
+                        cie.fde_encoding
+                    ),
+                    FrameEntry::Fde(fde) => {
+                        format!("FDE {:?} {:#x}+{:#x}", fde.cie, fde.pc_begin, fde.pc_range)
+                    }
+                };
+                let ops: Vec<String> = record
+                    .instructions
+                    .iter()
+                    .map(|op| format!("{} {}", op.name, op.operands))
+                    .collect();
+
+                format!(
+                    "{} {} {:#x} {}: {}",
+                    record.offset,
+                    record.size,
+                    record.address,
+                    entry,
+                    ops.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cie_fde_pair_and_search_table() {
+        let buf = build(
+            &[
+                eh_frame_hdr(".eh_frame_hdr", 1, &[(0x1000, EH_FRAME_ADDRESS + 24)]),
+                eh_frame(".eh_frame", 28),
+            ],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let hdr_start = elf.shdrs[1].file_offset;
+        let start = elf.shdrs[2].file_offset;
+
+        assert_eq!(
+            describe(&elf),
+            [
+                format!(
+                    "{} 24 0x2100 CIE v1 zR 1 -8 16 0x1b: DW_CFA_def_cfa r7 (rsp) ofs 8, \
+                     DW_CFA_offset r16 (rip) at cfa-8, DW_CFA_nop , DW_CFA_nop ",
+                    start
+                ),
+                format!(
+                    "{} 20 0x2118 FDE Some(0) 0x1000+0x20: DW_CFA_advance_loc 4 to 0x1004, \
+                     DW_CFA_def_cfa_offset 16",
+                    start + 24
+                )
+            ]
+        );
+
+        let hdr = elf.eh_frame_hdr.as_ref().unwrap();
+
+        assert_eq!(
+            (hdr.offset, hdr.size, hdr.address),
+            (hdr_start, 20, HDR_ADDRESS)
+        );
+        assert_eq!(
+            (
+                hdr.version,
+                hdr.eh_frame_ptr,
+                hdr.fde_count,
+                hdr.table_offset
+            ),
+            (1, Some(EH_FRAME_ADDRESS), Some(1), hdr_start + 12)
+        );
+        assert_eq!(hdr.table, [(0x1000, EH_FRAME_ADDRESS + 24)]);
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn records_found_through_the_program_headers() {
+        // neither section has its usual name
+        let buf = build(
+            &[
+                eh_frame_hdr(".hdr", 1, &[(0x1000, EH_FRAME_ADDRESS + 24)]),
+                eh_frame(".frames", 28),
+            ],
+            &[(PT_LOAD, 2), (PT_GNU_EH_FRAME, 1)],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[2].file_offset;
+        let records: Vec<_> = elf
+            .frame_records
+            .iter()
+            .map(|record| (record.offset, record.size, record.address))
+            .collect();
+
+        assert_eq!(
+            records,
+            [
+                (start, 24, EH_FRAME_ADDRESS),
+                (start + 24, 20, EH_FRAME_ADDRESS + 24)
+            ]
+        );
+        assert_eq!(
+            elf.eh_frame_hdr.as_ref().unwrap().table,
+            [(0x1000, EH_FRAME_ADDRESS + 24)]
+        );
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn malformed_records_and_search_table() {
+        // the FDE points at itself as its CIE, the table has an extra entry
+        // which is out of order and isn't an FDE
+        let table = [
+            (0x1000, EH_FRAME_ADDRESS + 24),
+            (0x800, EH_FRAME_ADDRESS + 30),
+        ];
+        let buf = build(
+            &[
+                eh_frame_hdr(".eh_frame_hdr", 2, &table),
+                eh_frame(".eh_frame", 4),
+            ],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let hdr_start = elf.shdrs[1].file_offset;
+        let start = elf.shdrs[2].file_offset;
+
+        assert_eq!(
+            describe(&elf)[1],
+            format!("{} 20 0x2118 FDE None 0x0+0x0: ", start + 24)
+        );
+
+        let diagnostics: Vec<_> = elf
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (&diagnostic.error, diagnostic.location))
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            [
+                (
+                    &ElfError::BadReference {
+                        offset: start + 24,
+                        structure: Structure::FrameRecord(1),
+                        field: "cie_pointer",
+                        value: 4,
+                        target: "a CIE",
+                    },
+                    Some((start + 28, 4))
+                ),
+                (
+                    &ElfError::Mismatch {
+                        offset: hdr_start,
+                        structure: Structure::EhFrameHdr,
+                        field: "fde_count",
+                        value: 2,
+                        expected: 1,
+                    },
+                    Some((hdr_start + 8, 4))
+                ),
+                (
+                    &ElfError::Unsorted {
+                        offset: hdr_start,
+                        structure: Structure::EhFrameHdr,
+                        field: "initial location",
+                    },
+                    Some((hdr_start + 20, 8))
+                ),
+                (
+                    &ElfError::BadReference {
+                        offset: hdr_start,
+                        structure: Structure::EhFrameHdr,
+                        field: "FDE address",
+                        value: EH_FRAME_ADDRESS + 30,
+                        target: "an FDE",
+                    },
+                    Some((hdr_start + 20, 8))
+                )
+            ]
+        );
+    }
+}
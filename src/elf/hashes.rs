@@ -0,0 +1,372 @@
+use super::defs::*;
+use super::error::{ElfError, Structure};
+use super::notes::Reader;
+use super::parser::{HashTable, ParsedElf, ParsedIdent, RangeType};
+
+// name (also the class of its range), offset in the section and size of a
+// part of a hash table
+type Part = (&'static str, usize, usize);
+
+// `count` words of `size` bytes at `offset`, as many of them as there are
+fn read_array<T>(
+    section: &[u8],
+    offset: usize,
+    count: usize,
+    size: usize,
+    read: impl Fn(&[u8], usize) -> Option<T>,
+) -> Vec<T> {
+    let available = section.len().saturating_sub(offset) / size;
+
+    (0..count.min(available))
+        .filter_map(|i| read(section, offset + i * size))
+        .collect()
+}
+
+impl<'a> ParsedElf<'a> {
+    pub(super) fn parse_hash_tables(&mut self, ident: &ParsedIdent) {
+        let reader = Reader::new(ident.endianness, ident.class);
+
+        for i in 0..self.shdrs.len() {
+            let table = match self.shdrs[i].shtype {
+                SHT_HASH => self.parse_sysv_hash(i, reader),
+                SHT_GNU_HASH => self.parse_gnu_hash(i, reader),
+                _ => None,
+            };
+
+            if let Some(table) = table {
+                self.hash_tables.insert(i as u16, table);
+            }
+        }
+    }
+
+    fn parse_sysv_hash(&mut self, idx: usize, reader: Reader) -> Option<HashTable> {
+        let section = self.section_data(&self.shdrs[idx])?;
+        let nbucket = reader.u32(section, 0).unwrap_or(0) as usize;
+        let nchain = reader.u32(section, 4).unwrap_or(0) as usize;
+        let chains_offset = nbucket.saturating_mul(4).saturating_add(8);
+
+        let parts = [
+            ("nbucket", 0, 4),
+            ("nchain", 4, 4),
+            ("bucket", 8, nbucket.saturating_mul(4)),
+            ("chain", chains_offset, nchain.saturating_mul(4)),
+        ];
+
+        if !self.add_hash_parts(idx, section.len(), &parts) {
+            return None;
+        }
+
+        let read = |buf: &[u8], offset| reader.u32(buf, offset);
+
+        Some(HashTable::Sysv {
+            buckets: read_array(section, 8, nbucket, 4, read),
+            chains: read_array(section, chains_offset, nchain, 4, read),
+        })
+    }
+
+    fn parse_gnu_hash(&mut self, idx: usize, reader: Reader) -> Option<HashTable> {
+        let shdr = &self.shdrs[idx];
+        let section = self.section_data(shdr)?;
+        let word = reader.word;
+        let nbuckets = reader.u32(section, 0).unwrap_or(0) as usize;
+        let symoffset = reader.u32(section, 4).unwrap_or(0);
+        let bloom_size = reader.u32(section, 8).unwrap_or(0) as usize;
+        let bloom_shift = reader.u32(section, 12).unwrap_or(0);
+        let buckets_offset = bloom_size.saturating_mul(word).saturating_add(16);
+        let chains_offset = nbuckets.saturating_mul(4).saturating_add(buckets_offset);
+        // the chains have no count of their own, they cover the symbols from
+        // symoffset to the end of the symbol table
+        let symbols = self.symtabs.get(&(shdr.link as u16)).map(Vec::len);
+        let nchain = match symbols {
+            Some(symbols) => symbols.saturating_sub(symoffset as usize),
+            None => section.len().saturating_sub(chains_offset) / 4,
+        };
+
+        let parts = [
+            ("gnu_nbucket", 0, 4),
+            ("symoffset", 4, 4),
+            ("bloom_size", 8, 4),
+            ("bloom_shift", 12, 4),
+            ("bloom", 16, bloom_size.saturating_mul(word)),
+            ("gnu_bucket", buckets_offset, nbuckets.saturating_mul(4)),
+            ("gnu_chain", chains_offset, nchain.saturating_mul(4)),
+        ];
+
+        if !self.add_hash_parts(idx, section.len(), &parts) {
+            return None;
+        }
+
+        if let Some(symbols) = symbols.filter(|&symbols| symoffset as usize > symbols) {
+            let start = self.shdrs[idx].file_offset;
+            let error = ElfError::OutOfBounds {
+                offset: start,
+                structure: Structure::HashTable(idx as u16),
+                field: "symoffset",
+                value: symoffset as u64,
+                limit: symbols as u64,
+            };
+
+            self.diagnose_field(error, start, section.len(), "symoffset");
+        }
+
+        let read = |buf: &[u8], offset| reader.u32(buf, offset);
+
+        Some(HashTable::Gnu {
+            symoffset,
+            bloom_shift,
+            bloom_bits: word as u32 * 8,
+            bloom: read_array(section, 16, bloom_size, word, |buf, offset| {
+                reader.word(buf, offset)
+            }),
+            buckets: read_array(section, buckets_offset, nbuckets, 4, read),
+            chains: read_array(section, chains_offset, nchain, 4, read),
+        })
+    }
+
+    // adds the ranges of the parts which are in the section, false if even
+    // the header isn't
+    fn add_hash_parts(&mut self, idx: usize, section_len: usize, parts: &[Part]) -> bool {
+        let start = self.shdrs[idx].file_offset;
+        let (_, last_offset, last_size) = parts[parts.len() - 1];
+        let size = last_offset.saturating_add(last_size);
+        let header_size = parts.iter().take_while(|(_, _, size)| *size == 4).count() * 4;
+
+        if size > section_len {
+            let error = ElfError::Truncated {
+                offset: start,
+                structure: Structure::HashTable(idx as u16),
+                size,
+                available: section_len,
+            };
+
+            self.diagnose(error, Some((start, section_len)));
+        }
+
+        if header_size > section_len {
+            return false;
+        }
+
+        for (name, offset, size) in parts.iter() {
+            let size = (*size).min(section_len.saturating_sub(*offset));
+
+            if size > 0 {
+                self.ranges
+                    .add_range(start + offset, size, RangeType::HashField(name));
+            }
+        }
+
+        true
+    }
+}
+
+impl HashTable {
+    // symbol indices in the chain of a bucket, in lookup order
+    pub fn chain(&self, bucket: usize) -> Vec<usize> {
+        match self {
+            HashTable::Sysv { buckets, chains } => {
+                let mut symbols = vec![];
+                let mut sym = buckets.get(bucket).map_or(0, |&sym| sym as usize);
+
+                // 0 ends the chain, malformed chains can loop but can't be
+                // longer than the table
+                while sym != 0 && sym < chains.len() && symbols.len() < chains.len() {
+                    symbols.push(sym);
+                    sym = chains[sym] as usize;
+                }
+
+                symbols
+            }
+            HashTable::Gnu {
+                symoffset,
+                buckets,
+                chains,
+                ..
+            } => {
+                let first = buckets.get(bucket).map_or(0, |&sym| sym as usize);
+                let symoffset = *symoffset as usize;
+
+                // 0 is an empty bucket, other values below symoffset are bogus
+                if first == 0 || first < symoffset {
+                    return vec![];
+                }
+
+                let hashes = chains.get(first - symoffset..).unwrap_or(&[]);
+                // the last hash value of a chain has its lowest bit set
+                let len = hashes
+                    .iter()
+                    .position(|hash| hash & 1 == 1)
+                    .map_or(hashes.len(), |last| last + 1);
+
+                (first..first + len).collect()
+            }
+        }
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        match self {
+            HashTable::Sysv { buckets, .. } | HashTable::Gnu { buckets, .. } => buckets.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_elf::{build, section, Bytes, Section};
+    use super::*;
+
+    fn words(values: &[u32]) -> Bytes {
+        values
+            .iter()
+            .fold(Bytes::default(), |data, &value| data.u32(value))
+    }
+
+    // .dynstr and a .dynsym of `count` symbols, the null one included
+    fn dynsym(count: usize) -> [Section; 2] {
+        let symbols = (0..count).fold(Bytes::default(), |data, _| data.raw(&[0; 24]));
+
+        [
+            section(".dynstr", SHT_STRTAB, Bytes::default().u8(0)),
+            Section {
+                link: 1,
+                entsize: 24,
+                ..section(".dynsym", SHT_DYNSYM, symbols)
+            },
+        ]
+    }
+
+    fn gnu_hash(symoffset: u32, chains: &[u32]) -> Section {
+        let data = words(&[1, symoffset, 1, 6])
+            .u64(0x8000_0000_0000_0001)
+            .raw(&words(&[symoffset]).0)
+            .raw(&words(chains).0);
+
+        Section {
+            link: 2,
+            ..section(".gnu.hash", SHT_GNU_HASH, data)
+        }
+    }
+
+    #[test]
+    fn sysv_table() {
+        let data = words(&[2, 4, 1, 3, 0, 2, 0, 0]);
+        let buf = build(&[section(".hash", SHT_HASH, data)], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let table = &elf.hash_tables[&1];
+
+        match table {
+            HashTable::Sysv { buckets, chains } => {
+                assert_eq!(buckets, &[1, 3]);
+                assert_eq!(chains, &[0, 2, 0, 0]);
+            }
+            _ => panic!("not a SysV table"),
+        }
+
+        assert_eq!(table.bucket_count(), 2);
+        assert_eq!(table.chain(0), [1, 2]);
+        assert_eq!(table.chain(1), [3]);
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn truncated_sysv_table() {
+        // room for one of the 4 chain entries
+        let data = words(&[2, 4, 1, 3, 0]);
+        let buf = build(&[section(".hash", SHT_HASH, data)], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[1].file_offset;
+
+        match &elf.hash_tables[&1] {
+            HashTable::Sysv { buckets, chains } => {
+                assert_eq!(buckets, &[1, 3]);
+                assert_eq!(chains, &[0]);
+            }
+            _ => panic!("not a SysV table"),
+        }
+
+        assert_eq!(elf.diagnostics.len(), 1);
+        assert_eq!(
+            elf.diagnostics[0].error,
+            ElfError::Truncated {
+                offset: start,
+                structure: Structure::HashTable(1),
+                size: 32,
+                available: 20,
+            }
+        );
+        assert_eq!(elf.diagnostics[0].location, Some((start, 20)));
+    }
+
+    #[test]
+    fn gnu_table_chains_cover_the_linked_symbols() {
+        // the hash of symbol 2 ends the chain, the one of symbol 3 is extra
+        let [dynstr, dynsym] = dynsym(4);
+        let buf = build(
+            &[dynstr, dynsym, gnu_hash(1, &[0x10, 0x21, 0x31, 0x41])],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let table = &elf.hash_tables[&3];
+
+        match table {
+            HashTable::Gnu {
+                symoffset,
+                bloom_shift,
+                bloom_bits,
+                bloom,
+                buckets,
+                chains,
+            } => {
+                assert_eq!(*symoffset, 1);
+                assert_eq!(*bloom_shift, 6);
+                assert_eq!(*bloom_bits, 64);
+                assert_eq!(bloom, &[0x8000_0000_0000_0001]);
+                assert_eq!(buckets, &[1]);
+                assert_eq!(chains, &[0x10, 0x21, 0x31]);
+            }
+            _ => panic!("not a GNU table"),
+        }
+
+        assert_eq!(table.chain(0), [1, 2]);
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn gnu_table_without_symbols_fills_the_section() {
+        let buf = build(&[gnu_hash(1, &[0x10, 0x21, 0x31, 0x41])], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+
+        match &elf.hash_tables[&1] {
+            HashTable::Gnu { chains, .. } => assert_eq!(chains, &[0x10, 0x21, 0x31, 0x41]),
+            _ => panic!("not a GNU table"),
+        }
+
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn gnu_symoffset_past_the_symbols() {
+        let [dynstr, dynsym] = dynsym(4);
+        let buf = build(&[dynstr, dynsym, gnu_hash(9, &[])], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[3].file_offset;
+
+        match &elf.hash_tables[&3] {
+            HashTable::Gnu { chains, .. } => assert!(chains.is_empty()),
+            _ => panic!("not a GNU table"),
+        }
+
+        assert_eq!(elf.hash_tables[&3].chain(0), Vec::<usize>::new());
+        assert_eq!(elf.diagnostics.len(), 1);
+        assert_eq!(
+            elf.diagnostics[0].error,
+            ElfError::OutOfBounds {
+                offset: start,
+                structure: Structure::HashTable(3),
+                field: "symoffset",
+                value: 9,
+                limit: 4,
+            }
+        );
+        assert_eq!(elf.diagnostics[0].location, Some((start + 4, 4)));
+    }
+}
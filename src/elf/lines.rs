@@ -0,0 +1,799 @@
+use super::defs::*;
+use super::dwarf::{cstr_at, is_known_form, sleb, uleb, Context, UnitHeader, Value};
+use super::error::{ElfError, Structure};
+use super::notes::Reader;
+use super::parser::{LineFile, LineOp, LineProgram, LineRow, ParsedElf, ParsedIdent, RangeType};
+
+// name (also the class of its range), offset in the section and size of a
+// part of a program header
+type Part = (&'static str, usize, usize);
+
+// registers of the line-number state machine
+struct State {
+    address: u64,
+    op_index: u64,
+    file: u64,
+    line: u64,
+    column: u64,
+    is_stmt: bool,
+}
+
+impl State {
+    fn new(default_is_stmt: bool) -> State {
+        State {
+            address: 0,
+            op_index: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: default_is_stmt,
+        }
+    }
+
+    fn row(&self, end_sequence: bool) -> LineRow {
+        LineRow {
+            address: self.address,
+            file: self.file,
+            line: self.line,
+            column: self.column,
+            is_stmt: self.is_stmt,
+            end_sequence,
+        }
+    }
+
+    // returns the address increment. op_index only matters for VLIW
+    // targets, it stays 0 with one operation per instruction
+    fn advance(&mut self, program: &LineProgram, operation_advance: u64) -> u64 {
+        let max_ops = program.max_ops_per_inst.max(1) as u64;
+        let ops = self.op_index.wrapping_add(operation_advance);
+        let delta = (program.min_inst_length as u64).wrapping_mul(ops / max_ops);
+
+        self.address = self.address.wrapping_add(delta);
+        self.op_index = ops % max_ops;
+
+        delta
+    }
+
+    fn advance_line(&mut self, delta: i64) {
+        self.line = (self.line as i64).wrapping_add(delta) as u64;
+    }
+}
+
+// reads a fixed-size field and records its part
+fn field(
+    context: &Context,
+    parts: &mut Vec<Part>,
+    name: &'static str,
+    pos: &mut usize,
+    size: usize,
+) -> Option<u64> {
+    let value = context.read(pos, size)?;
+
+    parts.push((name, *pos - size, size));
+
+    Some(value)
+}
+
+fn cstr(data: &[u8], pos: &mut usize) -> Option<String> {
+    let string = cstr_at(Some(data), *pos as u64)?;
+
+    // the terminator has to be there too
+    if *pos + string.len() >= data.len() {
+        return None;
+    }
+
+    *pos += string.len() + 1;
+
+    Some(string)
+}
+
+// directories and files before DWARF 5: a string followed by `ulebs`
+// numbers, the first of which is the directory index, up to an empty string
+fn legacy_entries(data: &[u8], pos: &mut usize, ulebs: usize) -> Option<Vec<LineFile>> {
+    let mut entries = vec![];
+
+    loop {
+        let name = cstr(data, pos)?;
+
+        if name.is_empty() {
+            return Some(entries);
+        }
+
+        let mut directory = 0;
+
+        for i in 0..ulebs {
+            let value = uleb(data, pos)?;
+
+            if i == 0 {
+                directory = value;
+            }
+        }
+
+        entries.push(LineFile { name, directory });
+    }
+}
+
+// DWARF 5 directories and files: the format of an entry as pairs of content
+// type and form, then the entries
+fn entries(
+    context: &Context,
+    unit: &UnitHeader,
+    pos: &mut usize,
+    parts: &mut Vec<Part>,
+    names: (&'static str, &'static str),
+    unknown_form: &mut Option<u64>,
+) -> Option<Vec<LineFile>> {
+    let data = &context.data[..unit.end];
+    let format_start = *pos;
+    let format_count = context.read(pos, 1)?;
+    let mut format = vec![];
+
+    for _ in 0..format_count {
+        format.push((uleb(data, pos)?, uleb(data, pos)?));
+    }
+
+    parts.push((names.0, format_start, *pos - format_start));
+
+    let entries_start = *pos;
+    let count = uleb(data, pos)?;
+    let mut entries = vec![];
+
+    // entries without a format would take no bytes, otherwise they take at
+    // least one
+    let count = if format.is_empty() {
+        0
+    } else {
+        count.min(data.len().saturating_sub(*pos) as u64)
+    };
+
+    for _ in 0..count {
+        let mut entry = LineFile {
+            name: String::new(),
+            directory: 0,
+        };
+
+        for &(content, form) in format.iter() {
+            let value = match context.value(pos, form, 0, unit) {
+                Some(value) => value,
+                None => {
+                    *unknown_form = Some(form).filter(|&form| !is_known_form(form));
+                    return None;
+                }
+            };
+
+            match (content, value) {
+                (DW_LNCT_PATH, value) => {
+                    entry.name = context.render(0, value, unit, (None, None)).0
+                }
+                (DW_LNCT_DIRECTORY_INDEX, Value::Unsigned(index)) => entry.directory = index,
+                _ => {}
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    parts.push((names.1, entries_start, *pos - entries_start));
+
+    Some(entries)
+}
+
+// reads the header fields after the version into `program`, returns where
+// the opcodes start or None at the first field which can't be read
+fn read_header(
+    context: &Context,
+    unit: &mut UnitHeader,
+    pos: &mut usize,
+    program: &mut LineProgram,
+    parts: &mut Vec<Part>,
+    unknown_form: &mut Option<u64>,
+) -> Option<usize> {
+    let data = &context.data[..unit.end];
+
+    if unit.version >= 5 {
+        unit.address_size = field(context, parts, "address_size", pos, 1)? as u8;
+        field(context, parts, "seg_sel_size", pos, 1)?;
+    }
+
+    let header_length = field(context, parts, "header_len", pos, unit.offset_size())?;
+    let ops_start = pos.saturating_add(header_length as usize);
+
+    program.min_inst_length = field(context, parts, "min_inst_len", pos, 1)? as u8;
+
+    if unit.version >= 4 {
+        program.max_ops_per_inst = field(context, parts, "max_ops", pos, 1)? as u8;
+    }
+
+    program.default_is_stmt = field(context, parts, "default_stmt", pos, 1)? != 0;
+    program.line_base = field(context, parts, "line_base", pos, 1)? as i8;
+    program.line_range = field(context, parts, "line_range", pos, 1)? as u8;
+    program.opcode_base = field(context, parts, "opcode_base", pos, 1)? as u8;
+
+    let count = (program.opcode_base as usize).saturating_sub(1);
+
+    program.opcode_lengths = data.get(*pos..*pos + count)?.to_vec();
+
+    if count > 0 {
+        parts.push(("opcode_lens", *pos, count));
+        *pos += count;
+    }
+
+    if unit.version >= 5 {
+        let names = ("dir_format", "directories");
+
+        program.directories = entries(context, unit, pos, parts, names, unknown_form)?
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+
+        let names = ("file_format", "file_names");
+
+        program.files = entries(context, unit, pos, parts, names, unknown_form)?;
+    } else {
+        let directories_start = *pos;
+
+        program.directories = legacy_entries(data, pos, 0)?
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        parts.push(("directories", directories_start, *pos - directories_start));
+
+        let files_start = *pos;
+
+        program.files = legacy_entries(data, pos, 3)?;
+        parts.push(("file_names", files_start, *pos - files_start));
+    }
+
+    Some(ops_start).filter(|_| *pos <= unit.end)
+}
+
+// runs the opcode at `pos`, returns its name, its operands and the row it
+// appends, None if it goes past `data`
+fn run_op(
+    context: &Context,
+    data: &[u8],
+    pos: &mut usize,
+    program: &LineProgram,
+    state: &mut State,
+) -> Option<(String, String, Option<LineRow>)> {
+    let opcode = *data.get(*pos)?;
+    let line_range = program.line_range.max(1) as u64;
+    let mut row = None;
+
+    *pos += 1;
+
+    let (name, operands) = if opcode == 0 {
+        let len = uleb(data, pos)? as usize;
+        let end = pos
+            .checked_add(len)
+            .filter(|&end| end <= data.len() && len > 0)?;
+        let extended = data[*pos];
+
+        *pos += 1;
+
+        let operands = match extended {
+            DW_LNE_END_SEQUENCE => {
+                row = Some(state.row(true));
+                *state = State::new(program.default_is_stmt);
+
+                String::new()
+            }
+            DW_LNE_SET_ADDRESS if len <= 9 => {
+                state.address = context.read(pos, len - 1)?;
+                state.op_index = 0;
+
+                format!("{:#x}", state.address)
+            }
+            DW_LNE_DEFINE_FILE => cstr(&data[..end], pos)?,
+            DW_LNE_SET_DISCRIMINATOR => format!("{}", uleb(&data[..end], pos)?),
+            _ => format!("{} bytes", len - 1),
+        };
+
+        *pos = end;
+
+        (dwarf_lne_to_string(extended), operands)
+    } else if opcode >= program.opcode_base {
+        let adjusted = (opcode - program.opcode_base) as u64;
+        let delta = state.advance(program, adjusted / line_range);
+        let line_delta = program.line_base as i64 + (adjusted % line_range) as i64;
+
+        state.advance_line(line_delta);
+        row = Some(state.row(false));
+
+        (
+            format!("special opcode {}", opcode),
+            format!("address +{:#x}, line {:+}", delta, line_delta),
+        )
+    } else {
+        let operands = match opcode {
+            DW_LNS_COPY => {
+                row = Some(state.row(false));
+
+                String::new()
+            }
+            DW_LNS_ADVANCE_PC => {
+                let operation_advance = uleb(data, pos)?;
+
+                format!("address +{:#x}", state.advance(program, operation_advance))
+            }
+            DW_LNS_ADVANCE_LINE => {
+                let delta = sleb(data, pos)?;
+
+                state.advance_line(delta);
+
+                format!("line {:+}", delta)
+            }
+            DW_LNS_SET_FILE => {
+                state.file = uleb(data, pos)?;
+
+                format!("{}", state.file)
+            }
+            DW_LNS_SET_COLUMN => {
+                state.column = uleb(data, pos)?;
+
+                format!("{}", state.column)
+            }
+            DW_LNS_NEGATE_STMT => {
+                state.is_stmt = !state.is_stmt;
+
+                format!("is_stmt = {}", state.is_stmt)
+            }
+            // the address increment of special opcode 255
+            DW_LNS_CONST_ADD_PC => {
+                let adjusted = 255 - program.opcode_base as u64;
+
+                format!(
+                    "address +{:#x}",
+                    state.advance(program, adjusted / line_range)
+                )
+            }
+            DW_LNS_FIXED_ADVANCE_PC => {
+                let delta = context.read(pos, 2)?;
+
+                state.address = state.address.wrapping_add(delta);
+                state.op_index = 0;
+
+                format!("address +{:#x}", delta)
+            }
+            DW_LNS_SET_ISA => format!("{}", uleb(data, pos)?),
+            DW_LNS_SET_BASIC_BLOCK | DW_LNS_SET_PROLOGUE_END | DW_LNS_SET_EPILOGUE_BEGIN => {
+                String::new()
+            }
+            // unknown ones can be skipped since the header has the number of
+            // their uleb operands
+            _ => {
+                let count = program.opcode_lengths[opcode as usize - 1];
+                let values = (0..count)
+                    .map(|_| uleb(data, pos).map(|value| format!("{}", value)))
+                    .collect::<Option<Vec<String>>>()?;
+
+                values.join(", ")
+            }
+        };
+
+        (dwarf_lns_to_string(opcode), operands)
+    };
+
+    if *pos > data.len() {
+        return None;
+    }
+
+    Some((name, operands, row))
+}
+
+impl<'a> ParsedElf<'a> {
+    pub(super) fn parse_debug_line(&mut self, ident: &ParsedIdent) {
+        let strings = self.debug_section(".debug_str");
+        let line_strings = self.debug_section(".debug_line_str");
+        let sections: Vec<usize> = (0..self.shdrs.len())
+            .filter(|&idx| self.is_debug_section(idx, ".debug_line"))
+            .collect();
+
+        for idx in sections {
+            let data = match self.section_contents(idx) {
+                Some(data) => data,
+                None => continue,
+            };
+            let context = Context {
+                reader: Reader::new(ident.endianness, ident.class),
+                data: &data,
+                strings: strings.as_deref(),
+                line_strings: line_strings.as_deref(),
+                str_offsets: None,
+                addresses: None,
+                relocations: self.debug_relocations(idx),
+            };
+
+            self.decode_contents(idx, |elf| {
+                let mut offset = 0;
+
+                while offset < data.len() {
+                    let (ops_start, end) = match elf.parse_line_header(idx, &context, offset) {
+                        Some(bounds) => bounds,
+                        None => break,
+                    };
+
+                    if let Some(ops_start) = ops_start {
+                        elf.parse_line_ops(idx, &context, ops_start, end);
+                    }
+
+                    offset = end;
+                }
+            });
+        }
+    }
+
+    // adds the ranges of the program and its header, returns where its
+    // opcodes start (None if the header is malformed) and end. None if
+    // there's no header
+    fn parse_line_header(
+        &mut self,
+        idx: usize,
+        context: &Context,
+        offset: usize,
+    ) -> Option<(Option<usize>, usize)> {
+        let program = self.line_programs.len() as u32;
+        let start = self.contents_offset(idx);
+        let available = context.data.len() - offset;
+        let truncated = |size| ElfError::Truncated {
+            offset: start + offset,
+            structure: Structure::LineProgram(program),
+            size,
+            available,
+        };
+        let mut pos = offset;
+        let mut parts = vec![];
+
+        let (format64, length) = match field(context, &mut parts, "unit_length", &mut pos, 4) {
+            Some(0xffff_ffff) => (true, field(context, &mut parts, "unit_len64", &mut pos, 8)),
+            length => (false, length),
+        };
+        // unit_length counts the bytes after itself
+        let length_end = pos;
+        let version = field(context, &mut parts, "line_version", &mut pos, 2);
+        let (length, version) = match (length, version) {
+            (Some(length), Some(version)) => (length as usize, version as u16),
+            _ => {
+                self.diagnose(
+                    truncated(pos - offset + 1),
+                    Some((start + offset, available)),
+                );
+
+                return None;
+            }
+        };
+        let end = length_end.saturating_add(length);
+        let size = end.min(context.data.len()) - offset;
+        let mut unit = UnitHeader {
+            offset,
+            end: offset + size,
+            format64,
+            version,
+            unit_type: 0,
+            address_size: 0,
+            abbrev_offset: 0,
+            dies_start: 0,
+        };
+        let mut lp = LineProgram {
+            section: idx as u16,
+            offset,
+            size,
+            format64,
+            version,
+            min_inst_length: 0,
+            max_ops_per_inst: 1,
+            default_is_stmt: false,
+            line_base: 0,
+            line_range: 0,
+            opcode_base: 0,
+            opcode_lengths: vec![],
+            directories: vec![],
+            files: vec![],
+            ops: vec![],
+            rows: vec![],
+        };
+        let mut unknown_form = None;
+        let ops_start = if (2..=5).contains(&version) {
+            read_header(
+                context,
+                &mut unit,
+                &mut pos,
+                &mut lp,
+                &mut parts,
+                &mut unknown_form,
+            )
+        } else {
+            None
+        };
+
+        if end > context.data.len() {
+            self.diagnose(truncated(end - offset), Some((start + offset, size)));
+        }
+
+        self.ranges
+            .add_range(start + offset, size, RangeType::LineProgram(program));
+
+        for (name, part_offset, part_size) in parts.into_iter() {
+            if part_offset + part_size <= offset + size {
+                self.ranges
+                    .add_range(start + part_offset, part_size, RangeType::DwarfField(name));
+            }
+        }
+
+        let structure = Structure::LineProgram(program);
+
+        if !(2..=5).contains(&version) {
+            let error = ElfError::UnknownValue {
+                offset: start + offset,
+                structure,
+                field: "line_version",
+                value: version as u64,
+            };
+
+            self.diagnose_field(error, start + offset, size, "line_version");
+            self.line_programs.push(lp);
+
+            return Some((None, offset + size));
+        }
+
+        let error = match (unknown_form, ops_start) {
+            (Some(form), _) => Some(ElfError::UnknownValue {
+                offset: start + offset,
+                structure,
+                field: "form",
+                value: form,
+            }),
+            // the header is longer than header_length says
+            (None, Some(ops_start)) if pos > ops_start => Some(ElfError::Truncated {
+                offset: start + offset,
+                structure,
+                size: pos - offset,
+                available: ops_start - offset,
+            }),
+            (None, Some(_)) => None,
+            (None, None) => Some(truncated(pos - offset + 1)),
+        };
+        let ops_start = match error {
+            Some(error) => {
+                self.diagnose(error, Some((start + offset, size)));
+                None
+            }
+            None => ops_start,
+        };
+
+        self.line_programs.push(lp);
+
+        Some((ops_start, offset + size))
+    }
+
+    fn parse_line_ops(&mut self, idx: usize, context: &Context, ops_start: usize, end: usize) {
+        let program = self.line_programs.len() - 1;
+        let start = self.contents_offset(idx);
+        let data = &context.data[..end];
+        let lp = &self.line_programs[program];
+        let mut state = State::new(lp.default_is_stmt);
+        let mut ops = vec![];
+        let mut rows = vec![];
+        let mut pos = ops_start;
+        let mut truncated = None;
+
+        while pos < end {
+            let op_start = pos;
+            let (name, operands, row) = match run_op(context, data, &mut pos, lp, &mut state) {
+                Some(op) => op,
+                None => {
+                    truncated = Some(op_start);
+                    break;
+                }
+            };
+
+            ops.push(LineOp {
+                offset: op_start,
+                size: pos - op_start,
+                name,
+                operands,
+                row: row.map(|row| {
+                    rows.push(row);
+
+                    rows.len() - 1
+                }),
+            });
+        }
+
+        for (i, op) in ops.iter().enumerate() {
+            self.ranges.add_range(
+                start + op.offset,
+                op.size,
+                RangeType::LineOp(program as u32, i as u32),
+            );
+        }
+
+        if let Some(op_start) = truncated {
+            let error = ElfError::Truncated {
+                offset: start + op_start,
+                structure: Structure::LineProgram(program as u32),
+                size: end - op_start + 1,
+                available: end - op_start,
+            };
+
+            self.diagnose(error, Some((start + op_start, end - op_start)));
+        }
+
+        self.line_programs[program].ops = ops;
+        self.line_programs[program].rows = rows;
+    }
+}
+
+impl LineProgram {
+    // file numbers start at 1 before DWARF 5
+    pub fn file_name(&self, file: u64) -> Option<&str> {
+        let index = if self.version >= 5 {
+            file
+        } else {
+            file.checked_sub(1)?
+        };
+
+        self.files
+            .get(index as usize)
+            .map(|file| file.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_elf::{build, section, Bytes};
+    use super::*;
+
+    // a DWARF 3 program with line_base -5, line_range 14 and opcode_base 13,
+    // directory "src" and file "a.c" in it
+    fn program(ops: Bytes) -> Bytes {
+        let header = Bytes::default()
+            .raw(&[1, 1, -5i8 as u8, 14, 13])
+            .raw(&[0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1])
+            .cstr("src")
+            .u8(0)
+            .cstr("a.c")
+            .raw(&[1, 0, 0])
+            .u8(0);
+
+        Bytes::default()
+            .u32((2 + 4 + header.size() + ops.size()) as u32)
+            .u16(3)
+            .u32(header.size() as u32)
+            .raw(&header.0)
+            .raw(&ops.0)
+    }
+
+    fn describe(program: &LineProgram) -> (Vec<String>, Vec<String>) {
+        let ops = program
+            .ops
+            .iter()
+            .map(|op| {
+                format!(
+                    "{} {} {} {} {:?}",
+                    op.offset, op.size, op.name, op.operands, op.row
+                )
+            })
+            .collect();
+        let rows = program
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{:#x} {}:{}:{} {} {}",
+                    row.address, row.file, row.line, row.column, row.is_stmt, row.end_sequence
+                )
+            })
+            .collect();
+
+        (ops, rows)
+    }
+
+    #[test]
+    fn dwarf3_program() {
+        // a special opcode for address +4, line +1
+        let ops = Bytes::default()
+            .raw(&[0, 9, DW_LNE_SET_ADDRESS])
+            .u64(0x1000)
+            .u8(DW_LNS_ADVANCE_LINE)
+            .sleb(4)
+            .u8(DW_LNS_COPY)
+            .u8(13 + 6 + 14 * 4)
+            .u8(DW_LNS_SET_COLUMN)
+            .uleb(3)
+            .u8(DW_LNS_ADVANCE_PC)
+            .uleb(2)
+            .raw(&[0, 1, DW_LNE_END_SEQUENCE]);
+        let buf = build(&[section(".debug_line", SHT_PROGBITS, program(ops))], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+
+        assert_eq!(elf.line_programs.len(), 1);
+
+        let lp = &elf.line_programs[0];
+
+        assert_eq!((lp.offset, lp.size, lp.version), (0, 62, 3));
+        assert_eq!(
+            (lp.min_inst_length, lp.max_ops_per_inst, lp.default_is_stmt),
+            (1, 1, true)
+        );
+        assert_eq!((lp.line_base, lp.line_range, lp.opcode_base), (-5, 14, 13));
+        assert_eq!(lp.opcode_lengths, [0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1]);
+        assert_eq!(lp.directories, ["src"]);
+        assert_eq!(
+            lp.files
+                .iter()
+                .map(|file| (file.name.as_str(), file.directory))
+                .collect::<Vec<_>>(),
+            [("a.c", 1)]
+        );
+        assert_eq!(lp.file_name(1), Some("a.c"));
+        assert_eq!(
+            describe(lp),
+            (
+                vec![
+                    "40 11 DW_LNE_set_address 0x1000 None".to_string(),
+                    "51 2 DW_LNS_advance_line line +4 None".to_string(),
+                    "53 1 DW_LNS_copy  Some(0)".to_string(),
+                    "54 1 special opcode 75 address +0x4, line +1 Some(1)".to_string(),
+                    "55 2 DW_LNS_set_column 3 None".to_string(),
+                    "57 2 DW_LNS_advance_pc address +0x2 None".to_string(),
+                    "59 3 DW_LNE_end_sequence  Some(2)".to_string(),
+                ],
+                vec![
+                    "0x1000 1:5:0 true false".to_string(),
+                    "0x1004 1:6:0 true false".to_string(),
+                    "0x1006 1:6:3 true true".to_string(),
+                ]
+            )
+        );
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn malformed_programs() {
+        // advance_pc is missing its operand, the second program has an
+        // unknown version
+        let ops = Bytes::default().u8(DW_LNS_COPY).u8(DW_LNS_ADVANCE_PC);
+        let data = program(ops).u32(2).u16(6);
+        let buf = build(&[section(".debug_line", SHT_PROGBITS, data)], &[]);
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[1].file_offset;
+        let programs: Vec<_> = elf
+            .line_programs
+            .iter()
+            .map(|lp| (lp.offset, lp.size, lp.version, lp.ops.len(), lp.rows.len()))
+            .collect();
+
+        assert_eq!(programs, [(0, 42, 3, 1, 1), (42, 6, 6, 0, 0)]);
+
+        let diagnostics: Vec<_> = elf
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (&diagnostic.error, diagnostic.location))
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            [
+                (
+                    &ElfError::Truncated {
+                        offset: start + 41,
+                        structure: Structure::LineProgram(0),
+                        size: 2,
+                        available: 1,
+                    },
+                    Some((start + 41, 1))
+                ),
+                (
+                    &ElfError::UnknownValue {
+                        offset: start + 42,
+                        structure: Structure::LineProgram(1),
+                        field: "line_version",
+                        value: 6,
+                    },
+                    Some((start + 46, 2))
+                )
+            ]
+        );
+    }
+}
@@ -1,5 +1,16 @@
+mod compression;
 pub mod defs;
+mod dwarf;
 mod elf32;
 mod elf64;
 mod elfxx;
+pub mod error;
+mod frames;
+mod hashes;
+mod lines;
+mod notes;
 pub mod parser;
+pub mod ranges;
+#[cfg(test)]
+mod test_elf;
+mod versions;
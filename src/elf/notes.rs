@@ -0,0 +1,649 @@
+use super::defs::*;
+use super::parser::{MappedFile, Note, ParsedElf, ParsedIdent};
+use std::convert::TryInto;
+
+// rows describing a note and the fields of its desc, given as offset from the
+// start of the desc, length and name
+pub type NoteRow = (String, String);
+pub type NoteField = (usize, usize, &'static str);
+
+// both empty for the notes we don't know or can't make sense of
+pub fn decode_note(
+    elf: &ParsedElf,
+    ident: &ParsedIdent,
+    note: &Note,
+) -> (Vec<NoteRow>, Vec<NoteField>) {
+    let mut decoder = Decoder {
+        elf,
+        desc: &note.desc[..],
+        endianness: ident.endianness,
+        class: ident.class,
+        rows: vec![],
+        fields: vec![],
+    };
+
+    match decoder.decode(note.owner(), note.ntype) {
+        Some(()) => (decoder.rows, decoder.fields),
+        None => (vec![], vec![]),
+    }
+}
+
+// the table of NT_FILE: mapped address ranges and the files behind them
+pub fn parse_mapped_files(desc: &[u8], endianness: u8, class: u8) -> Option<Vec<MappedFile>> {
+    let reader = Reader::new(endianness, class);
+    let word = reader.word;
+    let count = reader.word(desc, 0)? as usize;
+    let page_size = reader.word(desc, word)?;
+    let names_start = count.checked_mul(3 * word)?.checked_add(2 * word)?;
+    let mut names = desc.get(names_start..)?.split(|&b| b == 0);
+    let mut files = vec![];
+
+    for i in 0..count {
+        let entry = 2 * word + i * 3 * word;
+
+        files.push(MappedFile {
+            start: reader.word(desc, entry)?,
+            end: reader.word(desc, entry + word)?,
+            offset: reader
+                .word(desc, entry + 2 * word)?
+                .checked_mul(page_size)?,
+            path: String::from_utf8_lossy(names.next()?).into_owned(),
+        });
+    }
+
+    Some(files)
+}
+
+fn word_size(class: u8) -> usize {
+    if class == ELF_CLASS64 {
+        8
+    } else {
+        4
+    }
+}
+
+// strings in notes are usually, but not always, NUL-terminated
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn flags_or_none(flags: String) -> String {
+    if flags.is_empty() {
+        String::from("none")
+    } else {
+        flags
+    }
+}
+
+// reads integers of the file's endianness, also used for elf/versions.rs,
+// elf/hashes.rs, elf/dwarf.rs, elf/frames.rs and elf/compression.rs
+#[derive(Clone, Copy)]
+pub(super) struct Reader {
+    endianness: u8,
+    pub(super) word: usize,
+}
+
+impl Reader {
+    pub(super) fn new(endianness: u8, class: u8) -> Reader {
+        Reader {
+            endianness,
+            word: word_size(class),
+        }
+    }
+
+    pub(super) fn u16(&self, buf: &[u8], offset: usize) -> Option<u16> {
+        let bytes = buf.get(offset..offset.checked_add(2)?)?.try_into().ok()?;
+
+        Some(if self.endianness == ELF_DATA2LSB {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    pub(super) fn u32(&self, buf: &[u8], offset: usize) -> Option<u32> {
+        let bytes = buf.get(offset..offset.checked_add(4)?)?.try_into().ok()?;
+
+        Some(if self.endianness == ELF_DATA2LSB {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    pub(super) fn u64(&self, buf: &[u8], offset: usize) -> Option<u64> {
+        let bytes = buf.get(offset..offset.checked_add(8)?)?.try_into().ok()?;
+
+        Some(if self.endianness == ELF_DATA2LSB {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    // unsigned integer of 1 to 8 bytes
+    pub(super) fn uint(&self, buf: &[u8], offset: usize, size: usize) -> Option<u64> {
+        let bytes = buf.get(offset..offset.checked_add(size)?)?;
+        let value = |acc: u64, byte: &u8| acc << 8 | *byte as u64;
+
+        if size > 8 {
+            None
+        } else if self.endianness == ELF_DATA2LSB {
+            Some(bytes.iter().rev().fold(0, value))
+        } else {
+            Some(bytes.iter().fold(0, value))
+        }
+    }
+
+    // long and pointer sized values
+    pub(super) fn word(&self, buf: &[u8], offset: usize) -> Option<u64> {
+        if self.word == 8 {
+            self.u64(buf, offset)
+        } else {
+            self.u32(buf, offset).map(u64::from)
+        }
+    }
+}
+
+struct Decoder<'a> {
+    elf: &'a ParsedElf<'a>,
+    desc: &'a [u8],
+    endianness: u8,
+    class: u8,
+    rows: Vec<NoteRow>,
+    fields: Vec<NoteField>,
+}
+
+impl Decoder<'_> {
+    fn decode(&mut self, owner: &[u8], ntype: u32) -> Option<()> {
+        let desc = self.desc;
+
+        match (owner, ntype) {
+            (b"GNU", NT_GNU_ABI_TAG) => self.abi_tag(),
+            (b"GNU", NT_GNU_PROPERTY_TYPE_0) => self.properties(),
+            (b"GNU", NT_GNU_GOLD_VERSION) => self.row("Linker version", c_string(desc)),
+            (b"Go", NT_GO_BUILD_ID) => self.row("Go build ID", c_string(desc)),
+            (b"FDO", NT_FDO_PACKAGING_METADATA) => self.package_metadata(),
+            (b"Android", NT_ANDROID_TYPE_IDENT) => self.android_ident(),
+            (b"FreeBSD", NT_FREEBSD_ABI_TAG) => {
+                let version = self.reader().u32(desc, 0)?;
+
+                self.row("FreeBSD version", version.to_string())
+            }
+            (b"FreeBSD", NT_FREEBSD_ARCH_TAG) => self.row("Architecture", c_string(desc)),
+            (b"FreeBSD", NT_FREEBSD_FEATURE_CTL) => {
+                let flags = self.reader().u32(desc, 0)?;
+
+                self.row("Feature control", format!("{:#x}", flags))
+            }
+            (b"NetBSD", NT_NETBSD_IDENT) => {
+                let version = self.reader().u32(desc, 0)?;
+                let release = format!(
+                    "{}.{} ({})",
+                    version / 100_000_000,
+                    version / 1_000_000 % 100,
+                    version
+                );
+
+                self.row("NetBSD version", release)
+            }
+            (b"CORE", NT_PRSTATUS) => self.prstatus(),
+            (b"CORE", NT_PRPSINFO) => self.prpsinfo(),
+            (b"CORE", NT_AUXV) => self.auxv(),
+            (b"CORE", NT_FILE) => self.mapped_files(),
+            (b"CORE", NT_SIGINFO) => self.siginfo(),
+            _ => None,
+        }
+    }
+
+    fn reader(&self) -> Reader {
+        Reader::new(self.endianness, self.class)
+    }
+
+    fn row<L: Into<String>>(&mut self, label: L, value: String) -> Option<()> {
+        self.rows.push((label.into(), value));
+
+        Some(())
+    }
+
+    fn field(&mut self, offset: usize, len: usize, name: &'static str) {
+        if offset.saturating_add(len) <= self.desc.len() {
+            self.fields.push((offset, len, name));
+        }
+    }
+
+    fn abi_tag(&mut self) -> Option<()> {
+        let (r, desc) = (self.reader(), self.desc);
+        let os = r.u32(desc, 0)?;
+        let version = format!(
+            "{}.{}.{}",
+            r.u32(desc, 4)?,
+            r.u32(desc, 8)?,
+            r.u32(desc, 12)?
+        );
+
+        self.row("OS", abi_tag_os_to_string(os));
+        self.row("Minimum kernel", version)
+    }
+
+    // an array of pr_type, pr_datasz and pr_data, each padded to the word size
+    fn properties(&mut self) -> Option<()> {
+        let (r, desc) = (self.reader(), self.desc);
+        let machine = self.elf.machine;
+        let alignment = r.word;
+        let mut start = 0;
+
+        while start < desc.len() {
+            let (ptype, datasz) = match (r.u32(desc, start), r.u32(desc, start + 4)) {
+                (Some(ptype), Some(datasz)) => (ptype, datasz as usize),
+                _ => {
+                    self.row("Truncated property", hex_bytes(&desc[start..]));
+                    break;
+                }
+            };
+            let data_start = start + 8;
+            let data = match desc.get(data_start..data_start.saturating_add(datasz)) {
+                Some(data) => data,
+                None => {
+                    self.row("Truncated property", hex_bytes(&desc[start..]));
+                    break;
+                }
+            };
+            let flags = r.u32(data, 0).filter(|_| datasz == 4);
+
+            let value = match (machine, ptype, flags) {
+                (_, GNU_PROPERTY_STACK_SIZE, _) => match datasz {
+                    4 => r.u32(data, 0).map(|size| format!("{:#x}", size)),
+                    8 => r.u64(data, 0).map(|size| format!("{:#x}", size)),
+                    _ => None,
+                },
+                (_, GNU_PROPERTY_NO_COPY_ON_PROTECTED, _) if datasz == 0 => {
+                    Some(String::from("yes"))
+                }
+                (_, GNU_PROPERTY_1_NEEDED, Some(flags)) => {
+                    Some(flags_or_none(gnu_property_1_needed_to_string(flags)))
+                }
+                (EM_AARCH64, GNU_PROPERTY_AARCH64_FEATURE_1_AND, Some(flags)) => {
+                    Some(flags_or_none(aarch64_feature_1_to_string(flags)))
+                }
+                (EM_386, x, Some(flags)) | (EM_X86_64, x, Some(flags)) => match x {
+                    GNU_PROPERTY_X86_FEATURE_1_AND => {
+                        Some(flags_or_none(x86_feature_1_to_string(flags)))
+                    }
+                    GNU_PROPERTY_X86_FEATURE_2_NEEDED | GNU_PROPERTY_X86_FEATURE_2_USED => {
+                        Some(flags_or_none(x86_feature_2_to_string(flags)))
+                    }
+                    GNU_PROPERTY_X86_ISA_1_NEEDED | GNU_PROPERTY_X86_ISA_1_USED => {
+                        Some(flags_or_none(x86_isa_1_to_string(flags)))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            self.row(
+                gnu_property_to_string(machine, ptype),
+                value.unwrap_or_else(|| hex_bytes(data)),
+            );
+
+            start = match data_start.checked_add(datasz + alignment - 1) {
+                Some(end) => end / alignment * alignment,
+                None => break,
+            };
+        }
+
+        Some(())
+    }
+
+    // API level, followed by the NDK version and build number in newer binaries
+    fn android_ident(&mut self) -> Option<()> {
+        let desc = self.desc;
+
+        self.row("API level", self.reader().u32(desc, 0)?.to_string());
+
+        if let (Some(version), Some(build)) = (desc.get(4..68), desc.get(68..132)) {
+            self.row("NDK version", c_string(version));
+            self.row("NDK build number", c_string(build));
+        }
+
+        Some(())
+    }
+
+    // https://systemd.io/ELF_PACKAGE_METADATA/ is a flat JSON object, anything
+    // fancier than that is shown as it is
+    fn package_metadata(&mut self) -> Option<()> {
+        let json = c_string(self.desc);
+
+        match parse_flat_object(&json) {
+            Some(fields) if !fields.is_empty() => self.rows.extend(fields),
+            _ => self.rows.push((String::from("Package metadata"), json)),
+        }
+
+        Some(())
+    }
+
+    // struct elf_prstatus of 64-bit Linux, whose layout is the same on all
+    // architectures up to pr_reg
+    fn prstatus(&mut self) -> Option<()> {
+        if self.class != ELF_CLASS64 {
+            return None;
+        }
+
+        let (r, desc) = (self.reader(), self.desc);
+        let regs: &[&str] = match self.elf.machine {
+            EM_X86_64 => &X86_64_REGS,
+            EM_AARCH64 => &AARCH64_REGS,
+            _ => &[],
+        };
+
+        let signal = r.u16(desc, 12)?;
+        let (pid, ppid) = (r.u32(desc, 32)?, r.u32(desc, 36)?);
+        let (pgrp, sid) = (r.u32(desc, 40)?, r.u32(desc, 44)?);
+        let time = |offset| -> Option<String> {
+            Some(format!(
+                "{}.{:06} s",
+                r.u64(desc, offset)?,
+                r.u64(desc, offset + 8)?
+            ))
+        };
+        let (utime, stime) = (time(48)?, time(64)?);
+
+        self.row("Signal", signal_to_string(u32::from(signal)));
+        self.row("PID", pid.to_string());
+        self.row("PPID", ppid.to_string());
+        self.row("PGRP", pgrp.to_string());
+        self.row("SID", sid.to_string());
+        self.row("Pending signals", format!("{:#x}", r.u64(desc, 16)?));
+        self.row("Held signals", format!("{:#x}", r.u64(desc, 24)?));
+        self.row("User time", utime);
+        self.row("System time", stime);
+
+        self.field(0, 12, "pr_info");
+        self.field(12, 2, "pr_cursig");
+        self.field(16, 8, "pr_sigpend");
+        self.field(24, 8, "pr_sighold");
+        self.field(32, 4, "pr_pid");
+        self.field(36, 4, "pr_ppid");
+        self.field(40, 4, "pr_pgrp");
+        self.field(44, 4, "pr_sid");
+        self.field(48, 16, "pr_utime");
+        self.field(64, 16, "pr_stime");
+        self.field(80, 16, "pr_cutime");
+        self.field(96, 16, "pr_cstime");
+
+        if regs.is_empty() || desc.len() < 112 + regs.len() * 8 {
+            return Some(());
+        }
+
+        for (i, reg) in regs.iter().enumerate() {
+            self.row(*reg, format!("{:#018x}", r.u64(desc, 112 + i * 8)?));
+        }
+
+        self.field(112, regs.len() * 8, "pr_reg");
+        self.field(112 + regs.len() * 8, 4, "pr_fpvalid");
+
+        Some(())
+    }
+
+    // struct elf_prpsinfo of 64-bit Linux
+    fn prpsinfo(&mut self) -> Option<()> {
+        if self.class != ELF_CLASS64 || self.desc.len() < 136 {
+            return None;
+        }
+
+        let (r, desc) = (self.reader(), self.desc);
+        let state = format!("{} ({})", char::from(desc[1]), desc[0]);
+
+        self.row("State", state);
+        self.row("Zombie", (desc[2] != 0).to_string());
+        self.row("Nice", (desc[3] as i8).to_string());
+        self.row("Flags", format!("{:#x}", r.u64(desc, 8)?));
+        self.row("UID", r.u32(desc, 16)?.to_string());
+        self.row("GID", r.u32(desc, 20)?.to_string());
+        self.row("PID", r.u32(desc, 24)?.to_string());
+        self.row("PPID", r.u32(desc, 28)?.to_string());
+        self.row("PGRP", r.u32(desc, 32)?.to_string());
+        self.row("SID", r.u32(desc, 36)?.to_string());
+        self.row("Command", c_string(&desc[40..56]));
+        self.row("Arguments", c_string(&desc[56..136]));
+
+        self.field(0, 1, "pr_state");
+        self.field(1, 1, "pr_sname");
+        self.field(2, 1, "pr_zomb");
+        self.field(3, 1, "pr_nice");
+        self.field(8, 8, "pr_flag");
+        self.field(16, 4, "pr_uid");
+        self.field(20, 4, "pr_gid");
+        self.field(24, 4, "pr_pid");
+        self.field(28, 4, "pr_ppid");
+        self.field(32, 4, "pr_pgrp");
+        self.field(36, 4, "pr_sid");
+        self.field(40, 16, "pr_fname");
+        self.field(56, 80, "pr_psargs");
+
+        Some(())
+    }
+
+    // a string the process had in memory, if the core has the page it's on
+    fn string_at(&self, vaddr: u64) -> Option<String> {
+        let contents: &[u8] = self.elf.contents;
+        let start = self.elf.vaddr_to_offset(vaddr as usize)?;
+
+        let bytes = contents.get(start..)?;
+
+        Some(c_string(&bytes[..bytes.len().min(256)]))
+    }
+
+    // pairs of a_type and a_val, up to AT_NULL
+    fn auxv(&mut self) -> Option<()> {
+        let (r, desc) = (self.reader(), self.desc);
+        let word = r.word;
+        let mut offset = 0;
+
+        while let (Some(atype), Some(val)) = (r.word(desc, offset), r.word(desc, offset + word)) {
+            let value = match atype {
+                AT_PHENT | AT_PHNUM | AT_PAGESZ | AT_UID | AT_EUID | AT_GID | AT_EGID
+                | AT_CLKTCK | AT_SECURE | AT_MINSIGSTKSZ | AT_RSEQ_FEATURE_SIZE | AT_RSEQ_ALIGN => {
+                    val.to_string()
+                }
+                AT_PLATFORM | AT_BASE_PLATFORM | AT_EXECFN => match self.string_at(val) {
+                    Some(string) => format!("{:#x} ({})", val, string),
+                    None => format!("{:#x}", val),
+                },
+                _ => format!("{:#x}", val),
+            };
+
+            self.row(auxv_type_to_string(atype), value);
+            self.field(offset, 2 * word, "auxv_entry");
+
+            offset += 2 * word;
+
+            if atype == AT_NULL {
+                break;
+            }
+        }
+
+        Some(())
+    }
+
+    // mapped files are matched with the LOAD segments holding their memory
+    fn mapped_files(&mut self) -> Option<()> {
+        let files = parse_mapped_files(self.desc, self.endianness, self.class)?;
+        let word = self.reader().word;
+
+        self.row(
+            "Page size",
+            self.reader().word(self.desc, word)?.to_string(),
+        );
+
+        for file in files.iter() {
+            let segment = self
+                .elf
+                .phdrs
+                .iter()
+                .position(|phdr| phdr.ptype == PT_LOAD && phdr.vaddr as u64 == file.start);
+            let mut value = format!("{} at {:#x}", file.path, file.offset);
+
+            if let Some(idx) = segment {
+                value += &format!(", segment {}", idx);
+            }
+
+            self.row(format!("{:#x}-{:#x}", file.start, file.end), value);
+        }
+
+        let names_start = 2 * word + files.len() * 3 * word;
+
+        self.field(0, word, "file_count");
+        self.field(word, word, "file_pagesz");
+
+        for i in 0..files.len() {
+            self.field(2 * word + i * 3 * word, 3 * word, "file_entry");
+        }
+
+        self.field(names_start, self.desc.len() - names_start, "file_names");
+
+        Some(())
+    }
+
+    // siginfo_t, whose union is interpreted by signal and si_code
+    fn siginfo(&mut self) -> Option<()> {
+        let (r, desc) = (self.reader(), self.desc);
+        let signo = r.u32(desc, 0)?;
+        let code = r.u32(desc, 8)? as i32;
+        // the union is aligned to the pointer size
+        let union = if r.word == 8 { 16 } else { 12 };
+
+        self.row("Signal", signal_to_string(signo));
+        self.row("Errno", r.u32(desc, 4)?.to_string());
+        self.row("Code", code.to_string());
+
+        self.field(0, 4, "si_signo");
+        self.field(4, 4, "si_errno");
+        self.field(8, 4, "si_code");
+
+        // SIGILL, SIGTRAP, SIGBUS, SIGFPE and SIGSEGV raised by the kernel
+        if matches!(signo, 4 | 5 | 7 | 8 | 11) && code > 0 {
+            self.row("Fault address", format!("{:#x}", r.word(desc, union)?));
+            self.field(union, r.word, "si_addr");
+        } else if code <= 0 {
+            self.row("Sender PID", r.u32(desc, union)?.to_string());
+            self.row("Sender UID", r.u32(desc, union + 4)?.to_string());
+            self.field(union, 4, "si_pid");
+            self.field(union + 4, 4, "si_uid");
+        }
+
+        Some(())
+    }
+}
+
+fn parse_flat_object(json: &str) -> Option<Vec<(String, String)>> {
+    let mut chars = json.trim().chars().peekable();
+    let mut fields = vec![];
+
+    if chars.next()? != '{' {
+        return None;
+    }
+
+    loop {
+        skip_whitespace(&mut chars);
+
+        match chars.peek()? {
+            '}' if fields.is_empty() => {
+                chars.next();
+                break;
+            }
+            '"' => {}
+            _ => return None,
+        }
+
+        let key = parse_string(&mut chars)?;
+
+        skip_whitespace(&mut chars);
+
+        if chars.next()? != ':' {
+            return None;
+        }
+
+        skip_whitespace(&mut chars);
+
+        let value = if *chars.peek()? == '"' {
+            parse_string(&mut chars)?
+        } else {
+            let mut value = String::new();
+
+            while let Some(&c) = chars.peek() {
+                if c == ',' || c == '}' || c.is_whitespace() {
+                    break;
+                }
+
+                if c == '{' || c == '[' {
+                    return None;
+                }
+
+                value.push(c);
+                chars.next();
+            }
+
+            value
+        };
+
+        fields.push((key, value));
+
+        skip_whitespace(&mut chars);
+
+        match chars.next()? {
+            ',' => {}
+            '}' => break,
+            _ => return None,
+        }
+    }
+
+    skip_whitespace(&mut chars);
+
+    if chars.next().is_some() {
+        return None;
+    }
+
+    Some(fields)
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    let mut s = String::new();
+
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    loop {
+        match chars.next()? {
+            '"' => return Some(s),
+            '\\' => match chars.next()? {
+                'n' => s.push('\n'),
+                't' => s.push('\t'),
+                'r' => s.push('\r'),
+                'b' => s.push('\u{8}'),
+                'f' => s.push('\u{c}'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let code = u32::from_str_radix(&hex, 16).ok()?;
+
+                    s.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                }
+                c => s.push(c),
+            },
+            c => s.push(c),
+        }
+    }
+}
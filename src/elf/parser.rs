@@ -2,16 +2,19 @@ use super::defs::*;
 use super::elf32::Elf32;
 use super::elf64::Elf64;
 use super::elfxx::ElfXX;
+use super::error::{ElfError, Structure};
+use super::notes::{decode_note, parse_mapped_files};
+use super::ranges::Ranges;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
+use std::ops::Deref;
+use std::rc::Rc;
 
 pub type InfoTuple = (&'static str, &'static str, String);
 
-pub type ReadErr = std::array::TryFromSliceError;
-
 #[repr(u8)]
 #[derive(Clone, PartialEq)]
 pub enum RangeType {
-    End,
     Ident,
     FileHeader,
     HeaderField(&'static str),
@@ -21,13 +24,29 @@ pub enum RangeType {
     ShdrField(&'static str),
     Segment(u16),
     Section(u16),
-    SegmentSubrange,
-}
-
-// Interval tree that allows querying point for all intervals that intersect it should be better.
-// We can't beat O(n * m) but the average case should improve.
-pub struct Ranges {
-    pub data: Vec<Vec<RangeType>>,
+    Note(u32),
+    NoteField(&'static str),
+    Symbol(u16, u32),
+    SymbolField(&'static str),
+    Relocation(u16, u32),
+    RelocationField(&'static str),
+    Dynamic(u32),
+    DynamicField(&'static str),
+    Version(u16, u32),
+    VersionField(&'static str),
+    HashField(&'static str),
+    DwarfUnit(u32),
+    DwarfField(&'static str),
+    Die(u32, u32),
+    DieAttribute(u32, u32, u16),
+    LineProgram(u32),
+    LineOp(u32, u32),
+    FrameRecord(u32),
+    FrameField(&'static str),
+    CfaOp(u32, u32),
+    FrameHdrEntry(u32),
+    ChdrField(&'static str),
+    Malformed,
 }
 
 pub struct ParsedIdent {
@@ -49,8 +68,34 @@ pub struct ParsedElf<'a> {
     pub shdrs: Vec<ParsedShdr>,
     pub strtab: StrTab<'a>,
     pub shstrndx: u16,
+    // layout of the headers, used for locating fields of malformed ones
+    pub ehdr_size: usize,
+    pub shoff: usize,
+    pub shdr_size: usize,
+    pub machine: u16,
     pub shnstrtab: StrTab<'a>,
     pub notes: Vec<Note>,
+    // NT_FILE of core dumps
+    pub mapped_files: Vec<MappedFile>,
+    pub symtabs: BTreeMap<u16, Vec<Symbol>>,
+    pub relocations: BTreeMap<u16, Vec<Relocation>>,
+    pub dynamic: Vec<DynamicEntry>,
+    // .gnu.version by section index, one entry per symbol of the linked table
+    pub versyms: BTreeMap<u16, Vec<u16>>,
+    // .gnu.version_r and .gnu.version_d by section index
+    pub versions: BTreeMap<u16, Vec<VersionRecord>>,
+    // .hash and .gnu.hash by section index
+    pub hash_tables: BTreeMap<u16, HashTable>,
+    // units of all .debug_info sections, in file order
+    pub dwarf_units: Vec<DwarfUnit>,
+    // programs of all .debug_line sections, in file order
+    pub line_programs: Vec<LineProgram>,
+    // CIEs and FDEs of .eh_frame, in file order
+    pub frame_records: Vec<FrameRecord>,
+    pub eh_frame_hdr: Option<EhFrameHdr>,
+    // SHF_COMPRESSED and legacy .zdebug sections by section index
+    pub compressed: BTreeMap<u16, CompressedSection>,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 pub struct ParsedPhdr {
@@ -80,6 +125,263 @@ pub struct Note {
     pub name: Vec<u8>,
     pub desc: Vec<u8>,
     pub ntype: u32,
+    // location of the whole note, header and padding included
+    pub offset: usize,
+    pub size: usize,
+    pub desc_offset: usize,
+    // a note can be both in a segment and a section covering the same bytes
+    pub segment: Option<u16>,
+    pub section: Option<u16>,
+    // rows describing the desc if its type is known, see elf/notes.rs
+    pub decoded: Vec<(String, String)>,
+}
+
+pub struct MappedFile {
+    pub start: u64,
+    pub end: u64,
+    pub offset: u64,
+    pub path: String,
+}
+
+#[derive(Clone, Copy)]
+enum NoteArea {
+    Segment(u16),
+    Section(u16),
+}
+
+pub struct Symbol {
+    pub name: String,
+    pub value: usize,
+    pub size: usize,
+    pub binding: u8,
+    pub stype: u8,
+    pub visibility: u8,
+    pub shndx: u16,
+}
+
+// entries of the version sections in the order they are found following
+// their linked lists, the auxiliary ones right after the entry they belong to
+pub enum VersionRecord {
+    Need {
+        version: u16,
+        file: String,
+    },
+    NeedAux {
+        hash: u32,
+        flags: u16,
+        index: u16,
+        name: String,
+    },
+    Def {
+        version: u16,
+        flags: u16,
+        index: u16,
+        hash: u32,
+        // of the first Verdaux
+        name: String,
+    },
+    DefAux {
+        name: String,
+    },
+}
+
+// Bucket values are symbol indices. Chains of SysV tables are indexed by
+// symbol, those of GNU ones start at `symoffset` and hold hash values.
+pub enum HashTable {
+    Sysv {
+        buckets: Vec<u32>,
+        chains: Vec<u32>,
+    },
+    Gnu {
+        symoffset: u32,
+        bloom_shift: u32,
+        // bloom words are of the file's class
+        bloom_bits: u32,
+        bloom: Vec<u64>,
+        buckets: Vec<u32>,
+        chains: Vec<u32>,
+    },
+}
+
+// Offsets are relative to the start of the .debug_info section, as they are
+// in references between DIEs.
+pub struct DwarfUnit {
+    pub section: u16,
+    pub offset: usize,
+    // of the header and the DIEs
+    pub size: usize,
+    pub format64: bool,
+    pub version: u16,
+    pub unit_type: u8,
+    pub address_size: u8,
+    pub abbrev_offset: u64,
+    pub dies: Vec<Die>,
+}
+
+// the DIE tree, flattened in file order
+pub struct Die {
+    pub offset: usize,
+    // of the abbreviation code and attributes, not of the children
+    pub size: usize,
+    pub depth: usize,
+    pub tag: u64,
+    pub children: bool,
+    pub attributes: Vec<DieAttribute>,
+}
+
+pub struct DieAttribute {
+    pub name: u64,
+    pub form: u64,
+    pub offset: usize,
+    pub size: usize,
+    pub value: String,
+    // .debug_info offset of the DIE referred to
+    pub reference: Option<usize>,
+}
+
+// a line-number program of .debug_line and the line table it builds
+pub struct LineProgram {
+    pub section: u16,
+    pub offset: usize,
+    pub size: usize,
+    pub format64: bool,
+    pub version: u16,
+    pub min_inst_length: u8,
+    pub max_ops_per_inst: u8,
+    pub default_is_stmt: bool,
+    pub line_base: i8,
+    pub line_range: u8,
+    pub opcode_base: u8,
+    // operand counts of the standard opcodes, from opcode 1
+    pub opcode_lengths: Vec<u8>,
+    pub directories: Vec<String>,
+    pub files: Vec<LineFile>,
+    pub ops: Vec<LineOp>,
+    pub rows: Vec<LineRow>,
+}
+
+pub struct LineFile {
+    pub name: String,
+    pub directory: u64,
+}
+
+pub struct LineOp {
+    // relative to .debug_line
+    pub offset: usize,
+    pub size: usize,
+    pub name: String,
+    pub operands: String,
+    // index of the row appended to the table
+    pub row: Option<usize>,
+}
+
+pub struct LineRow {
+    pub address: u64,
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    // the address after the last instruction of a sequence
+    pub end_sequence: bool,
+}
+
+// a CIE or an FDE of .eh_frame. Offsets are file offsets since the section
+// may only be found through PT_GNU_EH_FRAME
+pub struct FrameRecord {
+    pub offset: usize,
+    pub size: usize,
+    pub address: u64,
+    pub entry: FrameEntry,
+    pub instructions: Vec<CfaOp>,
+}
+
+pub enum FrameEntry {
+    Cie(Cie),
+    Fde(Fde),
+}
+
+pub struct Cie {
+    pub version: u8,
+    pub augmentation: String,
+    pub code_alignment: u64,
+    pub data_alignment: i64,
+    pub return_register: u64,
+    // DW_EH_PE_* of the pointers in FDEs, DW_EH_PE_OMIT if they have no LSDA
+    pub fde_encoding: u8,
+    pub lsda_encoding: u8,
+    pub personality: Option<u64>,
+}
+
+pub struct Fde {
+    // index of the CIE in frame_records
+    pub cie: Option<usize>,
+    pub pc_begin: u64,
+    pub pc_range: u64,
+    pub lsda: Option<u64>,
+}
+
+pub struct CfaOp {
+    pub offset: usize,
+    pub size: usize,
+    pub name: String,
+    pub operands: String,
+}
+
+// .eh_frame_hdr and its binary search table, pointers decoded to addresses
+pub struct EhFrameHdr {
+    pub offset: usize,
+    pub size: usize,
+    pub address: u64,
+    pub version: u8,
+    pub eh_frame_ptr_encoding: u8,
+    pub fde_count_encoding: u8,
+    pub table_encoding: u8,
+    pub eh_frame_ptr: Option<u64>,
+    pub fde_count: Option<u64>,
+    // file offset of the table
+    pub table_offset: usize,
+    // initial location and FDE address
+    pub table: Vec<(u64, u64)>,
+}
+
+// Elf32_Chdr or Elf64_Chdr of a SHF_COMPRESSED section, or the "ZLIB" header
+// of a .zdebug one
+pub struct CompressedSection {
+    // ELFCOMPRESS_*, always ELFCOMPRESS_ZLIB for .zdebug sections
+    pub compression: u32,
+    pub legacy: bool,
+    // of the decompressed data, .zdebug headers have no alignment
+    pub size: u64,
+    pub alignment: u64,
+    pub header_size: usize,
+    // None if it can't be decompressed
+    pub data: Option<Rc<[u8]>>,
+}
+
+// bytes of a section as its decoders see them, see
+// ParsedElf::section_contents
+pub enum SectionContents<'a> {
+    File(&'a [u8]),
+    Decompressed(Rc<[u8]>),
+}
+
+pub struct Relocation {
+    pub offset: usize,
+    pub sym: u32,
+    pub rtype: u32,
+    pub addend: Option<i64>,
+}
+
+// problem found in a malformed file which didn't prevent parsing the rest of it
+pub struct Diagnostic {
+    pub error: ElfError,
+    // offset and length of the offending bytes, if known
+    pub location: Option<(usize, usize)>,
+}
+
+pub struct DynamicEntry {
+    pub tag: i64,
+    pub val: u64,
 }
 
 pub struct StrTab<'a> {
@@ -90,27 +392,62 @@ pub struct StrTab<'a> {
 impl RangeType {
     // this is a bit of a clusterfuck
     fn needs_class(&self) -> bool {
-        match self {
-            RangeType::ProgramHeader(_) => true,
-            RangeType::SectionHeader(_) => true,
-            RangeType::PhdrField(_) => true,
-            RangeType::ShdrField(_) => true,
-            RangeType::Segment(_) => true,
-            RangeType::Section(_) => true,
-            RangeType::SegmentSubrange => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            RangeType::ProgramHeader(_)
+                | RangeType::SectionHeader(_)
+                | RangeType::PhdrField(_)
+                | RangeType::ShdrField(_)
+                | RangeType::Segment(_)
+                | RangeType::Section(_)
+                | RangeType::Note(_)
+                | RangeType::NoteField(_)
+                | RangeType::Symbol(_, _)
+                | RangeType::SymbolField(_)
+                | RangeType::Relocation(_, _)
+                | RangeType::RelocationField(_)
+                | RangeType::Dynamic(_)
+                | RangeType::DynamicField(_)
+                | RangeType::Version(_, _)
+                | RangeType::VersionField(_)
+                | RangeType::HashField(_)
+                | RangeType::DwarfUnit(_)
+                | RangeType::DwarfField(_)
+                | RangeType::Die(_, _)
+                | RangeType::DieAttribute(_, _, _)
+                | RangeType::LineProgram(_)
+                | RangeType::LineOp(_, _)
+                | RangeType::FrameRecord(_)
+                | RangeType::FrameField(_)
+                | RangeType::CfaOp(_, _)
+                | RangeType::FrameHdrEntry(_)
+                | RangeType::ChdrField(_)
+                | RangeType::Malformed
+        )
     }
 
     // for those who need_class()
     fn needs_id(&self) -> bool {
-        match self {
-            RangeType::ProgramHeader(_) => true,
-            RangeType::SectionHeader(_) => true,
-            RangeType::Segment(_) => true,
-            RangeType::Section(_) => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            RangeType::ProgramHeader(_)
+                | RangeType::SectionHeader(_)
+                | RangeType::Segment(_)
+                | RangeType::Section(_)
+                | RangeType::Note(_)
+                | RangeType::Symbol(_, _)
+                | RangeType::Relocation(_, _)
+                | RangeType::Dynamic(_)
+                | RangeType::Version(_, _)
+                | RangeType::DwarfUnit(_)
+                | RangeType::Die(_, _)
+                | RangeType::DieAttribute(_, _, _)
+                | RangeType::LineProgram(_)
+                | RangeType::LineOp(_, _)
+                | RangeType::FrameRecord(_)
+                | RangeType::CfaOp(_, _)
+                | RangeType::FrameHdrEntry(_)
+        )
     }
 
     fn id(&self) -> String {
@@ -122,6 +459,21 @@ impl RangeType {
             RangeType::HeaderField(class) => String::from(*class),
             RangeType::Segment(idx) => format!("bin_segment{}", idx),
             RangeType::Section(idx) => format!("bin_section{}", idx),
+            RangeType::Note(idx) => format!("bin_note{}", idx),
+            RangeType::Symbol(section, idx) => format!("bin_sym{}_{}", section, idx),
+            RangeType::Relocation(section, idx) => format!("bin_rel{}_{}", section, idx),
+            RangeType::Dynamic(idx) => format!("bin_dyn_{}", idx),
+            RangeType::Version(section, idx) => format!("bin_ver{}_{}", section, idx),
+            RangeType::DwarfUnit(idx) => format!("bin_cu{}", idx),
+            RangeType::Die(unit, idx) => format!("bin_die{}_{}", unit, idx),
+            RangeType::DieAttribute(unit, die, idx) => {
+                format!("bin_die{}_{}_{}", unit, die, idx)
+            }
+            RangeType::LineProgram(idx) => format!("bin_lp{}", idx),
+            RangeType::LineOp(program, idx) => format!("bin_lop{}_{}", program, idx),
+            RangeType::FrameRecord(idx) => format!("bin_cfi{}", idx),
+            RangeType::CfaOp(record, idx) => format!("bin_cfa{}_{}", record, idx),
+            RangeType::FrameHdrEntry(idx) => format!("bin_ehhdr_{}", idx),
             _ => String::new(),
         }
     }
@@ -134,83 +486,112 @@ impl RangeType {
             RangeType::ShdrField(field) => format!("{} shdr_hover", field),
             RangeType::Segment(_) => String::from("segment"),
             RangeType::Section(_) => String::from("section"),
-            RangeType::SegmentSubrange => String::from("segment_subrange"),
+            RangeType::Note(_) => String::from("note"),
+            RangeType::NoteField(field) => format!("{} note_hover", field),
+            RangeType::Symbol(_, _) => String::from("sym"),
+            RangeType::SymbolField(field) => format!("{} sym_hover", field),
+            RangeType::Relocation(_, _) => String::from("rel"),
+            RangeType::RelocationField(field) => format!("{} rel_hover", field),
+            RangeType::Dynamic(_) => String::from("dyn"),
+            RangeType::DynamicField(field) => format!("{} dyn_hover", field),
+            RangeType::Version(_, _) => String::from("vers"),
+            RangeType::VersionField(field) => format!("{} vers_hover", field),
+            RangeType::HashField(field) => format!("{} hash_hover", field),
+            RangeType::DwarfUnit(_) => String::from("cu"),
+            RangeType::DwarfField(field) => format!("{} dwarf_hover", field),
+            RangeType::Die(_, _) => String::from("die"),
+            RangeType::DieAttribute(_, _, _) => String::from("die_attr"),
+            RangeType::LineProgram(_) => String::from("line_prog"),
+            RangeType::LineOp(_, _) => String::from("line_op"),
+            RangeType::FrameRecord(_) => String::from("cfi"),
+            RangeType::FrameField(field) => format!("{} cfi_hover", field),
+            RangeType::CfaOp(_, _) => String::from("cfa_op"),
+            RangeType::FrameHdrEntry(_) => String::from("hdr_entry"),
+            RangeType::ChdrField(field) => format!("{} chdr_hover", field),
+            RangeType::Malformed => String::from("malformed"),
             _ => String::new(),
         }
     }
 
     fn always_highlight(&self) -> bool {
         match self {
-            RangeType::HeaderField(class) => match *class {
-                "magic" => true,
-                "ver" => true,
-                "abi_ver" => true,
-                "pad" => true,
-                "e_version" => true,
-                "e_flags" => true,
-                "e_ehsize" => true,
-                "e_shstrndx" => true,
-                _ => false,
-            },
+            RangeType::HeaderField(class) => matches!(
+                *class,
+                "magic"
+                    | "ver"
+                    | "abi_ver"
+                    | "pad"
+                    | "e_version"
+                    | "e_flags"
+                    | "e_ehsize"
+                    | "e_shstrndx"
+            ),
             RangeType::Segment(_) => true,
             RangeType::Section(_) => true,
-            RangeType::SegmentSubrange => true,
+            RangeType::Note(_) => true,
             _ => false,
         }
     }
 
-    pub fn span_attributes(&self) -> String {
-        if self.needs_class() {
-            format!(
-                "{}class='{}{}'",
-                if self.needs_id() {
-                    format!("id='{}' ", self.id())
-                } else {
-                    String::new()
-                },
-                self.class(),
-                if self.always_highlight() {
-                    " hover"
-                } else {
-                    ""
-                }
-            )
+    // id and class of the range's span in the dump, also used by the lazily
+    // rendered dump (js/lazy.js)
+    pub fn html_id(&self) -> Option<String> {
+        if !self.needs_class() || self.needs_id() {
+            Some(self.id())
         } else {
-            format!("id='{}'", self.id())
-                + if self.always_highlight() {
-                    " class='hover'"
-                } else {
-                    ""
-                }
+            None
         }
     }
 
-    pub fn skippable(&self) -> bool {
-        match self {
-            RangeType::Segment(_) => true,
-            RangeType::Section(_) => true,
-            _ => false,
+    pub fn html_class(&self) -> Option<String> {
+        let hover = if self.always_highlight() {
+            " hover"
+        } else {
+            ""
+        };
+
+        if self.needs_class() {
+            Some(self.class() + hover)
+        } else if self.always_highlight() {
+            Some(String::from("hover"))
+        } else {
+            None
         }
     }
-}
 
-impl Ranges {
-    fn new(capacity: usize) -> Ranges {
-        Ranges {
-            data: vec![vec![]; capacity],
+    // `id_prefix` distinguishes the same range in the hex and ASCII dumps
+    pub fn span_attributes(&self, id_prefix: &str) -> String {
+        let mut attributes = vec![];
+
+        if let Some(id) = self.html_id() {
+            attributes.push(format!("id='{}{}'", id_prefix, id));
         }
+
+        if let Some(class) = self.html_class() {
+            attributes.push(format!("class='{}'", class));
+        }
+
+        attributes.join(" ")
     }
 
-    pub fn add_range(&mut self, start: usize, end: usize, range_type: RangeType) {
-        self.data[start].push(range_type);
-        self.data[start + end - 1].push(RangeType::End);
+    // the rest of a span that had to be closed early because a range inside
+    // of it ended, ids stay unique and collapse.js finds the parts by this
+    pub fn continuation_attributes(&self, id_prefix: &str) -> String {
+        let mut attributes = vec![];
+
+        if let Some(id) = self.html_id() {
+            attributes.push(format!("data-continues='{}{}'", id_prefix, id));
+        }
+
+        if let Some(class) = self.html_class() {
+            attributes.push(format!("class='{}'", class));
+        }
+
+        attributes.join(" ")
     }
 
-    pub fn lookup_range_ends(&self, point: usize) -> usize {
-        self.data[point]
-            .iter()
-            .filter(|&x| *x == RangeType::End)
-            .count()
+    pub fn skippable(&self) -> bool {
+        matches!(self, RangeType::Segment(_) | RangeType::Section(_))
     }
 }
 
@@ -228,15 +609,20 @@ impl ParsedIdent {
 }
 
 impl ParsedElf<'_> {
-    pub fn from_bytes<'a>(filename: &str, buf: &'a [u8]) -> Result<ParsedElf<'a>, String> {
+    pub fn from_bytes<'a>(filename: &str, buf: &'a [u8]) -> Result<ParsedElf<'a>, ElfError> {
         if buf.len() < ELF_EI_NIDENT as usize {
-            return Err(String::from("file is smaller than ELF header's e_ident"));
+            return Err(ElfError::Truncated {
+                offset: 0,
+                structure: Structure::Ident,
+                size: ELF_EI_NIDENT as usize,
+                available: buf.len(),
+            });
         }
 
-        let ident = ParsedIdent::from_bytes(&buf);
+        let ident = ParsedIdent::from_bytes(buf);
 
         if ident.magic != [0x7f, b'E', b'L', b'F'] {
-            return Err(String::from("mismatched magic: not an ELF file"));
+            return Err(ElfError::BadMagic { magic: ident.magic });
         }
 
         let mut elf = ParsedElf {
@@ -249,29 +635,88 @@ impl ParsedElf<'_> {
             shdrs: vec![],
             strtab: StrTab::empty(),
             shstrndx: 0,
+            ehdr_size: 0,
+            shoff: 0,
+            shdr_size: 0,
+            machine: 0,
             shnstrtab: StrTab::empty(),
             notes: vec![],
+            mapped_files: vec![],
+            symtabs: BTreeMap::new(),
+            relocations: BTreeMap::new(),
+            dynamic: vec![],
+            versyms: BTreeMap::new(),
+            versions: BTreeMap::new(),
+            hash_tables: BTreeMap::new(),
+            dwarf_units: vec![],
+            line_programs: vec![],
+            frame_records: vec![],
+            eh_frame_hdr: None,
+            compressed: BTreeMap::new(),
+            diagnostics: vec![],
         };
 
         elf.push_file_info();
 
-        elf.push_ident_info(&ident)?;
+        let known_encoding = elf.push_ident_info(&ident);
 
-        if ident.class == ELF_CLASS32 {
-            Elf32::parse(&buf, &ident, &mut elf)?;
-        } else {
-            Elf64::parse(&buf, &ident, &mut elf)?;
+        if known_encoding {
+            if ident.class == ELF_CLASS32 {
+                Elf32::parse(buf, &ident, &mut elf)?;
+            } else {
+                Elf64::parse(buf, &ident, &mut elf)?;
+            }
         }
 
         elf.add_ident_ranges();
 
         elf.parse_string_tables();
 
-        elf.parse_notes(ident.endianness);
+        elf.parse_notes(&ident);
+
+        elf.parse_versions(ident.endianness);
+
+        elf.parse_hash_tables(&ident);
+
+        elf.parse_compressed_sections(&ident);
+
+        elf.parse_debug_info(&ident);
+        elf.parse_debug_line(&ident);
+
+        elf.parse_eh_frame(&ident);
+
+        elf.add_diagnostic_ranges();
 
         Ok(elf)
     }
 
+    pub fn diagnose(&mut self, error: ElfError, location: Option<(usize, usize)>) {
+        self.diagnostics.push(Diagnostic { error, location });
+    }
+
+    // `struct_start` and `struct_len` locate the structure containing `field`,
+    // whose ranges have to be added already
+    pub fn diagnose_field(
+        &mut self,
+        error: ElfError,
+        struct_start: usize,
+        struct_len: usize,
+        field: &str,
+    ) {
+        let location = self.ranges.lookup_field(struct_start, struct_len, field);
+
+        self.diagnose(error, location);
+    }
+
+    // added last so that they are the innermost spans and their color wins
+    fn add_diagnostic_ranges(&mut self) {
+        for diagnostic in &self.diagnostics {
+            if let Some((start, len)) = diagnostic.location {
+                self.ranges.add_range(start, len, RangeType::Malformed);
+            }
+        }
+    }
+
     fn push_file_info(&mut self) {
         self.information
             .push(("file_name", "File name", self.filename.to_string()));
@@ -290,28 +735,45 @@ impl ParsedElf<'_> {
             .push(("file_size", "File size", file_size_str));
     }
 
-    fn push_ident_info(&mut self, ident: &ParsedIdent) -> Result<(), String> {
-        let information = &mut self.information;
+    // returns whether class and data encoding are known, i.e. rest of the file can be parsed
+    fn push_ident_info(&mut self, ident: &ParsedIdent) -> bool {
+        let mut known_encoding = true;
 
-        information.push((
-            "class",
-            "Object class",
-            match ident.class {
-                ELF_CLASS32 => String::from("32-bit"),
-                ELF_CLASS64 => String::from("64-bit"),
-                x => return Err(format!("Unknown bitness: {}", x)),
-            },
-        ));
+        let class = match ident.class {
+            ELF_CLASS32 => String::from("32-bit"),
+            ELF_CLASS64 => String::from("64-bit"),
+            x => {
+                let offset = ELF_EI_CLASS as usize;
 
-        information.push((
-            "data",
-            "Data encoding",
-            match ident.endianness {
-                ELF_DATA2LSB => String::from("Little endian"),
-                ELF_DATA2MSB => String::from("Big endian"),
-                x => return Err(format!("Unknown endianness: {}", x)),
-            },
-        ));
+                known_encoding = false;
+                self.diagnose(ElfError::BadClass { offset, class: x }, Some((offset, 1)));
+                format!("Unknown: {}", x)
+            }
+        };
+
+        let endianness = match ident.endianness {
+            ELF_DATA2LSB => String::from("Little endian"),
+            ELF_DATA2MSB => String::from("Big endian"),
+            x => {
+                let offset = ELF_EI_DATA as usize;
+
+                known_encoding = false;
+                self.diagnose(
+                    ElfError::BadEncoding {
+                        offset,
+                        encoding: x,
+                    },
+                    Some((offset, 1)),
+                );
+                format!("Unknown: {}", x)
+            }
+        };
+
+        self.information.push(("class", "Object class", class));
+
+        self.information.push(("data", "Data encoding", endianness));
+
+        let information = &mut self.information;
 
         if ident.version != ELF_EV_CURRENT {
             information.push(("ver", "Uncommon version(!)", format!("{}", ident.version)));
@@ -339,7 +801,7 @@ impl ParsedElf<'_> {
             ));
         }
 
-        Ok(())
+        known_encoding
     }
 
     fn add_ident_ranges(&mut self) {
@@ -357,50 +819,113 @@ impl ParsedElf<'_> {
     }
 
     fn find_strtab_shdr(shdrs: &[ParsedShdr]) -> Option<&ParsedShdr> {
-        for shdr in shdrs {
-            if shdr.shtype == SHT_STRTAB {
-                return Some(shdr);
-            }
-        }
-
-        None
+        shdrs.iter().find(|shdr| shdr.shtype == SHT_STRTAB)
     }
 
     fn parse_string_tables(&mut self) {
         let shdr = ParsedElf::find_strtab_shdr(&self.shdrs);
 
-        if let Some(shdr) = shdr {
-            let section = &self.contents[shdr.file_offset..shdr.file_offset + shdr.size];
+        if let Some(section) = shdr.and_then(|shdr| self.section_data(shdr)) {
+            self.strtab.populate(section, section.len());
+        }
+
+        if self.shstrndx == SHN_UNDEF {
+            return;
+        }
 
-            self.strtab.populate(&section, shdr.size);
+        match self.shdrs.get(self.shstrndx as usize) {
+            Some(shdr) => {
+                if let Some(section) = self.section_data(shdr) {
+                    self.shnstrtab.populate(section, section.len());
+                }
+            }
+            None => {
+                let error = ElfError::OutOfBounds {
+                    offset: 0,
+                    structure: Structure::FileHeader,
+                    field: "e_shstrndx",
+                    value: self.shstrndx as u64,
+                    limit: self.shdrs.len() as u64,
+                };
+
+                self.diagnose_field(error, 0, self.ehdr_size, "e_shstrndx");
+
+                return;
+            }
         }
 
-        if self.shstrndx != SHN_UNDEF {
-            let shdr = &self.shdrs[self.shstrndx as usize];
-            let section = &self.contents[shdr.file_offset..shdr.file_offset + shdr.size];
+        for i in 0..self.shdrs.len() {
+            let name = self.shdrs[i].name;
+
+            if name >= self.shnstrtab.size() {
+                let location = self.shdr_location(i);
+                let error = ElfError::OutOfBounds {
+                    offset: location.0,
+                    structure: Structure::SectionHeader(i as u16),
+                    field: "sh_name",
+                    value: name as u64,
+                    limit: self.shnstrtab.size() as u64,
+                };
 
-            self.shnstrtab.populate(&section, shdr.size);
+                self.diagnose_field(error, location.0, location.1, "sh_name");
+            }
         }
     }
 
-    fn parse_notes(&mut self, endianness: u8) {
+    pub fn shdr_location(&self, idx: usize) -> (usize, usize) {
+        (self.shoff + idx * self.shdr_size, self.shdr_size)
+    }
+
+    fn parse_notes(&mut self, ident: &ParsedIdent) {
         let mut areas = vec![];
 
-        for phdr in &self.phdrs {
+        for (idx, phdr) in self.phdrs.iter().enumerate() {
             if phdr.ptype == PT_NOTE {
-                areas.push((phdr.file_offset, phdr.file_size));
+                areas.push((
+                    phdr.file_offset,
+                    phdr.file_size,
+                    phdr.alignment,
+                    NoteArea::Segment(idx as u16),
+                ));
             }
         }
 
-        for (start, len) in areas {
-            self.parse_note_area(start, len, endianness);
+        for (idx, shdr) in self.shdrs.iter().enumerate() {
+            if shdr.shtype == SHT_NOTE {
+                areas.push((
+                    shdr.file_offset,
+                    shdr.size,
+                    shdr.addralign,
+                    NoteArea::Section(idx as u16),
+                ));
+            }
+        }
+
+        for (start, len, alignment, owner) in areas {
+            self.parse_note_area(start, len, alignment, owner, ident);
         }
     }
 
     // this is pretty ugly in terms of raw addressing, unwieldly offsets, etc.
     // area here stands for segment or section because notes may come from either of them.
-    fn parse_note_area(&mut self, area_start: usize, area_size: usize, endianness: u8) {
-        let area = &self.contents[area_start..area_start + area_size];
+    // notes that were already found in another area are only attributed to this one too.
+    fn parse_note_area(
+        &mut self,
+        area_start: usize,
+        area_size: usize,
+        alignment: usize,
+        owner: NoteArea,
+        ident: &ParsedIdent,
+    ) {
+        let endianness = ident.endianness;
+        let contents: &[u8] = self.contents;
+        let area = match contents.get(area_start..area_start.saturating_add(area_size)) {
+            Some(area) => area,
+            None => return,
+        };
+        // 64-bit notes such as NT_GNU_PROPERTY_TYPE_0 are 8-byte aligned, the
+        // alignment of the area they are in tells which one is used
+        let alignment = if alignment == 8 { 8 } else { 4 };
         let mut start = 0;
 
         loop {
@@ -408,17 +933,66 @@ impl ParsedElf<'_> {
                 break;
             }
 
-            match Note::from_bytes(&area[start..area_size], endianness) {
-                None => break,
-                Some((note, len_taken)) => {
-                    // not an ideal look because this can also be a SectionSubrange
+            let offset = area_start + start;
+
+            if let Some(note) = self.notes.iter_mut().find(|note| note.offset == offset) {
+                match owner {
+                    NoteArea::Segment(idx) => note.segment = Some(idx),
+                    NoteArea::Section(idx) => note.section = Some(idx),
+                }
+
+                start += note.size;
+                continue;
+            }
+
+            match Note::from_bytes(&area[start..area_size], offset, alignment, endianness) {
+                None => {
+                    let remaining = &area[start..area_size];
+                    let location = Some((offset, remaining.len()));
+                    let error = ElfError::Truncated {
+                        offset,
+                        structure: Structure::Note,
+                        size: Note::needed_size(remaining, alignment, endianness),
+                        available: remaining.len(),
+                    };
+
+                    if !self.diagnostics.iter().any(|d| d.location == location) {
+                        self.diagnose(error, location);
+                    }
+
+                    break;
+                }
+                Some(mut note) => {
+                    match owner {
+                        NoteArea::Segment(idx) => note.segment = Some(idx),
+                        NoteArea::Section(idx) => note.section = Some(idx),
+                    }
+
+                    let (decoded, fields) = decode_note(self, ident, &note);
+
+                    if note.owner() == b"CORE" && note.ntype == NT_FILE {
+                        let files = parse_mapped_files(&note.desc, endianness, ident.class);
+
+                        self.mapped_files.extend(files.unwrap_or_default());
+                    }
+
                     self.ranges.add_range(
-                        area_start + start,
-                        len_taken,
-                        RangeType::SegmentSubrange,
+                        offset,
+                        note.size,
+                        RangeType::Note(self.notes.len() as u32),
                     );
+
+                    for (start, len, field) in fields {
+                        self.ranges.add_range(
+                            note.desc_offset + start,
+                            len,
+                            RangeType::NoteField(field),
+                        );
+                    }
+
+                    note.decoded = decoded;
+                    start += note.size;
                     self.notes.push(note);
-                    start += len_taken;
                 }
             }
         }
@@ -426,23 +1000,57 @@ impl ParsedElf<'_> {
 }
 
 impl Note {
-    fn from_bytes(buf: &[u8], endianness: u8) -> Option<(Note, usize)> {
-        let (namesz, descsz, ntype) = Note::read_header(buf, endianness).ok()?;
-        let (namesz, descsz) = (namesz as usize, descsz as usize);
+    // the name without its terminator and padding
+    pub fn owner(&self) -> &[u8] {
+        let len = self.name.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
 
-        let name = buf.get(12..12 + namesz)?.to_vec();
-        let desc = buf.get(12 + namesz..12 + namesz + descsz)?.to_vec();
+        &self.name[..len]
+    }
 
-        let mut len: usize = 12 + namesz + descsz;
+    // the name is padded to `alignment`, and so is the desc after it
+    fn from_bytes(buf: &[u8], offset: usize, alignment: usize, endianness: u8) -> Option<Note> {
+        let (namesz, descsz, ntype) = Note::read_header(buf.get(0..12)?, endianness).ok()?;
+        let (namesz, descsz) = (namesz as usize, descsz as usize);
+        let name_end = 12usize.checked_add(namesz)?;
+        let desc_start = align_up(name_end, alignment)?;
+        let desc_end = desc_start.checked_add(descsz)?;
+
+        let name = buf.get(12..name_end)?.to_vec();
+        let desc = buf.get(desc_start..desc_end)?.to_vec();
+
+        // padding after the last note of an area may be left out
+        let size = align_up(desc_end, alignment)?.min(buf.len());
+
+        Some(Note {
+            name,
+            desc,
+            ntype,
+            offset,
+            size,
+            desc_offset: offset + desc_start,
+            segment: None,
+            section: None,
+            decoded: vec![],
+        })
+    }
 
-        while len % 4 != 0 {
-            len += 1;
+    // size of a note whose header starts at `buf`, for reporting truncated ones
+    fn needed_size(buf: &[u8], alignment: usize, endianness: u8) -> usize {
+        match buf
+            .get(0..12)
+            .map(|header| Note::read_header(header, endianness))
+        {
+            Some(Ok((namesz, descsz, _))) => align_up(12 + namesz as usize, alignment)
+                .and_then(|desc_start| desc_start.checked_add(descsz as usize))
+                .unwrap_or(usize::MAX),
+            _ => 12,
         }
-
-        Some((Note { name, desc, ntype }, len))
     }
 
-    fn read_header(buf: &[u8], endianness: u8) -> Result<(u32, u32, u32), ReadErr> {
+    fn read_header(
+        buf: &[u8],
+        endianness: u8,
+    ) -> Result<(u32, u32, u32), std::array::TryFromSliceError> {
         Ok(if endianness == ELF_DATA2LSB {
             (
                 u32::from_le_bytes(buf[0..4].try_into()?),
@@ -459,9 +1067,99 @@ impl Note {
     }
 }
 
+fn align_up(value: usize, alignment: usize) -> Option<usize> {
+    Some(value.checked_add(alignment - 1)? / alignment * alignment)
+}
+
+impl<'a> ParsedElf<'a> {
+    pub fn section_data(&self, shdr: &ParsedShdr) -> Option<&'a [u8]> {
+        if shdr.shtype == SHT_NOBITS {
+            return None;
+        }
+
+        let contents: &'a [u8] = self.contents;
+
+        contents.get(shdr.file_offset..shdr.file_offset.checked_add(shdr.size)?)
+    }
+
+    // None unless the address is mapped from bytes that are in the file
+    pub fn vaddr_to_offset(&self, vaddr: usize) -> Option<usize> {
+        self.vaddr_mapping(vaddr).map(|(offset, _)| offset)
+    }
+
+    // the file offset of the address along with the LOAD segment mapping it
+    pub fn vaddr_mapping(&self, vaddr: usize) -> Option<(usize, &ParsedPhdr)> {
+        let phdr = self
+            .phdrs
+            .iter()
+            .filter(|phdr| phdr.ptype == PT_LOAD)
+            .find(|phdr| vaddr >= phdr.vaddr && vaddr - phdr.vaddr < phdr.file_size)?;
+
+        phdr.file_offset
+            .checked_add(vaddr - phdr.vaddr)
+            .filter(|&offset| offset < self.contents.len())
+            .map(|offset| (offset, phdr))
+    }
+
+    pub fn dynamic_value(&self, tag: i64) -> Option<u64> {
+        self.dynamic
+            .iter()
+            .find(|entry| entry.tag == tag)
+            .map(|entry| entry.val)
+    }
+
+    // string table used by DT_NEEDED and friends, falls back to the section
+    // linked to .dynamic if DT_STRTAB can't be mapped to file contents
+    pub fn dynamic_strtab(&self) -> StrTab<'a> {
+        let contents: &'a [u8] = self.contents;
+        let mut strtab = StrTab::empty();
+
+        if let (Some(addr), Some(size)) =
+            (self.dynamic_value(DT_STRTAB), self.dynamic_value(DT_STRSZ))
+        {
+            let section = self
+                .vaddr_to_offset(addr as usize)
+                .and_then(|start| contents.get(start..start.checked_add(size as usize)?));
+
+            if let Some(section) = section {
+                strtab.populate(section, section.len());
+                return strtab;
+            }
+        }
+
+        match self.shdrs.iter().find(|shdr| shdr.shtype == SHT_DYNAMIC) {
+            Some(shdr) => self.linked_strtab(shdr),
+            None => strtab,
+        }
+    }
+
+    pub fn linked_strtab(&self, shdr: &ParsedShdr) -> StrTab<'a> {
+        let mut strtab = StrTab::empty();
+
+        if let Some(linked) = self.shdrs.get(shdr.link) {
+            if let Some(section) = self.section_data(linked) {
+                strtab.populate(section, section.len());
+            }
+        }
+
+        strtab
+    }
+}
+
+impl Deref for SectionContents<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            SectionContents::File(data) => data,
+            SectionContents::Decompressed(data) => data,
+        }
+    }
+}
+
 impl<'a> StrTab<'a> {
     // decently ugly
-    fn empty() -> StrTab<'static> {
+    pub fn empty() -> StrTab<'static> {
         StrTab {
             strings: &[],
             section_size: 0,
@@ -474,21 +1172,19 @@ impl<'a> StrTab<'a> {
         self.section_size = section_size;
     }
 
-    pub fn get(&self, idx: usize) -> &str {
-        let start_idx = idx;
-
-        for end_idx in start_idx..start_idx + self.section_size {
-            if self.strings[end_idx] == 0 {
-                let maybe = std::str::from_utf8(&self.strings[start_idx..end_idx]);
+    pub fn size(&self) -> usize {
+        self.section_size
+    }
 
-                if maybe.is_err() {
-                    return "";
-                }
+    pub fn get(&self, idx: usize) -> &str {
+        let tail = match self.strings.get(idx..self.section_size) {
+            Some(tail) => tail,
+            None => return "",
+        };
 
-                return maybe.unwrap();
-            }
+        match tail.iter().position(|&c| c == 0) {
+            Some(len) => std::str::from_utf8(&tail[..len]).unwrap_or(""),
+            None => "",
         }
-
-        ""
     }
 }
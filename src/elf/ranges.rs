@@ -0,0 +1,451 @@
+use super::parser::RangeType;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// Bytes start..end of the file, end is exclusive
+#[derive(Clone, PartialEq)]
+pub struct Range {
+    pub start: usize,
+    pub end: usize,
+    pub range_type: RangeType,
+}
+
+struct Node {
+    range: Range,
+    // insertion order, breaks ties between ranges with the same bytes
+    seq: usize,
+    priority: u64,
+    // largest end in this subtree
+    max_end: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+// Interval tree: a treap ordered by start, longer ranges first, whose nodes also
+// know the largest end in their subtree, so that subtrees which can't
+// intersect a query are skipped. Memory and time depend on the number of
+// ranges, not on the size of the file.
+pub struct Ranges {
+    file_size: usize,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+pub enum RangeEvent<'a> {
+    // before the byte at range.start
+    Open(&'a Range),
+    // after the byte at range.end - 1
+    Close(&'a Range),
+}
+
+// splitmix64 of the insertion order, the treap stays balanced even though
+// ranges are mostly added in increasing order
+fn priority(seq: usize) -> u64 {
+    let mut x = (seq as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    x ^ (x >> 31)
+}
+
+impl Ranges {
+    pub fn new(file_size: usize) -> Ranges {
+        Ranges {
+            file_size,
+            nodes: vec![],
+            root: None,
+        }
+    }
+
+    // ranges from malformed files can go past the end of it, these are cut short
+    pub fn add_range(&mut self, start: usize, len: usize, range_type: RangeType) {
+        let end = start.saturating_add(len).min(self.file_size);
+
+        if start >= end {
+            return;
+        }
+
+        let seq = self.nodes.len();
+
+        self.nodes.push(Node {
+            range: Range {
+                start,
+                end,
+                range_type,
+            },
+            seq,
+            priority: priority(seq),
+            max_end: end,
+            left: None,
+            right: None,
+        });
+
+        self.root = Some(self.insert(self.root, seq));
+    }
+
+    // ranges with the same start are ordered outer first, whichever of them
+    // was added first, e.g. PT_TLS and PT_GNU_RELRO
+    fn key(&self, idx: usize) -> (usize, Reverse<usize>, usize) {
+        let node = &self.nodes[idx];
+
+        (node.range.start, Reverse(node.range.end), node.seq)
+    }
+
+    fn update(&mut self, idx: usize) {
+        let node = &self.nodes[idx];
+        let max_end = [node.left, node.right]
+            .iter()
+            .flatten()
+            .map(|&child| self.nodes[child].max_end)
+            .fold(node.range.end, usize::max);
+
+        self.nodes[idx].max_end = max_end;
+    }
+
+    fn rotate_right(&mut self, idx: usize) -> usize {
+        let left = self.nodes[idx].left.unwrap();
+
+        self.nodes[idx].left = self.nodes[left].right;
+        self.nodes[left].right = Some(idx);
+        self.update(idx);
+        self.update(left);
+
+        left
+    }
+
+    fn rotate_left(&mut self, idx: usize) -> usize {
+        let right = self.nodes[idx].right.unwrap();
+
+        self.nodes[idx].right = self.nodes[right].left;
+        self.nodes[right].left = Some(idx);
+        self.update(idx);
+        self.update(right);
+
+        right
+    }
+
+    fn insert(&mut self, subtree: Option<usize>, new: usize) -> usize {
+        let mut idx = match subtree {
+            Some(idx) => idx,
+            None => return new,
+        };
+
+        if self.key(new) < self.key(idx) {
+            let left = self.insert(self.nodes[idx].left, new);
+
+            self.nodes[idx].left = Some(left);
+
+            if self.nodes[left].priority > self.nodes[idx].priority {
+                idx = self.rotate_right(idx);
+            }
+        } else {
+            let right = self.insert(self.nodes[idx].right, new);
+
+            self.nodes[idx].right = Some(right);
+
+            if self.nodes[right].priority > self.nodes[idx].priority {
+                idx = self.rotate_left(idx);
+            }
+        }
+
+        self.update(idx);
+
+        idx
+    }
+
+    fn collect<'a>(
+        &'a self,
+        subtree: Option<usize>,
+        start: usize,
+        end: usize,
+        out: &mut Vec<&'a Range>,
+    ) {
+        let node = match subtree {
+            Some(idx) => &self.nodes[idx],
+            None => return,
+        };
+
+        if node.max_end <= start {
+            return;
+        }
+
+        self.collect(node.left, start, end, out);
+
+        // everything to the right starts even later
+        if node.range.start >= end {
+            return;
+        }
+
+        if node.range.end > start {
+            out.push(&node.range);
+        }
+
+        self.collect(node.right, start, end, out);
+    }
+
+    // ranges intersecting start..end, ordered by start with outer ranges first
+    pub fn overlapping(&self, start: usize, end: usize) -> Vec<&Range> {
+        let mut out = vec![];
+
+        self.collect(self.root, start, end, &mut out);
+
+        out
+    }
+
+    // ranges containing the byte at `point`, outermost first
+    pub fn at(&self, point: usize) -> Vec<&Range> {
+        self.overlapping(point, point.saturating_add(1))
+    }
+
+    // finds a field by name inside of a structure
+    pub fn lookup_field(
+        &self,
+        struct_start: usize,
+        struct_len: usize,
+        field: &str,
+    ) -> Option<(usize, usize)> {
+        let struct_end = struct_start.saturating_add(struct_len);
+
+        self.overlapping(struct_start, struct_end)
+            .into_iter()
+            .filter(|range| range.start >= struct_start)
+            .find(|range| match range.range_type {
+                RangeType::HeaderField(name)
+                | RangeType::PhdrField(name)
+                | RangeType::ShdrField(name)
+                | RangeType::SymbolField(name)
+                | RangeType::RelocationField(name)
+                | RangeType::DynamicField(name)
+                | RangeType::VersionField(name)
+                | RangeType::HashField(name)
+                | RangeType::DwarfField(name)
+                | RangeType::FrameField(name)
+                | RangeType::ChdrField(name) => name == field,
+                _ => false,
+            })
+            .map(|range| (range.start, range.end - range.start))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // all ranges ordered by start with outer ranges first
+    pub fn iter(&self) -> Iter<'_> {
+        let mut iter = Iter {
+            ranges: self,
+            stack: vec![],
+        };
+
+        iter.push_left(self.root);
+
+        iter
+    }
+
+    // open and close events in file order. At one offset ranges are closed
+    // before others are opened, and ranges ending at the same byte are closed
+    // innermost first
+    pub fn events(&self) -> Events<'_> {
+        Events {
+            starts: self.iter(),
+            open: BinaryHeap::new(),
+            opened: 0,
+        }
+    }
+}
+
+pub struct Iter<'a> {
+    ranges: &'a Ranges,
+    stack: Vec<usize>,
+}
+
+impl<'a> Iter<'a> {
+    fn push_left(&mut self, mut subtree: Option<usize>) {
+        while let Some(idx) = subtree {
+            self.stack.push(idx);
+            subtree = self.ranges.nodes[idx].left;
+        }
+    }
+
+    fn next_node(&mut self) -> Option<usize> {
+        let idx = self.stack.pop()?;
+
+        self.push_left(self.ranges.nodes[idx].right);
+
+        Some(idx)
+    }
+
+    fn peek_node(&self) -> Option<usize> {
+        self.stack.last().copied()
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Range;
+
+    fn next(&mut self) -> Option<&'a Range> {
+        let ranges = self.ranges;
+
+        self.next_node().map(|idx| &ranges.nodes[idx].range)
+    }
+}
+
+pub struct Events<'a> {
+    starts: Iter<'a>,
+    // (end, order of opening, node) of the ranges opened so far
+    open: BinaryHeap<(Reverse<usize>, usize, usize)>,
+    opened: usize,
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = RangeEvent<'a>;
+
+    fn next(&mut self) -> Option<RangeEvent<'a>> {
+        let nodes = &self.starts.ranges.nodes;
+        let next_start = self.starts.peek_node().map(|idx| nodes[idx].range.start);
+
+        if let Some(&(Reverse(end), _, idx)) = self.open.peek() {
+            if next_start.is_none_or(|start| end <= start) {
+                self.open.pop();
+
+                return Some(RangeEvent::Close(&nodes[idx].range));
+            }
+        }
+
+        let idx = self.starts.next_node()?;
+
+        self.open
+            .push((Reverse(nodes[idx].range.end), self.opened, idx));
+        self.opened += 1;
+
+        Some(RangeEvent::Open(&nodes[idx].range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(ranges: Vec<&Range>) -> Vec<(usize, usize)> {
+        ranges
+            .iter()
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    fn events(ranges: &Ranges) -> Vec<(char, usize, usize)> {
+        ranges
+            .events()
+            .map(|event| match event {
+                RangeEvent::Open(range) => ('o', range.start, range.end),
+                RangeEvent::Close(range) => ('c', range.start, range.end),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_ranges_open_outer_first_and_close_inner_first() {
+        let mut ranges = Ranges::new(16);
+
+        ranges.add_range(0, 10, RangeType::Segment(0));
+        ranges.add_range(2, 3, RangeType::Section(1));
+        ranges.add_range(10, 2, RangeType::Section(2));
+
+        assert_eq!(spans(ranges.iter().collect()), [(0, 10), (2, 5), (10, 12)]);
+        assert_eq!(
+            events(&ranges),
+            [
+                ('o', 0, 10),
+                ('o', 2, 5),
+                ('c', 2, 5),
+                ('c', 0, 10),
+                ('o', 10, 12),
+                ('c', 10, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn equal_starts_are_ordered_by_length() {
+        let mut ranges = Ranges::new(16);
+
+        ranges.add_range(4, 2, RangeType::Segment(0));
+        ranges.add_range(4, 8, RangeType::Segment(1));
+        ranges.add_range(4, 4, RangeType::Section(1));
+
+        assert_eq!(spans(ranges.iter().collect()), [(4, 12), (4, 8), (4, 6)]);
+        assert_eq!(
+            events(&ranges),
+            [
+                ('o', 4, 12),
+                ('o', 4, 8),
+                ('o', 4, 6),
+                ('c', 4, 6),
+                ('c', 4, 8),
+                ('c', 4, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn crossing_ranges_close_at_their_end() {
+        let mut ranges = Ranges::new(16);
+
+        ranges.add_range(0, 6, RangeType::Segment(0));
+        ranges.add_range(4, 6, RangeType::Section(1));
+        ranges.add_range(8, 4, RangeType::Section(2));
+
+        assert_eq!(
+            events(&ranges),
+            [
+                ('o', 0, 6),
+                ('o', 4, 10),
+                ('c', 0, 6),
+                ('o', 8, 12),
+                ('c', 4, 10),
+                ('c', 8, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn lookups_on_boundaries() {
+        let mut ranges = Ranges::new(32);
+
+        ranges.add_range(8, 8, RangeType::ProgramHeader(0));
+        ranges.add_range(8, 4, RangeType::PhdrField("p_type"));
+        ranges.add_range(12, 4, RangeType::PhdrField("p_flags"));
+        ranges.add_range(16, 4, RangeType::PhdrField("p_type"));
+
+        assert!(ranges.at(7).is_empty());
+        assert_eq!(spans(ranges.at(8)), [(8, 16), (8, 12)]);
+        assert_eq!(spans(ranges.at(15)), [(8, 16), (12, 16)]);
+        assert_eq!(spans(ranges.at(16)), [(16, 20)]);
+        assert!(ranges.at(20).is_empty());
+
+        assert_eq!(spans(ranges.overlapping(12, 16)), [(8, 16), (12, 16)]);
+        assert!(ranges.overlapping(0, 8).is_empty());
+
+        assert_eq!(ranges.lookup_field(8, 8, "p_type"), Some((8, 4)));
+        assert_eq!(ranges.lookup_field(8, 8, "p_flags"), Some((12, 4)));
+        assert_eq!(ranges.lookup_field(12, 4, "p_type"), None);
+        assert_eq!(ranges.lookup_field(16, 4, "p_type"), Some((16, 4)));
+    }
+
+    #[test]
+    fn ranges_past_the_end_are_cut_short() {
+        let mut ranges = Ranges::new(16);
+
+        ranges.add_range(12, 8, RangeType::Segment(0));
+        ranges.add_range(16, 4, RangeType::Segment(1));
+        ranges.add_range(4, 0, RangeType::Segment(2));
+        ranges.add_range(usize::MAX, 2, RangeType::Segment(3));
+
+        assert_eq!(spans(ranges.iter().collect()), [(12, 16)]);
+    }
+}
@@ -0,0 +1,189 @@
+// Little-endian ELF64 files put together byte by byte, for the decoders' tests
+
+use super::defs::*;
+
+#[derive(Default)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    pub fn u8(mut self, value: u8) -> Bytes {
+        self.0.push(value);
+        self
+    }
+
+    pub fn u16(mut self, value: u16) -> Bytes {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn u32(mut self, value: u32) -> Bytes {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn u64(mut self, value: u64) -> Bytes {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn uleb(mut self, mut value: u64) -> Bytes {
+        loop {
+            let byte = (value & 0x7f) as u8;
+
+            value >>= 7;
+
+            if value == 0 {
+                self.0.push(byte);
+                return self;
+            }
+
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    pub fn sleb(mut self, mut value: i64) -> Bytes {
+        loop {
+            let byte = (value & 0x7f) as u8;
+
+            value >>= 7;
+
+            if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                self.0.push(byte);
+                return self;
+            }
+
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    pub fn cstr(mut self, s: &str) -> Bytes {
+        self.0.extend_from_slice(s.as_bytes());
+        self.0.push(0);
+        self
+    }
+
+    pub fn raw(mut self, bytes: &[u8]) -> Bytes {
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    pub fn size(&self) -> usize {
+        self.0.len()
+    }
+}
+
+pub struct Section {
+    pub name: &'static str,
+    pub shtype: u32,
+    pub flags: u64,
+    pub addr: u64,
+    pub link: u32,
+    pub info: u32,
+    pub entsize: u64,
+    pub data: Vec<u8>,
+}
+
+pub fn section(name: &'static str, shtype: u32, data: Bytes) -> Section {
+    Section {
+        name,
+        shtype,
+        flags: 0,
+        addr: 0,
+        link: 0,
+        info: 0,
+        entsize: 0,
+        data: data.0,
+    }
+}
+
+// The sections follow a null one, so the first has index 1, and .shstrtab
+// comes last. Segments are (p_type, index of the section they map), with the
+// section's address as their own.
+pub fn build(sections: &[Section], segments: &[(u32, usize)]) -> Vec<u8> {
+    let phoff = 64;
+    let mut data = vec![0; phoff + 56 * segments.len()];
+    let mut offsets = vec![0];
+    let mut shstrtab = Bytes::default().u8(0);
+    let mut names = vec![0];
+
+    for section in sections {
+        data.resize(data.len().next_multiple_of(8), 0);
+        offsets.push(data.len());
+        data.extend_from_slice(&section.data);
+        names.push(shstrtab.size() as u32);
+        shstrtab = shstrtab.cstr(section.name);
+    }
+
+    names.push(shstrtab.size() as u32);
+    shstrtab = shstrtab.cstr(".shstrtab");
+    offsets.push(data.len());
+    data.extend_from_slice(&shstrtab.0);
+    data.resize(data.len().next_multiple_of(8), 0);
+
+    let shoff = data.len();
+    let shnum = sections.len() + 2;
+    let header = Bytes::default()
+        .raw(&[0x7f, b'E', b'L', b'F', ELF_CLASS64, ELF_DATA2LSB, 1])
+        .raw(&[0; 9])
+        .u16(ELF_ET_EXEC)
+        .u16(0x3e)
+        .u32(1)
+        .u64(0)
+        .u64(phoff as u64)
+        .u64(shoff as u64)
+        .u32(0)
+        .u16(64)
+        .u16(56)
+        .u16(segments.len() as u16)
+        .u16(64)
+        .u16(shnum as u16)
+        .u16(shnum as u16 - 1);
+
+    data[..64].copy_from_slice(&header.0);
+
+    for (i, &(ptype, idx)) in segments.iter().enumerate() {
+        let section = &sections[idx - 1];
+        let size = section.data.len() as u64;
+        let phdr = Bytes::default()
+            .u32(ptype)
+            .u32(4)
+            .u64(offsets[idx] as u64)
+            .u64(section.addr)
+            .u64(section.addr)
+            .u64(size)
+            .u64(size)
+            .u64(8);
+        let start = phoff + 56 * i;
+
+        data[start..start + 56].copy_from_slice(&phdr.0);
+    }
+
+    let mut shdrs = Bytes(data).raw(&[0; 64]);
+
+    for (i, section) in sections.iter().enumerate() {
+        shdrs = shdrs
+            .u32(names[i + 1])
+            .u32(section.shtype)
+            .u64(section.flags)
+            .u64(section.addr)
+            .u64(offsets[i + 1] as u64)
+            .u64(section.data.len() as u64)
+            .u32(section.link)
+            .u32(section.info)
+            .u64(8)
+            .u64(section.entsize);
+    }
+
+    shdrs
+        .u32(names[shnum - 1])
+        .u32(SHT_STRTAB)
+        .u64(0)
+        .u64(0)
+        .u64(offsets[shnum - 1] as u64)
+        .u64(shstrtab.size() as u64)
+        .u32(0)
+        .u32(0)
+        .u64(1)
+        .u64(0)
+        .0
+}
@@ -0,0 +1,619 @@
+use super::defs::*;
+use super::error::{ElfError, Structure};
+use super::notes::Reader;
+use super::parser::{ParsedElf, RangeType, VersionRecord};
+use std::collections::HashSet;
+
+// Version sections are linked lists of entries, each with a linked list of
+// auxiliary entries. Offsets in them are relative to the entry they are in.
+struct Layout {
+    size: usize,
+    // name, offset and size of every field, all of them are 2 or 4 bytes
+    fields: &'static [(&'static str, usize, usize)],
+    next: &'static str,
+    // string table offset of the file or version name
+    name: Option<&'static str>,
+    // count and offset of the auxiliary entries
+    aux: Option<(&'static str, &'static str)>,
+}
+
+const VERNEED: Layout = Layout {
+    size: 16,
+    fields: &[
+        ("vn_version", 0, 2),
+        ("vn_cnt", 2, 2),
+        ("vn_file", 4, 4),
+        ("vn_aux", 8, 4),
+        ("vn_next", 12, 4),
+    ],
+    next: "vn_next",
+    name: Some("vn_file"),
+    aux: Some(("vn_cnt", "vn_aux")),
+};
+
+const VERNAUX: Layout = Layout {
+    size: 16,
+    fields: &[
+        ("vna_hash", 0, 4),
+        ("vna_flags", 4, 2),
+        ("vna_other", 6, 2),
+        ("vna_name", 8, 4),
+        ("vna_next", 12, 4),
+    ],
+    next: "vna_next",
+    name: Some("vna_name"),
+    aux: None,
+};
+
+const VERDEF: Layout = Layout {
+    size: 20,
+    fields: &[
+        ("vd_version", 0, 2),
+        ("vd_flags", 2, 2),
+        ("vd_ndx", 4, 2),
+        ("vd_cnt", 6, 2),
+        ("vd_hash", 8, 4),
+        ("vd_aux", 12, 4),
+        ("vd_next", 16, 4),
+    ],
+    next: "vd_next",
+    name: None,
+    aux: Some(("vd_cnt", "vd_aux")),
+};
+
+const VERDAUX: Layout = Layout {
+    size: 8,
+    fields: &[("vda_name", 0, 4), ("vda_next", 4, 4)],
+    next: "vda_next",
+    name: Some("vda_name"),
+    aux: None,
+};
+
+// a record found in a version section, its bytes and whether it's auxiliary
+struct Found<'a> {
+    entry: &'a [u8],
+    aux: bool,
+}
+
+impl Layout {
+    fn read(&self, reader: Reader, entry: &[u8], field: &str) -> u32 {
+        let (_, offset, size) = self
+            .fields
+            .iter()
+            .find(|(name, _, _)| *name == field)
+            .expect("field of the layout");
+
+        let value = if *size == 2 {
+            reader.u16(entry, *offset).map(u32::from)
+        } else {
+            reader.u32(entry, *offset)
+        };
+
+        value.unwrap_or(0)
+    }
+}
+
+impl<'a> ParsedElf<'a> {
+    pub(super) fn parse_versions(&mut self, endianness: u8) {
+        // all fields have fixed sizes, the class doesn't matter
+        let reader = Reader::new(endianness, ELF_CLASS64);
+
+        for i in 0..self.shdrs.len() {
+            match self.shdrs[i].shtype {
+                SHT_VER_SYM => self.parse_versym(i, reader),
+                SHT_VER_NEED => self.parse_verneed(i, reader),
+                SHT_VER_DEF => self.parse_verdef(i, reader),
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_versym(&mut self, idx: usize, reader: Reader) {
+        let shdr = &self.shdrs[idx];
+        let section = match self.section_data(shdr) {
+            Some(section) => section,
+            None => return,
+        };
+        let start = shdr.file_offset;
+        let mut versyms = vec![];
+
+        for j in 0..section.len() / 2 {
+            versyms.push(reader.u16(section, j * 2).unwrap_or(0));
+
+            self.ranges
+                .add_range(start + j * 2, 2, RangeType::Version(idx as u16, j as u32));
+        }
+
+        self.versyms.insert(idx as u16, versyms);
+    }
+
+    fn parse_verneed(&mut self, idx: usize, reader: Reader) {
+        let strtab = self.linked_strtab(&self.shdrs[idx]);
+        let found = self.walk_versions(idx, reader, &VERNEED, &VERNAUX);
+
+        let records = found
+            .iter()
+            .map(|record| {
+                let (layout, entry) = (if record.aux { &VERNAUX } else { &VERNEED }, record.entry);
+                let read = |field| layout.read(reader, entry, field);
+
+                if record.aux {
+                    VersionRecord::NeedAux {
+                        hash: read("vna_hash"),
+                        flags: read("vna_flags") as u16,
+                        index: read("vna_other") as u16,
+                        name: strtab.get(read("vna_name") as usize).to_string(),
+                    }
+                } else {
+                    VersionRecord::Need {
+                        version: read("vn_version") as u16,
+                        file: strtab.get(read("vn_file") as usize).to_string(),
+                    }
+                }
+            })
+            .collect();
+
+        self.versions.insert(idx as u16, records);
+    }
+
+    fn parse_verdef(&mut self, idx: usize, reader: Reader) {
+        let strtab = self.linked_strtab(&self.shdrs[idx]);
+        let found = self.walk_versions(idx, reader, &VERDEF, &VERDAUX);
+        let mut records = vec![];
+
+        for (i, record) in found.iter().enumerate() {
+            let (layout, entry) = (if record.aux { &VERDAUX } else { &VERDEF }, record.entry);
+            let read = |field| layout.read(reader, entry, field);
+
+            records.push(if record.aux {
+                VersionRecord::DefAux {
+                    name: strtab.get(read("vda_name") as usize).to_string(),
+                }
+            } else {
+                // the version itself is named by the first Verdaux
+                let name = match found.get(i + 1).filter(|next| next.aux) {
+                    Some(next) => VERDAUX.read(reader, next.entry, "vda_name") as usize,
+                    None => usize::MAX,
+                };
+
+                VersionRecord::Def {
+                    version: read("vd_version") as u16,
+                    flags: read("vd_flags") as u16,
+                    index: read("vd_ndx") as u16,
+                    hash: read("vd_hash"),
+                    name: strtab.get(name).to_string(),
+                }
+            });
+        }
+
+        self.versions.insert(idx as u16, records);
+    }
+
+    // sh_info is the number of entries
+    fn walk_versions(
+        &mut self,
+        idx: usize,
+        reader: Reader,
+        outer: &Layout,
+        inner: &Layout,
+    ) -> Vec<Found<'a>> {
+        let shdr = &self.shdrs[idx];
+        let section = match self.section_data(shdr) {
+            Some(section) => section,
+            None => return vec![],
+        };
+        let count = shdr.info;
+        let strtab_size = self.linked_strtab(shdr).size();
+        let mut found: Vec<Found> = vec![];
+        let mut visited = HashSet::new();
+        let mut offset = 0;
+
+        for _ in 0..count {
+            let record = found.len();
+            let entry = match self.version_record(idx, section, offset, outer, &mut visited) {
+                Some(entry) => entry,
+                None => break,
+            };
+            let (count_field, aux_field) = outer.aux.expect("entries with auxiliary ones");
+            let aux_count = outer.read(reader, entry, count_field);
+            // record the link is in, the link and its value
+            let mut link = (
+                (offset, record, outer),
+                aux_field,
+                outer.read(reader, entry, aux_field),
+            );
+
+            self.check_version_name(idx, (offset, record, outer), reader, entry, strtab_size);
+            found.push(Found { entry, aux: false });
+
+            for _ in 0..aux_count {
+                let (from, field, delta) = link;
+                let aux_offset =
+                    match self.follow_version_link(idx, section.len(), from, field, delta) {
+                        Some(aux_offset) => aux_offset,
+                        None => break,
+                    };
+                let aux_record = found.len();
+                let aux = match self.version_record(idx, section, aux_offset, inner, &mut visited) {
+                    Some(aux) => aux,
+                    None => break,
+                };
+                let at = (aux_offset, aux_record, inner);
+
+                self.check_version_name(idx, at, reader, aux, strtab_size);
+                found.push(Found {
+                    entry: aux,
+                    aux: true,
+                });
+
+                let next = inner.read(reader, aux, inner.next);
+
+                if next == 0 {
+                    break;
+                }
+
+                link = (at, inner.next, next);
+            }
+
+            let next = outer.read(reader, entry, outer.next);
+            let from = (offset, record, outer);
+
+            if next == 0 {
+                break;
+            }
+
+            offset = match self.follow_version_link(idx, section.len(), from, outer.next, next) {
+                Some(offset) => offset,
+                None => break,
+            };
+        }
+
+        found
+    }
+
+    // adds the ranges of the record at `offset` of the section, None if it
+    // doesn't fit or was already seen, so that loops in the lists end
+    fn version_record(
+        &mut self,
+        idx: usize,
+        section: &'a [u8],
+        offset: usize,
+        layout: &Layout,
+        visited: &mut HashSet<usize>,
+    ) -> Option<&'a [u8]> {
+        let start = self.shdrs[idx].file_offset + offset;
+        let record = visited.len() as u32;
+
+        let entry = match section.get(offset..offset.saturating_add(layout.size)) {
+            Some(entry) => entry,
+            None => {
+                let available = section.len().saturating_sub(offset);
+                let error = ElfError::Truncated {
+                    offset: start,
+                    structure: Structure::VersionRecord(idx as u16, record),
+                    size: layout.size,
+                    available,
+                };
+
+                self.diagnose(error, Some((start, available)));
+
+                return None;
+            }
+        };
+
+        if !visited.insert(offset) {
+            return None;
+        }
+
+        self.ranges
+            .add_range(start, layout.size, RangeType::Version(idx as u16, record));
+
+        for (field, field_offset, size) in layout.fields.iter() {
+            self.ranges
+                .add_range(start + field_offset, *size, RangeType::VersionField(field));
+        }
+
+        Some(entry)
+    }
+
+    // offset in the section `delta` bytes after the record `from` (its offset,
+    // index and layout), whose `field` holds the delta
+    fn follow_version_link(
+        &mut self,
+        idx: usize,
+        section_len: usize,
+        from: (usize, usize, &Layout),
+        field: &'static str,
+        delta: u32,
+    ) -> Option<usize> {
+        let (base, record, layout) = from;
+        let target = base
+            .checked_add(delta as usize)
+            .filter(|&target| target < section_len);
+
+        if target.is_none() {
+            let start = self.shdrs[idx].file_offset + base;
+            let error = ElfError::OutOfBounds {
+                offset: start,
+                structure: Structure::VersionRecord(idx as u16, record as u32),
+                field,
+                value: delta as u64,
+                limit: (section_len - base) as u64,
+            };
+
+            self.diagnose_field(error, start, layout.size, field);
+        }
+
+        target
+    }
+
+    fn check_version_name(
+        &mut self,
+        idx: usize,
+        at: (usize, usize, &Layout),
+        reader: Reader,
+        entry: &[u8],
+        strtab_size: usize,
+    ) {
+        let (offset, record, layout) = at;
+        let field = match layout.name {
+            Some(field) => field,
+            None => return,
+        };
+        let name = layout.read(reader, entry, field) as usize;
+
+        if name >= strtab_size {
+            let start = self.shdrs[idx].file_offset + offset;
+            let error = ElfError::OutOfBounds {
+                offset: start,
+                structure: Structure::VersionRecord(idx as u16, record as u32),
+                field,
+                value: name as u64,
+                limit: strtab_size as u64,
+            };
+
+            self.diagnose_field(error, start, layout.size, field);
+        }
+    }
+
+    // name of the version of a symbol with "@" in front, "@@" if the version
+    // is the symbol's default one, None for local and global symbols
+    pub fn symbol_version(&self, symtab: u16, sym: usize) -> Option<String> {
+        let versym_section = self
+            .shdrs
+            .iter()
+            .position(|shdr| shdr.shtype == SHT_VER_SYM && shdr.link == symtab as usize)?;
+        let versym = *self.versyms.get(&(versym_section as u16))?.get(sym)?;
+        let index = versym & VERSYM_VERSION;
+
+        if index == VER_NDX_LOCAL || index == VER_NDX_GLOBAL {
+            return None;
+        }
+
+        self.versions
+            .values()
+            .flatten()
+            .find_map(|record| match record {
+                VersionRecord::NeedAux { index: i, name, .. } if *i == index => {
+                    Some(format!("@{}", name))
+                }
+                VersionRecord::Def { index: i, name, .. } if *i == index => {
+                    if versym & VERSYM_HIDDEN != 0 {
+                        Some(format!("@{}", name))
+                    } else {
+                        Some(format!("@@{}", name))
+                    }
+                }
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_elf::{build, section, Bytes, Section};
+    use super::*;
+
+    fn dynstr(strings: &[&str]) -> Section {
+        let data = strings
+            .iter()
+            .fold(Bytes::default().u8(0), |data, s| data.cstr(s));
+
+        section(".dynstr", SHT_STRTAB, data)
+    }
+
+    fn version_section(name: &'static str, shtype: u32, count: u32, data: Bytes) -> Section {
+        Section {
+            link: 1,
+            info: count,
+            ..section(name, shtype, data)
+        }
+    }
+
+    fn describe(records: &[VersionRecord]) -> Vec<String> {
+        records
+            .iter()
+            .map(|record| match record {
+                VersionRecord::Need { version, file } => format!("need {} {}", version, file),
+                VersionRecord::NeedAux {
+                    hash,
+                    flags,
+                    index,
+                    name,
+                } => format!("needaux {:x} {} {} {}", hash, flags, index, name),
+                VersionRecord::Def {
+                    version,
+                    flags,
+                    index,
+                    hash,
+                    name,
+                } => format!("def {} {} {} {:x} {}", version, flags, index, hash, name),
+                VersionRecord::DefAux { name } => format!("defaux {}", name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn verneed_list_ends_at_a_record_already_read() {
+        // vn_next leads back to the Vernaux, sh_info allows a second Verneed
+        let data = Bytes::default()
+            .u16(1)
+            .u16(1)
+            .u32(1)
+            .u32(16)
+            .u32(16)
+            .u32(0x0969_1a75)
+            .u16(0)
+            .u16(2)
+            .u32(11)
+            .u32(0);
+        let buf = build(
+            &[
+                dynstr(&["libc.so.6", "GLIBC_2.2.5"]),
+                version_section(".gnu.version_r", SHT_VER_NEED, 2, data),
+            ],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+
+        assert_eq!(
+            describe(&elf.versions[&2]),
+            ["need 1 libc.so.6", "needaux 9691a75 0 2 GLIBC_2.2.5"]
+        );
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn verneed_out_of_bounds_name_and_link() {
+        let data = Bytes::default()
+            .u16(1)
+            .u16(1)
+            .u32(1)
+            .u32(16)
+            .u32(0x1000)
+            .u32(0x0969_1a75)
+            .u16(0)
+            .u16(2)
+            .u32(100)
+            .u32(0);
+        let buf = build(
+            &[
+                dynstr(&["libc.so.6", "GLIBC_2.2.5"]),
+                version_section(".gnu.version_r", SHT_VER_NEED, 2, data),
+            ],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[2].file_offset;
+
+        assert_eq!(
+            describe(&elf.versions[&2]),
+            ["need 1 libc.so.6", "needaux 9691a75 0 2 "]
+        );
+        assert_eq!(elf.diagnostics.len(), 2);
+        assert_eq!(
+            elf.diagnostics[0].error,
+            ElfError::OutOfBounds {
+                offset: start + 16,
+                structure: Structure::VersionRecord(2, 1),
+                field: "vna_name",
+                value: 100,
+                limit: 23,
+            }
+        );
+        assert_eq!(elf.diagnostics[0].location, Some((start + 24, 4)));
+        assert_eq!(
+            elf.diagnostics[1].error,
+            ElfError::OutOfBounds {
+                offset: start,
+                structure: Structure::VersionRecord(2, 0),
+                field: "vn_next",
+                value: 0x1000,
+                limit: 32,
+            }
+        );
+        assert_eq!(elf.diagnostics[1].location, Some((start + 12, 4)));
+    }
+
+    #[test]
+    fn verdef_aux_shared_by_two_entries_is_read_once() {
+        // both Verdefs point at the Verdaux at 48
+        let data = Bytes::default()
+            .u16(1)
+            .u16(1)
+            .u16(1)
+            .u16(1)
+            .u32(0x1234)
+            .u32(48)
+            .u32(20)
+            .u16(1)
+            .u16(0)
+            .u16(2)
+            .u16(1)
+            .u32(0x5678)
+            .u32(28)
+            .u32(0)
+            .u64(0)
+            .u32(1)
+            .u32(0);
+        let buf = build(
+            &[
+                dynstr(&["libfoo.so", "FOO_1"]),
+                version_section(".gnu.version_d", SHT_VER_DEF, 2, data),
+            ],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+
+        assert_eq!(
+            describe(&elf.versions[&2]),
+            [
+                "def 1 1 1 1234 libfoo.so",
+                "defaux libfoo.so",
+                "def 1 0 2 5678 "
+            ]
+        );
+        assert!(elf.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn verdef_truncated_entry() {
+        // the second Verdef starts 8 bytes before the end of the section
+        let data = Bytes::default()
+            .u16(1)
+            .u16(1)
+            .u16(1)
+            .u16(1)
+            .u32(0x1234)
+            .u32(20)
+            .u32(28)
+            .u32(11)
+            .u32(0)
+            .u64(0);
+        let buf = build(
+            &[
+                dynstr(&["libfoo.so", "FOO_1"]),
+                version_section(".gnu.version_d", SHT_VER_DEF, 2, data),
+            ],
+            &[],
+        );
+        let elf = ParsedElf::from_bytes("test", &buf).unwrap();
+        let start = elf.shdrs[2].file_offset;
+
+        assert_eq!(
+            describe(&elf.versions[&2]),
+            ["def 1 1 1 1234 FOO_1", "defaux FOO_1"]
+        );
+        assert_eq!(elf.diagnostics.len(), 1);
+        assert_eq!(
+            elf.diagnostics[0].error,
+            ElfError::Truncated {
+                offset: start + 28,
+                structure: Structure::VersionRecord(2, 2),
+                size: 20,
+                available: 8,
+            }
+        );
+        assert_eq!(elf.diagnostics[0].location, Some((start + 28, 8)));
+    }
+}
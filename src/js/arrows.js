@@ -24,6 +24,9 @@ function getBoundingBoxSizes(elem) {
 }
 
 function addSvgArrow(elem1, elem2) {
+    elem1 = shownElem(elem1);
+    elem2 = shownElem(elem2);
+
     var off1 = getAbsPosition(elem1);
     var off2 = getAbsPosition(elem2);
 
@@ -42,7 +45,7 @@ function addSvgArrow(elem1, elem2) {
 }
 
 function jumpToElem(elem) {
-    elem.scrollIntoView();
+    shownElem(elem).scrollIntoView();
 }
 
 function setJumpCallback(elemFrom, elemTo) {
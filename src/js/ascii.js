@@ -0,0 +1,166 @@
+// the hex and ASCII dumps contain the same spans in the same order, so an
+// element in one of them can be found in the other by its path of child indices
+function childPath(elem, root) {
+    var path = [];
+
+    while (elem !== root) {
+        path.unshift(Array.prototype.indexOf.call(elem.parentNode.children, elem));
+        elem = elem.parentNode;
+    }
+
+    return path;
+}
+
+function followPath(root, path) {
+    var elem = root;
+
+    for (var i = 0; i < path.length; i++) {
+        elem = elem.children[path[i]];
+
+        if (elem === undefined) {
+            return null;
+        }
+    }
+
+    return elem;
+}
+
+var bytesPane = document.getElementById("bytes");
+var asciiPane = document.getElementById("ascii");
+var syncedElem = null;
+
+function clearSynced() {
+    if (syncedElem !== null) {
+        syncedElem.classList.remove("synced");
+        syncedElem = null;
+    }
+}
+
+function syncRanges(pane, otherPane) {
+    pane.addEventListener("mouseover", function (event) {
+        clearSynced();
+
+        if (event.target === pane || !pane.contains(event.target)) {
+            return;
+        }
+
+        var counterpart = followPath(otherPane, childPath(event.target, pane));
+
+        if (counterpart !== null) {
+            counterpart.classList.add("synced");
+            syncedElem = counterpart;
+        }
+    }, false);
+
+    pane.addEventListener("mouseleave", clearSynced, false);
+}
+
+// individual bytes don't have elements of their own, so they are located by
+// position instead: both dumps use a monospace font with a fixed number of
+// characters per byte (3 in the hex one, 1 in the ASCII one)
+var charWidth = 0;
+var lineHeight = 0;
+
+function measureFont() {
+    var probe = document.createElement("span");
+
+    probe.textContent = "0000000000";
+    asciiPane.appendChild(probe);
+    charWidth = probe.getBoundingClientRect().width / 10;
+    asciiPane.removeChild(probe);
+
+    lineHeight = dumpLineHeight();
+}
+
+// where the rows of a dump are and which one comes first. js/lazy.js only
+// builds the rows in view and replaces these
+var dumpRows = function (pane) {
+    return { elem: pane, firstRow: 0 };
+};
+
+var dumpLineHeight = function () {
+    return asciiPane.clientHeight / visualRows;
+};
+
+function byteAt(pane, event, charsPerByte) {
+    var rows = dumpRows(pane);
+    var rect = rows.elem.getBoundingClientRect();
+    var x = event.clientX - rect.left - rows.elem.clientLeft;
+    var y = event.clientY - rect.top - rows.elem.clientTop;
+    var col = Math.floor(x / charWidth / charsPerByte);
+    var row = rows.firstRow + Math.floor(y / lineHeight);
+
+    if (col < 0 || col >= bytesPerRow || row < rows.firstRow) {
+        return null;
+    }
+
+    // rows don't map to offsets one to one when something is collapsed
+    return offsetAt(row, col);
+}
+
+function createCursor() {
+    var cursor = document.createElement("div");
+
+    cursor.className = "byte_cursor";
+    document.body.appendChild(cursor);
+
+    return cursor;
+}
+
+var bytesCursor = createCursor();
+var asciiCursor = createCursor();
+
+function placeCursor(cursor, pane, idx, charsPerByte, charsWide) {
+    var rows = dumpRows(pane);
+    var rect = rows.elem.getBoundingClientRect();
+    var row = rowOfOffset(idx) - rows.firstRow;
+    var col = idx % bytesPerRow;
+    var x = rect.left + window.scrollX + rows.elem.clientLeft + col * charsPerByte * charWidth;
+    var y = rect.top + window.scrollY + rows.elem.clientTop + row * lineHeight;
+
+    cursor.style.left = x + "px";
+    cursor.style.top = y + "px";
+    cursor.style.width = charsWide * charWidth + "px";
+    cursor.style.height = lineHeight + "px";
+    cursor.style.display = "block";
+}
+
+function hideCursors() {
+    bytesCursor.style.display = "none";
+    asciiCursor.style.display = "none";
+}
+
+function syncBytes(pane, charsPerByte) {
+    pane.addEventListener("mousemove", function (event) {
+        if (charWidth === 0) {
+            measureFont();
+        }
+
+        var idx = byteAt(pane, event, charsPerByte);
+
+        describeByte(idx);
+
+        if (idx === null) {
+            hideCursors();
+            return;
+        }
+
+        placeCursor(bytesCursor, bytesPane, idx, 3, 2);
+        placeCursor(asciiCursor, asciiPane, idx, 1, 1);
+    }, false);
+
+    pane.addEventListener("mouseleave", function () {
+        hideCursors();
+        describeByte(null);
+    }, false);
+}
+
+syncRanges(bytesPane, asciiPane);
+syncRanges(asciiPane, bytesPane);
+
+syncBytes(bytesPane, 3);
+syncBytes(asciiPane, 1);
+
+window.addEventListener("resize", function () {
+    charWidth = 0;
+}, false);
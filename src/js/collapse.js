@@ -1,38 +1,263 @@
-var savedBytes = {};
+// Segments and sections are collapsed with a right click and expanded again
+// by clicking their placeholder. The placeholder takes up a row of its own,
+// and the rows with bytes before and after the collapsed range stay in their
+// columns, so that every other row still starts at a multiple of bytesPerRow.
 
-var marker = '..';
+var collapsedIds = {};
+var collapsibleIndices = {};
 
-function bytesCallback(ev) {
-    var elem = ev.target;
+for (var i = 0; i < collapsibleRanges.length; i++) {
+    collapsibleIndices[collapsibleRanges[i][2]] = i;
 
-    if (elem.innerHTML === marker) {
-        elem.innerHTML = savedBytes[elem.id];
-    } else {
-        elem.innerHTML = marker;
+    if (collapsibleRanges[i][4]) {
+        collapsedIds[collapsibleRanges[i][2]] = true;
     }
+}
 
-    redrawArrows();
+// collapsed ranges that aren't inside of another collapsed one
+var collapseRegions = [];
+// the bytes around them as [start, end, first row], there is one more of
+// these than regions
+var collapseRuns = [];
+// rows of the dump, the placeholders included
+var visualRows = 0;
+
+function runRows(run) {
+    return Math.ceil(run[1] / bytesPerRow) - Math.floor(run[0] / bytesPerRow);
+}
+
+function updateCollapseLayout() {
+    collapseRegions = [];
+
+    // ranges are ordered by start, the part of a range that sticks out of a
+    // collapsed one it crosses is a region of its own, with the placeholder
+    // after the range's last span
+    for (var i = 0; i < collapsibleRanges.length; i++) {
+        var range = collapsibleRanges[i];
+        var last = collapseRegions[collapseRegions.length - 1];
+
+        if (!collapsedIds[range[2]]) {
+            continue;
+        }
+
+        if (last === undefined || range[0] >= last[1]) {
+            collapseRegions.push(range);
+        } else if (range[1] > last[1]) {
+            collapseRegions.push([last[1], range[1], range[2], range[3]]);
+        }
+    }
+
+    collapseRuns = [];
+    visualRows = 0;
+
+    for (var i = 0; i <= collapseRegions.length; i++) {
+        var start = i === 0 ? 0 : collapseRegions[i - 1][1];
+        var end = i === collapseRegions.length ? fileLen : collapseRegions[i][0];
+        var run = [start, end, visualRows];
+
+        collapseRuns.push(run);
+        visualRows += runRows(run);
+
+        if (i < collapseRegions.length) {
+            visualRows++;
+        }
+    }
+}
+
+// index of the last run whose field (0 for the start, 2 for the first row)
+// is at most value
+function findRun(value, field) {
+    var lo = 0;
+    var hi = collapseRuns.length - 1;
+
+    while (lo < hi) {
+        var mid = (lo + hi + 1) >> 1;
+
+        if (collapseRuns[mid][field] <= value) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    return lo;
+}
+
+// offset that the first column of a row stands for, null for placeholders
+function rowOffset(row) {
+    var run = collapseRuns[findRun(row, 2)];
+    var idx = row - run[2];
+
+    if (idx >= runRows(run)) {
+        return null;
+    }
+
+    return (Math.floor(run[0] / bytesPerRow) + idx) * bytesPerRow;
+}
+
+// the byte shown at a row and column, if any
+function offsetAt(row, col) {
+    if (row < 0 || row >= visualRows) {
+        return null;
+    }
+
+    var run = collapseRuns[findRun(row, 2)];
+    var offset = rowOffset(row);
+
+    if (offset === null || offset + col < run[0] || offset + col >= run[1]) {
+        return null;
+    }
+
+    return offset + col;
+}
+
+// bytes inside of a collapsed range are on the row of its placeholder
+function rowOfOffset(offset) {
+    var idx = findRun(offset, 0);
+    var run = collapseRuns[idx];
+
+    if (offset >= run[1] && idx < collapseRegions.length) {
+        return run[2] + runRows(run);
+    }
+
+    return run[2] + Math.floor(offset / bytesPerRow) - Math.floor(run[0] / bytesPerRow);
+}
+
+// the label goes on a row of its own, the tail fills the columns of the
+// range's last row so that the bytes after it stay in place
+function placeholderHtml(range, hex, withLabel) {
+    var html = "<span class='placeholder' data-collapsed='" + range[2] + "'>";
+    var tail = range[1] % bytesPerRow;
+
+    if (withLabel) {
+        html += "<span class='placeholder_label'>[+] " + range[3] + "</span>";
+    }
+
+    if (tail !== 0) {
+        // in the hex dump the separator after the last byte is outside of
+        // the range
+        var width = hex ? 3 * tail - 1 : tail;
+
+        html += "<span class='placeholder_tail' style='width: " + width + "ch'></span>";
+    }
+
+    return html + "</span>";
+}
+
+// the id of the range that elem is a span of, see continuation_attributes()
+function rangeId(elem) {
+    return elem.id || elem.dataset.continues || "";
+}
 
-    ev.preventDefault();
-    return false;
+// a range crossing another one is split into several spans, the first one
+// has the id and the others continue it
+function rangeSpans(id) {
+    var first = document.getElementById(id);
+    var rest = document.querySelectorAll("[data-continues='" + id + "']");
+
+    return (first === null ? [] : [first]).concat(Array.prototype.slice.call(rest));
 }
 
-function collapseBytes() {
-    var elements = document.querySelectorAll('.segment, .section');
+// collapsed ranges are represented by their placeholder, which comes after
+// their last span, by the outermost one if they are nested
+function shownElem(elem) {
+    var shown = elem;
+
+    for (var parent = elem; parent !== null; parent = parent.parentElement) {
+        if (parent.classList.contains("collapsed")) {
+            var spans = rangeSpans(rangeId(parent));
+
+            shown = spans[spans.length - 1].nextElementSibling;
+        }
+    }
 
-    for (var i = 0; i < elements.length; ++i) {
-        var elem = elements[i];
+    return shown;
+}
 
-        if (elem.children.length > 0) {
+// the regular report has every row in the document, js/lazy.js builds them
+// as they come into view and replaces these
+var showCollapsed = function (range, collapsed) {
+    var ids = [range[2], "ascii_" + range[2]];
+
+    for (var i = 0; i < ids.length; i++) {
+        var spans = rangeSpans(ids[i]);
+
+        if (spans.length === 0) {
             continue;
         }
 
-        savedBytes[elem.id] = elem.innerHTML;
+        for (var j = 0; j < spans.length; j++) {
+            spans[j].classList.toggle("collapsed", collapsed);
+        }
 
-        elem.innerHTML = marker;
+        var last = spans[spans.length - 1];
 
-        elem.addEventListener("contextmenu", bytesCallback, false);
+        if (collapsed) {
+            last.insertAdjacentHTML("afterend", placeholderHtml(range, i === 0, true));
+        } else {
+            last.parentNode.removeChild(last.nextElementSibling);
+        }
     }
+};
+
+var collapseChanged = function () {
+    populateOffsets();
+    redrawArrows();
+};
+
+function setCollapsed(id, collapsed) {
+    if (!collapsedIds[id] === !collapsed) {
+        return;
+    }
+
+    if (collapsed) {
+        collapsedIds[id] = true;
+    } else {
+        delete collapsedIds[id];
+    }
+
+    showCollapsed(collapsibleRanges[collapsibleIndices[id]], collapsed);
+    updateCollapseLayout();
+    hideCursors();
+    clearSynced();
+    collapseChanged();
+}
+
+function collapsibleAt(elem, pane) {
+    for (; elem !== null && elem !== pane; elem = elem.parentElement) {
+        var id = rangeId(elem).replace(/^ascii_/, "");
+
+        if (collapsibleIndices[id] !== undefined && !elem.classList.contains("collapsed")) {
+            return id;
+        }
+    }
+
+    return null;
+}
+
+[bytesPane, asciiPane].forEach(function (pane) {
+    pane.addEventListener("contextmenu", function (event) {
+        var id = collapsibleAt(event.target, pane);
+
+        if (id !== null) {
+            event.preventDefault();
+            setCollapsed(id, true);
+        }
+    }, false);
+
+    // ahead of the arrows, which jump when their ends are clicked
+    pane.addEventListener("click", function (event) {
+        var placeholder = event.target.closest(".placeholder");
+
+        if (placeholder !== null) {
+            event.stopPropagation();
+            setCollapsed(placeholder.dataset.collapsed, false);
+        }
+    }, true);
+});
+
+for (var id in collapsedIds) {
+    showCollapsed(collapsibleRanges[collapsibleIndices[id]], true);
 }
 
-collapseBytes();
+updateCollapseLayout();
@@ -3,42 +3,52 @@ function listOfParents(el) {
 
     while (el.tagName !== "HTML") {
         el = el.parentNode;
-
-        // use unshift() instead of push() because we are stupid and have
-        // an assumption that section spans are inside segment spans
-        // so that break; in loop below doesn't have to go away.
         list.unshift(el.id);
     }
 
     return list;
 }
 
-var prevTableId;
+var prevTables = [];
 
 document.addEventListener("mouseover", function (e) {
     var event = e || window.event;
     var target = event.target || event.srcElement;
     var prefix = 'bin_';
+    var asciiPrefix = 'ascii_';
 
-    parents = listOfParents(target);
+    var parents = listOfParents(target);
+    var tables = [];
 
+    // ranges nest, e.g. a section inside a segment, so show the info table
+    // of every range under the cursor and not just the outermost one
     for (var i = 0; i < parents.length; i++) {
         var id = parents[i];
 
-        if (id.startsWith(prefix)) {
-            var tableId = id.replace(prefix, "info_");
-            var target = document.getElementById(tableId);
-
-            if (target !== null) {
-                if (prevTableId) {
-                    prevTableId.style.display = "none";
-                }
+        if (id && id.startsWith(asciiPrefix)) {
+            id = id.replace(asciiPrefix, "");
+        }
 
-                target.style.display = "block";
-                prevTableId = target;
+        if (id && id.startsWith(prefix)) {
+            var table = document.getElementById(id.replace(prefix, "info_"));
 
-                break;
+            if (table !== null) {
+                tables.push(table);
             }
         }
     }
+
+    if (tables.length === 0) {
+        return;
+    }
+
+    for (var i = 0; i < prevTables.length; i++) {
+        prevTables[i].style.display = "none";
+    }
+
+    for (var i = 0; i < tables.length; i++) {
+        tables[i].style.display = "block";
+    }
+
+    prevTables = tables;
 }, false);
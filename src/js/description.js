@@ -44,7 +44,151 @@ let descriptions = {
     sh_addralign: "Address alignment of the section (sh_addralign)",
     sh_entsize:   "Size of each entry if section has table of fixed-size entries (sh_entsize)",
     section:      "Section",
+    note:         "Note: header, name and desc",
+    pr_info:      "Signal info: number, code and errno (pr_info)",
+    pr_cursig:    "Current signal (pr_cursig)",
+    pr_sigpend:   "Set of pending signals (pr_sigpend)",
+    pr_sighold:   "Set of held signals (pr_sighold)",
+    pr_pid:       "Process or thread ID (pr_pid)",
+    pr_ppid:      "Parent process ID (pr_ppid)",
+    pr_pgrp:      "Process group ID (pr_pgrp)",
+    pr_sid:       "Session ID (pr_sid)",
+    pr_utime:     "User time (pr_utime)",
+    pr_stime:     "System time (pr_stime)",
+    pr_cutime:    "Cumulative user time of children (pr_cutime)",
+    pr_cstime:    "Cumulative system time of children (pr_cstime)",
+    pr_reg:       "General purpose registers (pr_reg)",
+    pr_fpvalid:   "Whether floating point registers are saved (pr_fpvalid)",
+    pr_state:     "Numeric process state (pr_state)",
+    pr_sname:     "Process state as a character (pr_sname)",
+    pr_zomb:      "Whether the process is a zombie (pr_zomb)",
+    pr_nice:      "Nice value (pr_nice)",
+    pr_flag:      "Process flags (pr_flag)",
+    pr_uid:       "User ID (pr_uid)",
+    pr_gid:       "Group ID (pr_gid)",
+    pr_fname:     "Filename of the executable (pr_fname)",
+    pr_psargs:    "Initial part of the argument list (pr_psargs)",
+    auxv_entry:   "Auxiliary vector entry: a_type and a_val",
+    file_count:   "Number of mapped files (NT_FILE)",
+    file_pagesz:  "Page size the file offsets are given in (NT_FILE)",
+    file_entry:   "Start, end and file offset in pages of a mapping (NT_FILE)",
+    file_names:   "Paths of the mapped files (NT_FILE)",
+    si_signo:     "Signal number (si_signo)",
+    si_errno:     "Error number (si_errno)",
+    si_code:      "Signal code (si_code)",
+    si_addr:      "Faulting address (si_addr)",
+    si_pid:       "Sending process ID (si_pid)",
+    si_uid:       "Real user ID of the sending process (si_uid)",
+    sym:          "Symbol table entry",
+    st_name:      "Offset to the string table containing this symbol name (st_name)",
+    st_value:     "Value of the symbol, usually an address (st_value)",
+    st_size:      "Size of the object this symbol refers to, 0 if unknown (st_size)",
+    st_info:      "Symbol binding in high nibble and type in low nibble (st_info)",
+    st_other:     "Symbol visibility (st_other)",
+    st_shndx:     "Index of the section this symbol is defined in (st_shndx)",
+    rel:          "Relocation entry",
+    r_offset:     "Location to apply the relocation to (r_offset)",
+    r_info:       "Symbol table index and relocation type (r_info)",
+    r_addend:     "Constant addend used to compute the relocated value (r_addend)",
+    dyn:          "Dynamic section entry",
+    d_tag:        "Type of the entry, determines how d_val is interpreted (d_tag)",
+    d_val:        "Integer or address value of the entry (d_val)",
+    vers:         "Symbol version entry",
+    vn_version:   "Version of the structure, should be 1 (vn_version)",
+    vn_cnt:       "Number of Vernaux entries (vn_cnt)",
+    vn_file:      "String table offset of the needed file name (vn_file)",
+    vn_aux:       "Offset from this entry to its first Vernaux entry (vn_aux)",
+    vn_next:      "Offset from this entry to the next Verneed entry (vn_next)",
+    vna_hash:     "Hash of the version name (vna_hash)",
+    vna_flags:    "Version flags (vna_flags)",
+    vna_other:    "Version index used by the symbol versions (vna_other)",
+    vna_name:     "String table offset of the version name (vna_name)",
+    vna_next:     "Offset from this entry to the next Vernaux entry (vna_next)",
+    vd_version:   "Version of the structure, should be 1 (vd_version)",
+    vd_flags:     "Version flags (vd_flags)",
+    vd_ndx:       "Version index used by the symbol versions (vd_ndx)",
+    vd_cnt:       "Number of Verdaux entries (vd_cnt)",
+    vd_hash:      "Hash of the version name (vd_hash)",
+    vd_aux:       "Offset from this entry to its first Verdaux entry (vd_aux)",
+    vd_next:      "Offset from this entry to the next Verdef entry (vd_next)",
+    vda_name:     "String table offset of the version or parent name (vda_name)",
+    vda_next:     "Offset from this entry to the next Verdaux entry (vda_next)",
+    nbucket:      "Number of hash buckets (nbucket)",
+    nchain:       "Number of chain entries, same as the number of symbols (nchain)",
+    bucket:       "Buckets: index of the first symbol of each chain",
+    chain:        "Chains: index of the next symbol with the same bucket, by symbol",
+    gnu_nbucket:  "Number of hash buckets (nbuckets)",
+    symoffset:    "Index of the first symbol in the hash table (symoffset)",
+    bloom_size:   "Number of bloom filter words (bloom_size)",
+    bloom_shift:  "Shift of the hash for the second bloom filter bit (bloom_shift)",
+    bloom:        "Bloom filter words, two bits are set for every symbol",
+    gnu_bucket:   "Buckets: index of the lowest symbol of each chain",
+    gnu_chain:    "Hash values of the symbols, the lowest bit ends a chain",
+    cu:           "DWARF unit: a header followed by a tree of DIEs",
+    unit_length:  "Size of the unit after this field (unit_length)",
+    unit_len64:   "Size of the unit after this field, DWARF64 (unit_length)",
+    cu_version:   "DWARF version of the unit (version)",
+    unit_type:    "Kind of unit, DWARF 5 only (unit_type)",
+    abbrev_off:   "Offset of the unit's abbreviations in .debug_abbrev (debug_abbrev_offset)",
+    address_size: "Size of an address on the target (address_size)",
+    type_sig:     "Signature of the type defined by the unit (type_signature)",
+    type_offset:  "Offset of the type's DIE in the unit (type_offset)",
+    dwo_id:       "ID matching the skeleton and split units (dwo_id)",
+    die:          "Debugging information entry, described by its abbreviation",
+    abbrev_code:  "Code of the DIE's abbreviation in .debug_abbrev, 0 ends a list of siblings",
+    die_attr:     "Attribute value, encoded as given by its form",
+    line_prog:    "Line-number program: a header followed by opcodes building a line table",
+    line_version: "DWARF version of the line-number program (version)",
+    seg_sel_size: "Size of a segment selector, DWARF 5 only (segment_selector_size)",
+    header_len:   "Size of the rest of the header, the opcodes follow it (header_length)",
+    min_inst_len: "Size of the smallest instruction (minimum_instruction_length)",
+    max_ops:      "Operations per instruction, 1 except for VLIW (maximum_operations_per_instruction)",
+    default_stmt: "Initial value of the is_stmt register (default_is_stmt)",
+    line_base:    "Smallest line increment of a special opcode (line_base)",
+    line_range:   "Number of line increments of special opcodes (line_range)",
+    opcode_base:  "Number of the first special opcode (opcode_base)",
+    opcode_lens:  "Number of operands of each standard opcode (standard_opcode_lengths)",
+    dir_format:   "Content types and forms of a directory entry (directory_entry_format)",
+    directories:  "Include directories (include_directories)",
+    file_format:  "Content types and forms of a file entry (file_name_entry_format)",
+    file_names:   "Source files (file_names)",
+    line_op:      "Line-number program opcode",
+    cfi:          "Call frame information record of .eh_frame: a CIE or an FDE",
+    cfi_length:   "Size of the record after this field, 0 ends .eh_frame (length)",
+    cfi_len64:    "Size of the record after this field, 64-bit format (extended length)",
+    terminator:   "Zero length ending .eh_frame",
+    cie_id:       "0 for a CIE (CIE_id)",
+    cie_pointer:  "Offset back from this field to the FDE's CIE (CIE_pointer)",
+    cie_version:  "Version of the CIE, 1 or 3 (version)",
+    augmentation: "Which augmentation data follows, e.g. zR or zPLR (augmentation)",
+    eh_data:      "GCC exception table pointer of the old \"eh\" augmentation",
+    cie_addrsize: "Size of an address on the target, version 4 only (address_size)",
+    cie_segsize:  "Size of a segment selector, version 4 only (segment_selector_size)",
+    code_align:   "Factor of advance_loc deltas (code_alignment_factor)",
+    data_align:   "Factor of register save offsets (data_alignment_factor)",
+    return_reg:   "Register holding the return address (return_address_register)",
+    aug_length:   "Size of the augmentation data",
+    aug_data:     "Augmentation data: LSDA, personality and FDE pointer encodings",
+    pc_begin:     "Address of the first instruction covered by the FDE (initial_location)",
+    pc_range:     "Number of bytes of instructions covered by the FDE (address_range)",
+    lsda:         "Pointer to the language-specific data area, e.g. a C++ exception table",
+    cfa_op:       "Call frame instruction, building the unwinding rules row by row",
+    eh_version:   "Version of .eh_frame_hdr, should be 1",
+    eh_ptr_enc:   "Encoding of eh_frame_ptr (eh_frame_ptr_enc)",
+    count_enc:    "Encoding of fde_count (fde_count_enc)",
+    table_enc:    "Encoding of the binary search table entries (table_enc)",
+    eh_frame_ptr: "Pointer to the start of .eh_frame (eh_frame_ptr)",
+    fde_count:    "Number of entries in the binary search table (fde_count)",
+    hdr_entry:    "Binary search table entry: initial location and address of an FDE",
+    ch_type:      "Compression algorithm, 1 is zlib and 2 is zstd (ch_type)",
+    ch_reserved:  "Reserved (ch_reserved)",
+    ch_size:      "Size of the section once decompressed (ch_size)",
+    ch_addralign: "Alignment of the section once decompressed (ch_addralign)",
+    zlib_magic:   "\"ZLIB\": legacy .zdebug section compressed with zlib",
+    zlib_size:    "Size of the section once decompressed, big-endian",
     segment_and_section: "Segment and section",
+    placeholder:  "Collapsed segment or section, click to expand",
+    malformed:    "Malformed value, see the list of problems at the top",
 }
 let separator = "<br>&#x2193<br>";
 
@@ -56,6 +200,11 @@ function stripBinPrefix(str) {
     return str.replace("bin_", "");
 }
 
+// ranges in the ASCII column have the same ids as in the hex one but prefixed
+function stripAsciiPrefix(str) {
+    return str.replace(/^ascii_/, "");
+}
+
 function hasDescription(id) {
     return descriptions[id] !== undefined
         || descriptions[stripInfoPrefix(id)] !== undefined
@@ -84,7 +233,11 @@ function iterateParents(el) {
 
     do {
         if (el.id !== undefined) {
-            keywords.push(el.id);
+            keywords.push(stripAsciiPrefix(el.id));
+        }
+
+        if (el.dataset !== undefined && el.dataset.continues !== undefined) {
+            keywords.push(stripAsciiPrefix(el.dataset.continues));
         }
 
         var classList = el.classList;
@@ -139,10 +292,58 @@ function iterateParents(el) {
     return txt;
 }
 
+// sourceLines has the file offsets of instructions and their source
+// locations as [start, end, "file:line"], sorted by start
+function sourceLineAt(offset) {
+    var lo = 0;
+    var hi = sourceLines.length;
+
+    while (lo < hi) {
+        var mid = (lo + hi) >> 1;
+
+        if (sourceLines[mid][0] <= offset) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    if (lo > 0 && offset < sourceLines[lo - 1][1]) {
+        return sourceLines[lo - 1][2];
+    }
+
+    return null;
+}
+
+var hoveredElem = null;
+var hoveredLine = null;
+
+function showDescription() {
+    var txt = hoveredElem === null ? "" : iterateParents(hoveredElem);
+
+    if (hoveredLine !== null) {
+        var line = "Source: " + hoveredLine;
+
+        txt = txt === "" ? line : txt + separator + line;
+    }
+
+    document.getElementById('desc').innerHTML = txt;
+}
+
+// called by js/ascii.js with the offset of the byte under the cursor, or
+// null when there's none
+function describeByte(offset) {
+    var line = offset === null ? null : sourceLineAt(offset);
+
+    if (line !== hoveredLine) {
+        hoveredLine = line;
+        showDescription();
+    }
+}
+
 document.addEventListener("mouseover", function (e) {
     var event = e || window.event;
 
-    var target = event.target || event.srcElement;
-
-    document.getElementById('desc').innerHTML = iterateParents(target);
+    hoveredElem = event.target || event.srcElement;
+    showDescription();
 }, false);
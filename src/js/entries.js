@@ -0,0 +1,97 @@
+function compareCells(a, b) {
+    var x = Number(a);
+    var y = Number(b);
+
+    if (!isNaN(x) && !isNaN(y)) {
+        return x - y;
+    }
+
+    return a.localeCompare(b);
+}
+
+function sortTable(table, column) {
+    var rows = Array.prototype.slice.call(table.rows, 1);
+    var ascending = table.dataset.sortColumn != column || table.dataset.sortOrder !== "asc";
+
+    rows.sort(function(r1, r2) {
+        var res = compareCells(r1.cells[column].textContent, r2.cells[column].textContent);
+
+        return ascending ? res : -res;
+    });
+
+    for (var i = 0; i < rows.length; ++i) {
+        rows[i].parentNode.appendChild(rows[i]);
+    }
+
+    table.dataset.sortColumn = column;
+    table.dataset.sortOrder = ascending ? "asc" : "desc";
+}
+
+function makeSortable(table) {
+    var headers = table.rows[0].cells;
+
+    for (var i = 0; i < headers.length; ++i) {
+        (function(column) {
+            headers[column].addEventListener("click", function() {
+                sortTable(table, column);
+            }, false);
+        })(i);
+    }
+}
+
+// rows of the DWARF tree are hidden in collapsed <details> elements
+function openParentDetails(row) {
+    var parent = row.parentElement;
+
+    if (row.tagName === "SUMMARY") {
+        parent = parent.parentElement;
+    }
+
+    for (var details = parent.closest("details"); details !== null;
+         details = details.parentElement.closest("details")) {
+        details.open = true;
+    }
+}
+
+function scrollRowIntoView(row) {
+    openParentDetails(row);
+
+    var wrapper = row.closest(".entries_wrapper");
+    var wrapperRect = wrapper.getBoundingClientRect();
+    var rowRect = row.getBoundingClientRect();
+
+    if (rowRect.top < wrapperRect.top || rowRect.bottom > wrapperRect.bottom) {
+        wrapper.scrollTop += rowRect.top - wrapperRect.top - wrapper.clientHeight / 2;
+    }
+}
+
+// entries in the dump have ids like bin_sym6_3 (ascii_bin_sym6_3 in the ASCII
+// column), their rows in info tables are row_sym6_3
+function linkEntries() {
+    var tables = document.querySelectorAll("table.sortable");
+
+    for (var i = 0; i < tables.length; ++i) {
+        makeSortable(tables[i]);
+    }
+
+    var entries = document.querySelectorAll("#bytes [id^='bin_'], #ascii [id^='ascii_bin_']");
+
+    for (var i = 0; i < entries.length; ++i) {
+        var rowId = entries[i].id.replace("ascii_", "").replace("bin_", "row_");
+        var row = document.getElementById(rowId);
+
+        if (row === null) {
+            continue;
+        }
+
+        highlightIds(entries[i].id, rowId);
+
+        (function(row) {
+            entries[i].addEventListener("mouseenter", function() {
+                scrollRowIntoView(row);
+            }, false);
+        })(row);
+    }
+}
+
+linkEntries();
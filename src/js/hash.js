@@ -0,0 +1,133 @@
+// hashTables maps section indices to the tables, see add_hash_script()
+
+function sysvHash(bytes) {
+    var h = 0;
+
+    for (var i = 0; i < bytes.length; ++i) {
+        h = ((h << 4) + bytes[i]) >>> 0;
+
+        var g = h & 0xf0000000;
+
+        if (g !== 0) {
+            h ^= g >>> 24;
+        }
+
+        h = (h & ~g) >>> 0;
+    }
+
+    return h;
+}
+
+function gnuHash(bytes) {
+    var h = 5381;
+
+    for (var i = 0; i < bytes.length; ++i) {
+        h = (h * 33 + bytes[i]) >>> 0;
+    }
+
+    return h;
+}
+
+function hex(value) {
+    return "0x" + value.toString(16);
+}
+
+function lookupSysv(table, name, h) {
+    var bucket = h % table.buckets.length;
+    var sym = table.buckets[bucket];
+    var steps = 0;
+    var prefix = "hash " + hex(h) + ", bucket " + bucket + ": ";
+
+    // chains can loop in malformed files
+    while (sym !== 0 && sym < table.chains.length && steps < table.chains.length) {
+        steps++;
+
+        if (table.names[sym] === name) {
+            return prefix + "symbol " + sym + " after " + steps + " comparisons";
+        }
+
+        sym = table.chains[sym];
+    }
+
+    return prefix + "not found after " + steps + " comparisons";
+}
+
+function lookupGnu(table, name, h) {
+    var prefix = "hash " + hex(h) + ", ";
+
+    if (table.bloom.length > 0) {
+        var bits = table.bloomBits;
+        var word = BigInt(table.bloom[Math.floor(h / bits) % table.bloom.length]);
+        var bit1 = BigInt(h % bits);
+        var bit2 = BigInt((h >>> table.bloomShift) % bits);
+        var one = BigInt(1);
+
+        if (((word >> bit1) & (word >> bit2) & one) !== one) {
+            return prefix + "rejected by the bloom filter";
+        }
+    }
+
+    var bucket = h % table.buckets.length;
+    var sym = table.buckets[bucket];
+    var steps = 0;
+    var compares = 0;
+
+    prefix += "bucket " + bucket + ": ";
+
+    if (sym === 0 || sym < table.symoffset) {
+        return prefix + "empty bucket, passed the bloom filter";
+    }
+
+    for (; sym - table.symoffset < table.chains.length; ++sym) {
+        var chainHash = table.chains[sym - table.symoffset];
+
+        steps++;
+
+        // the lowest bit marks the end of the chain
+        if ((chainHash | 1) === (h | 1)) {
+            compares++;
+
+            if (table.names[sym] === name) {
+                return prefix + "symbol " + sym + " after " + steps + " hashes and " +
+                    compares + " name comparisons";
+            }
+        }
+
+        if (chainHash & 1) {
+            break;
+        }
+    }
+
+    return prefix + "not found after " + steps + " hashes and " + compares +
+        " name comparisons";
+}
+
+function lookupHash(input) {
+    var section = input.dataset.section;
+    var table = hashTables[section];
+    var result = document.getElementById("hash_result" + section);
+    var name = input.value;
+
+    if (name === "" || table.buckets.length === 0) {
+        result.textContent = "";
+        return;
+    }
+
+    var bytes = new TextEncoder().encode(name);
+
+    if (table.gnu) {
+        result.textContent = lookupGnu(table, name, gnuHash(bytes));
+    } else {
+        result.textContent = lookupSysv(table, name, sysvHash(bytes));
+    }
+}
+
+var hashInputs = document.querySelectorAll(".hash_lookup");
+
+for (var i = 0; i < hashInputs.length; ++i) {
+    (function(input) {
+        input.addEventListener("input", function() {
+            lookupHash(input);
+        }, false);
+    })(hashInputs[i]);
+}
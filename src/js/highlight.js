@@ -1,7 +1,11 @@
-var color = "#ee9";
+function highlightColor() {
+    return document.documentElement.classList.contains("dark") ? "#664" : "#ee9";
+}
 
 function addPairHighlighting(elem, depElem) {
     elem.addEventListener("mouseenter", function(event) {
+        var color = highlightColor();
+
         event.target.style.backgroundColor = color;
         depElem.style.backgroundColor = color;
     }, false);
@@ -0,0 +1,671 @@
+// Only the rows of the dumps which are in view exist in the document, they
+// are built from fileBase64 and fileRanges whenever the page is scrolled.
+
+function decodeBase64(str) {
+    var padding = str.endsWith("==") ? 2 : str.endsWith("=") ? 1 : 0;
+    var bytes = new Uint8Array(str.length / 4 * 3 - padding);
+    var pos = 0;
+    // multiple of 4, so that every piece decodes on its own
+    var pieceLen = 1 << 20;
+
+    for (var i = 0; i < str.length; i += pieceLen) {
+        var piece = atob(str.substr(i, pieceLen));
+
+        for (var j = 0; j < piece.length; j++) {
+            bytes[pos++] = piece.charCodeAt(j);
+        }
+    }
+
+    return bytes;
+}
+
+var fileBytes = decodeBase64(fileBase64);
+
+fileBase64 = null;
+
+// [start, end, id, class], ordered by start with outer ranges first. See
+// write_packed_ranges() in report_gen.rs for the encoding.
+function unpackRanges(packed) {
+    var bytes = decodeBase64(packed);
+    var pos = 0;
+    var ranges = [];
+    var start = 0;
+
+    function next() {
+        var value = 0;
+        var scale = 1;
+        var byte;
+
+        // multiplying instead of shifting, so that values above 2^31 survive
+        do {
+            byte = bytes[pos++];
+            value += (byte & 0x7f) * scale;
+            scale *= 128;
+        } while (byte & 0x80);
+
+        return value;
+    }
+
+    while (pos < bytes.length) {
+        start += next();
+
+        var end = start + next();
+        var classIdx = next();
+        var templateIdx = next();
+        var id = null;
+
+        if (templateIdx !== 0) {
+            var parts = rangeIdTemplates[templateIdx - 1];
+
+            id = parts[0];
+
+            for (var i = 1; i < parts.length; i++) {
+                id += next() + parts[i];
+            }
+        }
+
+        ranges.push([start, end, id, classIdx === 0 ? null : rangeClasses[classIdx - 1]]);
+    }
+
+    return ranges;
+}
+
+var fileRanges = unpackRanges(packedRanges);
+
+packedRanges = null;
+
+// ranges open at the start of every chunk of the file, so that the ranges
+// open at some offset are found without going through the whole file
+var chunkLen = 4096;
+
+while (fileLen / chunkLen > 1 << 16) {
+    chunkLen *= 2;
+}
+
+var chunkRanges = [];
+
+for (var i = 0; i <= fileLen / chunkLen; i++) {
+    chunkRanges.push([]);
+}
+
+for (var i = 0; i < fileRanges.length; i++) {
+    var first = Math.floor(fileRanges[i][0] / chunkLen) + 1;
+    var last = Math.floor((fileRanges[i][1] - 1) / chunkLen);
+
+    for (var chunk = first; chunk <= last; chunk++) {
+        chunkRanges[chunk].push(i);
+    }
+}
+
+// index of the first range starting at or after offset
+function firstRangeFrom(offset) {
+    var lo = 0;
+    var hi = fileRanges.length;
+
+    while (lo < hi) {
+        var mid = (lo + hi) >> 1;
+
+        if (fileRanges[mid][0] < offset) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    return lo;
+}
+
+// ranges containing offset but starting before it, outer ranges first
+function rangesOpenAt(offset) {
+    var chunk = Math.floor(offset / chunkLen);
+    var open = chunkRanges[chunk].filter(function (i) {
+        return fileRanges[i][1] > offset;
+    });
+
+    for (var i = firstRangeFrom(chunk * chunkLen); i < fileRanges.length; i++) {
+        if (fileRanges[i][0] >= offset) {
+            break;
+        }
+
+        if (fileRanges[i][1] > offset) {
+            open.push(i);
+        }
+    }
+
+    return open;
+}
+
+var rangeIndices = {};
+
+for (var i = 0; i < fileRanges.length; i++) {
+    var id = fileRanges[i][2];
+
+    if (id !== null && rangeIndices[id] === undefined) {
+        rangeIndices[id] = i;
+    }
+}
+
+// both ends of an arrow jump to each other
+var arrowPartners = {};
+
+for (var i = 0; i < fileArrows.length; i++) {
+    arrowPartners[fileArrows[i][0]] = fileArrows[i][1];
+    arrowPartners[fileArrows[i][1]] = fileArrows[i][0];
+}
+
+// continued spans carry on after a range crossing theirs ended, like
+// continuation_attributes() in the regular report
+function openSpan(rangeIdx, idPrefix, markArrows, continued) {
+    var range = fileRanges[rangeIdx];
+    var attributes = "";
+
+    if (range[2] !== null) {
+        attributes += (continued ? " data-continues='" : " id='") + idPrefix + range[2] + "'";
+    }
+
+    if (range[3] !== null) {
+        attributes += " class='" + range[3] + "'";
+    }
+
+    if (markArrows && !continued && arrowPartners[rangeIdx] !== undefined) {
+        attributes += " data-range='" + rangeIdx + "'";
+    }
+
+    return "<span" + attributes + ">";
+}
+
+function hexByte(idx) {
+    var byte = fileBytes[idx];
+
+    // the magic is shown as characters, same as in the regular report
+    if (idx < 4 && byte > 0x20 && byte < 0x7f) {
+        return "&nbsp;" + String.fromCharCode(byte);
+    }
+
+    return (byte < 0x10 ? "0" : "") + byte.toString(16);
+}
+
+var asciiEscapes = { "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;" };
+
+function asciiByte(idx) {
+    var byte = fileBytes[idx];
+
+    if (byte <= 0x20 || byte >= 0x7f) {
+        return ".";
+    }
+
+    var ch = String.fromCharCode(byte);
+
+    return asciiEscapes[ch] || ch;
+}
+
+function RowBuilder() {
+    this.hex = "";
+    this.ascii = "";
+    this.offsets = "";
+    // ranges with an open span, outermost first
+    this.open = [];
+}
+
+RowBuilder.prototype.emit = function (hex, ascii) {
+    this.hex += hex;
+    this.ascii += ascii;
+};
+
+RowBuilder.prototype.openRange = function (rangeIdx, continued) {
+    this.emit(openSpan(rangeIdx, "", true, continued), openSpan(rangeIdx, "ascii_", false, continued));
+    this.open.push(rangeIdx);
+};
+
+// closes and opens spans until exactly the ranges in target are open, ranges
+// starting before offset get continued spans, the first rows have no offset
+RowBuilder.prototype.openOnly = function (target, offset) {
+    var keep = 0;
+
+    // both are in the order the spans are opened in
+    while (keep < this.open.length && this.open[keep] === target[keep]) {
+        keep++;
+    }
+
+    while (this.open.length > keep) {
+        this.emit("</span>", "</span>");
+        this.open.pop();
+    }
+
+    for (var i = keep; i < target.length; i++) {
+        this.openRange(target[i], fileRanges[target[i]][0] < offset);
+    }
+};
+
+// the spans opened after the outermost range ending here are closed along
+// with it and continued, the same as close_ranges() does
+RowBuilder.prototype.closeRanges = function (idx) {
+    var first = this.open.findIndex(function (i) {
+        return fileRanges[i][1] === idx + 1;
+    });
+
+    if (first === -1) {
+        return;
+    }
+
+    var closed = this.open.splice(first);
+
+    for (var i = 0; i < closed.length; i++) {
+        this.emit("</span>", "</span>");
+    }
+
+    for (var i = 0; i < closed.length; i++) {
+        if (fileRanges[closed[i]][1] !== idx + 1) {
+            this.openRange(closed[i], true);
+        }
+    }
+};
+
+RowBuilder.prototype.separator = function (idx) {
+    if ((idx + 1) % bytesPerRow === 0) {
+        this.emit("\n", "\n");
+    } else {
+        this.emit(" ", "");
+    }
+};
+
+RowBuilder.prototype.bytes = function (start, end) {
+    var next = firstRangeFrom(start);
+
+    this.openOnly(rangesOpenAt(start));
+
+    for (var idx = start; idx < end; idx++) {
+        while (next < fileRanges.length && fileRanges[next][0] === idx) {
+            this.openRange(next);
+            next++;
+        }
+
+        this.emit(hexByte(idx), asciiByte(idx));
+
+        this.closeRanges(idx);
+
+        this.separator(idx);
+    }
+};
+
+// where the regular report has the collapsed range's last span, with the
+// ranges around it open
+RowBuilder.prototype.placeholder = function (regionIdx, withLabel) {
+    var region = collapseRegions[regionIdx];
+    var next = collapseRegions[regionIdx + 1];
+    var rangeIdx = rangeIndices[region[2]];
+    var lastByte = fileRanges[rangeIdx][1] - 1;
+    var outer = rangesOpenAt(lastByte).filter(function (i) {
+        return i < rangeIdx;
+    });
+
+    for (var i = firstRangeFrom(lastByte); i < rangeIdx; i++) {
+        outer.push(i);
+    }
+
+    this.openOnly(withLabel ? outer : rangesOpenAt(region[1]), withLabel ? region[0] : undefined);
+    this.emit(placeholderHtml(region, true, withLabel), placeholderHtml(region, false, withLabel));
+
+    // a collapsed range crossing the end of this one has the separator in
+    // its hidden spans
+    if (next !== undefined && next[0] === region[1] && fileRanges[rangeIndices[next[2]]][0] < next[0]) {
+        return;
+    }
+
+    // what is still open after the range's last span are its parents and the
+    // continued spans of the ranges crossing its end
+    this.openOnly(rangesOpenAt(region[1]), withLabel ? region[1] : undefined);
+    this.separator(region[1] - 1);
+};
+
+// the same markup that the regular report has for rows first..last
+function buildRows(first, last) {
+    var builder = new RowBuilder();
+    var row = first;
+
+    while (row < last) {
+        var runIdx = findRun(row, 2);
+        var run = collapseRuns[runIdx];
+        var runRow = row - run[2];
+
+        if (runRow >= runRows(run)) {
+            builder.offsets += "..<br>\n";
+            builder.placeholder(runIdx, true);
+            row++;
+            continue;
+        }
+
+        var rows = Math.min(runRows(run) - runRow, last - row);
+        var rowStart = rowOffset(row);
+        var start = Math.max(rowStart, run[0]);
+
+        // the first row after a placeholder starts with the rest of it
+        if (row === first && start > rowStart) {
+            builder.placeholder(runIdx - 1, false);
+        }
+
+        for (var i = 0; i < rows; i++) {
+            builder.offsets += (rowStart + i * bytesPerRow).toString(16) + "<br>\n";
+        }
+
+        builder.bytes(start, Math.min(rowStart + rows * bytesPerRow, run[1]));
+        row += rows;
+    }
+
+    builder.openOnly([]);
+
+    return builder;
+}
+
+var offsetsPane = document.getElementById("offsets");
+var lazyPanes = [offsetsPane, bytesPane, asciiPane];
+var lazyBlocks = lazyPanes.map(function (pane) {
+    var block = document.createElement("div");
+
+    block.className = "lazy_rows";
+    pane.classList.add("lazy_pane");
+    pane.appendChild(block);
+
+    return block;
+});
+
+offsetsPane.style.width = Math.max(fileLen - 1, 0).toString(16).length + "ch";
+
+// browsers can't lay out elements much taller than this. Longer dumps are
+// squeezed into it and the rows follow the viewport instead of the page
+var maxPaneHeight = 1 << 23;
+var rowHeight = 0;
+var paneHeight = 0;
+var shownRows = { first: -1, count: 0 };
+
+function measureRows() {
+    lazyBlocks[2].innerHTML = "0";
+    rowHeight = lazyBlocks[2].getBoundingClientRect().height || 1;
+    paneHeight = Math.min(visualRows * rowHeight, maxPaneHeight);
+    shownRows.first = -1;
+
+    for (var i = 0; i < lazyPanes.length; i++) {
+        lazyPanes[i].style.height = paneHeight + "px";
+    }
+}
+
+function squeezed() {
+    return visualRows * rowHeight > maxPaneHeight;
+}
+
+function paneTop() {
+    return bytesPane.getBoundingClientRect().top + window.scrollY + bytesPane.clientTop;
+}
+
+function rowsInView() {
+    return Math.ceil(window.innerHeight / rowHeight) + 1;
+}
+
+// squeezed dumps map the scroll position through the pane onto the rows
+function scrollRange() {
+    return Math.max(paneHeight - window.innerHeight, 1);
+}
+
+function lastFirstRow() {
+    return Math.max(visualRows - rowsInView() + 1, 0);
+}
+
+function firstRowAt(scrolled) {
+    if (squeezed()) {
+        var fraction = Math.min(Math.max(scrolled / scrollRange(), 0), 1);
+
+        return Math.floor(fraction * lastFirstRow());
+    }
+
+    // a screen of rows above and below, rebuilt only every screen of scrolling
+    var screen = Math.floor(scrolled / rowHeight / rowsInView());
+    var first = (screen - 1) * rowsInView();
+
+    return Math.min(Math.max(first, 0), lastFirstRow());
+}
+
+function render() {
+    var scrolled = window.scrollY - paneTop();
+    var first = firstRowAt(scrolled);
+    var count = squeezed() ? rowsInView() : 3 * rowsInView();
+
+    count = Math.min(count, visualRows - first);
+
+    if (first !== shownRows.first || count !== shownRows.count) {
+        var rows = buildRows(first, first + count);
+
+        lazyBlocks[0].innerHTML = rows.offsets;
+        lazyBlocks[1].innerHTML = rows.hex;
+        lazyBlocks[2].innerHTML = rows.ascii;
+
+        shownRows = { first: first, count: count };
+
+        hideCursors();
+        clearSynced();
+        clearHighlights();
+    }
+
+    var top = first * rowHeight;
+
+    if (squeezed()) {
+        top = Math.min(Math.max(scrolled, 0), paneHeight - count * rowHeight);
+    }
+
+    for (var i = 0; i < lazyBlocks.length; i++) {
+        lazyBlocks[i].style.top = top + "px";
+    }
+
+    drawArrows();
+}
+
+var renderQueued = false;
+
+function queueRender() {
+    if (!renderQueued) {
+        renderQueued = true;
+
+        window.requestAnimationFrame(function () {
+            renderQueued = false;
+            render();
+        });
+    }
+}
+
+function scrollToOffset(offset, center) {
+    var row = rowOfOffset(offset);
+
+    if (center) {
+        row -= Math.floor(rowsInView() / 2);
+    }
+
+    row = Math.max(row, 0);
+
+    var y = row * rowHeight;
+
+    if (squeezed()) {
+        y = Math.min(row / Math.max(lastFirstRow(), 1), 1) * scrollRange();
+    }
+
+    window.scrollTo(window.scrollX, paneTop() + y);
+}
+
+dumpRows = function (pane) {
+    return { elem: lazyBlocks[lazyPanes.indexOf(pane)], firstRow: shownRows.first };
+};
+
+dumpLineHeight = function () {
+    return rowHeight;
+};
+
+// collapsed ranges are left out when building the rows
+showCollapsed = function () {};
+
+collapseChanged = function () {
+    measureRows();
+    render();
+};
+
+// arrows are drawn between ranges of which both ends are built
+function drawArrows() {
+    var svg = document.getElementById("arrows");
+    var origin = svg.ownerSVGElement.getBoundingClientRect();
+    var lines = "";
+
+    for (var i = 0; i < fileArrows.length; i++) {
+        var from = lazyBlocks[1].querySelector("[data-range='" + fileArrows[i][0] + "']");
+        var to = lazyBlocks[1].querySelector("[data-range='" + fileArrows[i][1] + "']");
+
+        if (from === null || to === null) {
+            continue;
+        }
+
+        var fromRect = from.getBoundingClientRect();
+        var toRect = to.getBoundingClientRect();
+
+        lines += '<line '
+            + 'x1="' + (fromRect.left - origin.left + fromRect.width / 2) + '" '
+            + 'y1="' + (fromRect.top - origin.top) + '" '
+            + 'x2="' + (toRect.left - origin.left) + '" '
+            + 'y2="' + (toRect.top - origin.top) + '" '
+            + '/>';
+    }
+
+    svg.innerHTML = lines;
+}
+
+bytesPane.addEventListener("click", function (event) {
+    var elem = event.target.closest("[data-range]");
+
+    if (elem !== null && bytesPane.contains(elem)) {
+        var partner = arrowPartners[elem.dataset.range];
+
+        scrollToOffset(fileRanges[partner][0], false);
+    }
+}, false);
+
+// a segment can show up in the virtual memory map more than once, e.g. as an
+// overlay in several overlapping LOAD mappings
+function vmapIds(id) {
+    var match = id.match(/^bin_segment(\d+)$/);
+
+    if (match === null) {
+        return [];
+    }
+
+    var elems = document.querySelectorAll("#vmap [data-segment='" + match[1] + "']");
+
+    return Array.prototype.map.call(elems, function (elem) {
+        return elem.id;
+    });
+}
+
+// ids of the elements which highlight along with elem
+function highlightPartners(elem) {
+    var partners = [];
+
+    if (bytesPane.contains(elem) || asciiPane.contains(elem)) {
+        var id = elem.id.replace(/^ascii_/, "");
+
+        if (id.startsWith("bin_")) {
+            partners.push(id.replace("bin_", "row_"));
+            partners = partners.concat(vmapIds(id));
+        } else {
+            partners.push("info_" + id);
+        }
+    } else if (elem.dataset.segment !== undefined) {
+        var id = "bin_segment" + elem.dataset.segment;
+
+        partners.push(id);
+        partners.push("ascii_" + id);
+    } else {
+        var match = elem.id.match(/^(info_|row_)(.*)$/);
+
+        if (match !== null) {
+            var id = match[1] === "info_" ? match[2] : "bin_" + match[2];
+
+            partners.push(id);
+            partners.push("ascii_" + id);
+        }
+    }
+
+    return partners;
+}
+
+var highlighted = [];
+
+function clearHighlights() {
+    for (var i = 0; i < highlighted.length; i++) {
+        highlighted[i].style.backgroundColor = "";
+    }
+
+    highlighted = [];
+}
+
+// the regular report hooks up every range when the page loads, here the
+// rows come and go so everything goes through the document instead
+document.addEventListener("mouseover", function (event) {
+    clearHighlights();
+
+    for (var elem = event.target; elem !== null && elem !== document.body; elem = elem.parentElement) {
+        if (!elem.id) {
+            continue;
+        }
+
+        var partners = highlightPartners(elem).map(function (id) {
+            return document.getElementById(id);
+        }).filter(function (partner) {
+            return partner !== null;
+        });
+
+        if (partners.length === 0) {
+            continue;
+        }
+
+        var color = highlightColor();
+
+        elem.style.backgroundColor = color;
+        highlighted.push(elem);
+
+        for (var i = 0; i < partners.length; i++) {
+            partners[i].style.backgroundColor = color;
+            highlighted.push(partners[i]);
+
+            if (partners[i].id.startsWith("row_")) {
+                scrollRowIntoView(partners[i]);
+            }
+        }
+    }
+}, false);
+
+function linkLazyMappings() {
+    var mappings = document.querySelectorAll("#vmap [data-segment]");
+
+    for (var i = 0; i < mappings.length; i++) {
+        var rangeIdx = rangeIndices["bin_segment" + mappings[i].dataset.segment];
+
+        if (rangeIdx === undefined) {
+            continue;
+        }
+
+        mappings[i].classList.add("vmap_linked");
+
+        mappings[i].addEventListener("click", function (offset) {
+            return function (event) {
+                event.stopPropagation();
+                scrollToOffset(offset, true);
+            };
+        }(fileRanges[rangeIdx][0]), false);
+    }
+}
+
+linkLazyMappings();
+
+measureRows();
+render();
+
+window.addEventListener("scroll", queueRender, false);
+
+window.addEventListener("resize", function () {
+    measureRows();
+    queueRender();
+}, false);
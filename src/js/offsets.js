@@ -1,11 +1,11 @@
-function populateOffsets(columns) {
-    let rows = Math.ceil(fileLen / columns);
+function populateOffsets() {
     let elements = "";
-    var offset = 0;
 
-    for (var i = 0; i < rows; ++i) {
-        elements += offset.toString(16) + "</br>\n";
-        offset += columns;
+    // collapsed ranges take up a row each, see js/collapse.js
+    for (var row = 0; row < visualRows; ++row) {
+        var offset = rowOffset(row);
+
+        elements += (offset === null ? ".." : offset.toString(16)) + "</br>\n";
     }
 
     document.getElementById('offsets').innerHTML = elements;
@@ -0,0 +1,66 @@
+var themeKey = "elfcat_theme";
+
+function applyTheme(theme) {
+    var root = document.documentElement;
+
+    root.classList.remove("light", "dark");
+    root.classList.add(theme);
+}
+
+function storedTheme() {
+    // localStorage may be unavailable for file:// pages
+    try {
+        return localStorage.getItem(themeKey);
+    } catch (e) {
+        return null;
+    }
+}
+
+function systemTheme() {
+    var query = window.matchMedia && window.matchMedia("(prefers-color-scheme: dark)");
+
+    return query && query.matches ? "dark" : "light";
+}
+
+// a theme given on the command line wins over the one stored by the toggle
+function preferredTheme() {
+    if (defaultTheme === "light" || defaultTheme === "dark") {
+        return defaultTheme;
+    }
+
+    var stored = storedTheme();
+
+    if (stored === "light" || stored === "dark") {
+        return stored;
+    }
+
+    return systemTheme();
+}
+
+function toggleTheme() {
+    var isDark = document.documentElement.classList.contains("dark");
+    var theme = isDark ? "light" : "dark";
+
+    applyTheme(theme);
+
+    try {
+        localStorage.setItem(themeKey, theme);
+    } catch (e) {
+    }
+}
+
+applyTheme(preferredTheme());
+
+if (window.matchMedia) {
+    window.matchMedia("(prefers-color-scheme: dark)").addListener(function () {
+        applyTheme(preferredTheme());
+    });
+}
+
+document.addEventListener("DOMContentLoaded", function () {
+    var toggle = document.getElementById("theme_toggle");
+
+    if (toggle !== null) {
+        toggle.addEventListener("click", toggleTheme, false);
+    }
+}, false);
@@ -0,0 +1,27 @@
+function linkMappings() {
+    var mappings = document.querySelectorAll("#vmap [data-segment]");
+
+    for (var i = 0; i < mappings.length; i++) {
+        var mapping = mappings[i];
+        var segmentId = "bin_segment" + mapping.dataset.segment;
+        var segment = document.getElementById(segmentId);
+
+        if (segment === null) {
+            continue;
+        }
+
+        mapping.classList.add("vmap_linked");
+
+        highlightIds(mapping.id, segmentId);
+
+        mapping.addEventListener("click", function (segment) {
+            return function (event) {
+                // overlays are nested in mappings, don't let the outer one win
+                event.stopPropagation();
+                shownElem(segment).scrollIntoView({ block: "center" });
+            };
+        }(segment), false);
+    }
+}
+
+linkMappings();
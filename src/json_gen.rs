@@ -0,0 +1,780 @@
+use crate::elf::defs::*;
+use crate::elf::parser::{
+    Die, EhFrameHdr, FrameEntry, FrameRecord, HashTable, LineProgram, Note, ParsedElf, ParsedPhdr,
+    ParsedShdr, RangeType, VersionRecord,
+};
+use crate::utils::strip_html_tags;
+use std::fmt::{self, Write};
+
+// Bump on any incompatible change to the layout below. Adding new keys is
+// not considered incompatible.
+//
+// Version 1 has these top-level keys: schema, version, file, machine,
+// information, program_headers, section_headers, notes, symbol_tables,
+// relocations, versions, hash_tables, compressed_sections, dwarf_units,
+// line_programs, frame_records, eh_frame_hdr, dynamic, diagnostics and
+// ranges. Every range has start, end and kind, plus keys by kind:
+//
+// - no more: ident, file_header, malformed
+// - field: file_header_field, program_header_field, section_header_field,
+//   note_field, symbol_field, relocation_field, dynamic_field,
+//   version_field, hash_field, dwarf_field, frame_field,
+//   compression_header_field
+// - index: program_header, section_header, segment, section, note, dynamic,
+//   dwarf_unit, line_program, frame_record, eh_frame_hdr_entry
+// - section and index: symbol, relocation, version
+// - unit and index: die
+// - unit, die and index: die_attribute
+// - program and index: line_op
+// - record and index: cfa_op
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+const INDENT: &str = "  ";
+
+enum Scalar {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Str(String),
+}
+
+struct Container {
+    close: char,
+    inline: bool,
+    empty: bool,
+}
+
+// Writes the document straight into `o` as it's produced, so that large
+// tables such as the ranges are never held in memory. Containers which only
+// hold scalars are kept on one line so that the output diffs well, everything
+// else is spread over multiple lines. Callers pick the layout when they open
+// a container.
+struct JsonWriter<'a> {
+    o: &'a mut dyn Write,
+    open: Vec<Container>,
+}
+
+impl<'a> JsonWriter<'a> {
+    fn new(o: &'a mut dyn Write) -> JsonWriter<'a> {
+        JsonWriter { o, open: vec![] }
+    }
+
+    fn separator(&mut self) -> fmt::Result {
+        let level = self.open.len();
+        let container = match self.open.last_mut() {
+            Some(container) => container,
+            None => return Ok(()),
+        };
+        let first = container.empty;
+
+        container.empty = false;
+
+        if container.inline {
+            if !first {
+                self.o.write_str(", ")?;
+            }
+        } else {
+            self.o.write_str(if first { "\n" } else { ",\n" })?;
+            self.o.write_str(&INDENT.repeat(level))?;
+        }
+
+        Ok(())
+    }
+
+    // starts the next item of the innermost array
+    fn item(&mut self) -> fmt::Result {
+        self.separator()
+    }
+
+    // starts the next field of the innermost object
+    fn key(&mut self, key: &str) -> fmt::Result {
+        self.separator()?;
+        write_json_str(self.o, key)?;
+        self.o.write_str(": ")
+    }
+
+    fn scalar(&mut self, value: Scalar) -> fmt::Result {
+        match value {
+            Scalar::Null => self.o.write_str("null"),
+            Scalar::Bool(value) => write!(self.o, "{}", value),
+            Scalar::Int(value) => write!(self.o, "{}", value),
+            Scalar::Str(value) => write_json_str(self.o, &value),
+        }
+    }
+
+    fn begin(&mut self, open: char, close: char, inline: bool) -> fmt::Result {
+        self.open.push(Container {
+            close,
+            inline,
+            empty: true,
+        });
+
+        self.o.write_char(open)
+    }
+
+    fn begin_obj(&mut self, inline: bool) -> fmt::Result {
+        self.begin('{', '}', inline)
+    }
+
+    fn begin_arr(&mut self, inline: bool) -> fmt::Result {
+        self.begin('[', ']', inline)
+    }
+
+    fn end(&mut self) -> fmt::Result {
+        let container = self.open.pop().expect("no open container");
+
+        if !container.inline && !container.empty {
+            self.o.write_char('\n')?;
+            self.o.write_str(&INDENT.repeat(self.open.len()))?;
+        }
+
+        self.o.write_char(container.close)
+    }
+
+    fn field(&mut self, key: &str, value: Scalar) -> fmt::Result {
+        self.key(key)?;
+        self.scalar(value)
+    }
+
+    // multi-line array with one item per element of `items`
+    fn arr<T>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+        mut write_item: impl FnMut(&mut Self, T) -> fmt::Result,
+    ) -> fmt::Result {
+        self.begin_arr(false)?;
+
+        for item in items {
+            self.item()?;
+            write_item(self, item)?;
+        }
+
+        self.end()
+    }
+
+    // one-line array of scalars
+    fn scalars(&mut self, items: impl IntoIterator<Item = Scalar>) -> fmt::Result {
+        self.begin_arr(true)?;
+
+        for item in items {
+            self.item()?;
+            self.scalar(item)?;
+        }
+
+        self.end()
+    }
+}
+
+fn write_json_str(o: &mut dyn Write, s: &str) -> fmt::Result {
+    o.write_char('"')?;
+
+    for ch in s.chars() {
+        match ch {
+            '"' => o.write_str("\\\"")?,
+            '\\' => o.write_str("\\\\")?,
+            '\n' => o.write_str("\\n")?,
+            '\r' => o.write_str("\\r")?,
+            '\t' => o.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(o, "\\u{:04x}", c as u32)?,
+            c => o.write_char(c)?,
+        }
+    }
+
+    o.write_char('"')
+}
+
+fn int<T: Into<i128>>(value: T) -> Scalar {
+    Scalar::Int(value.into())
+}
+
+fn uint(value: usize) -> Scalar {
+    Scalar::Int(value as i128)
+}
+
+fn string<T: Into<String>>(value: T) -> Scalar {
+    Scalar::Str(value.into())
+}
+
+fn hex_string(bytes: &[u8]) -> Scalar {
+    Scalar::Str(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn information(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.information.iter(), |w, (id, desc, value)| {
+        w.begin_obj(true)?;
+        w.field("id", string(*id))?;
+        w.field("description", string(*desc))?;
+        w.field("value", string(strip_html_tags(value)))?;
+        w.end()
+    })
+}
+
+fn program_header(w: &mut JsonWriter, idx: usize, phdr: &ParsedPhdr) -> fmt::Result {
+    w.begin_obj(true)?;
+    w.field("index", uint(idx))?;
+    w.field("type", int(phdr.ptype))?;
+    w.field("type_name", string(ptype_to_string(phdr.ptype)))?;
+    w.field("flags", string(phdr.flags.as_str()))?;
+    w.field("offset", uint(phdr.file_offset))?;
+    w.field("file_size", uint(phdr.file_size))?;
+    w.field("vaddr", uint(phdr.vaddr))?;
+    w.field("memory_size", uint(phdr.memsz))?;
+    w.field("alignment", uint(phdr.alignment))?;
+    w.end()
+}
+
+fn section_header(
+    w: &mut JsonWriter,
+    elf: &ParsedElf,
+    idx: usize,
+    shdr: &ParsedShdr,
+) -> fmt::Result {
+    w.begin_obj(true)?;
+    w.field("index", uint(idx))?;
+    w.field("name", string(elf.shnstrtab.get(shdr.name)))?;
+    w.field("type", int(shdr.shtype))?;
+    w.field("type_name", string(shtype_to_string(shdr.shtype)))?;
+    w.field("flags", int(shdr.flags))?;
+    w.field("addr", uint(shdr.addr))?;
+    w.field("offset", uint(shdr.file_offset))?;
+    w.field("size", uint(shdr.size))?;
+    w.field("link", uint(shdr.link))?;
+    w.field("info", uint(shdr.info))?;
+    w.field("addralign", uint(shdr.addralign))?;
+    w.field("entsize", uint(shdr.entsize))?;
+    w.end()
+}
+
+fn note(w: &mut JsonWriter, note: &Note) -> fmt::Result {
+    let name = note.name.split(|&c| c == 0).next().unwrap_or(&[]);
+
+    w.begin_obj(false)?;
+    w.field("name", string(String::from_utf8_lossy(name)))?;
+    w.field("type", int(note.ntype))?;
+    w.field("desc", hex_string(&note.desc))?;
+    w.field("offset", uint(note.offset))?;
+    w.field("size", uint(note.size))?;
+    w.field("segment", note.segment.map_or(Scalar::Null, int))?;
+    w.field("section", note.section.map_or(Scalar::Null, int))?;
+    w.key("decoded")?;
+    w.arr(note.decoded.iter(), |w, (label, value)| {
+        w.scalars(vec![string(label), string(value)])
+    })?;
+    w.end()
+}
+
+fn symbol_tables(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.symtabs.iter(), |w, (section, symbols)| {
+        w.begin_obj(false)?;
+        w.field("section", int(*section))?;
+        w.key("symbols")?;
+        w.arr(symbols.iter().enumerate(), |w, (i, sym)| {
+            let version = elf.symbol_version(*section, i);
+
+            w.begin_obj(true)?;
+            w.field("name", string(sym.name.as_str()))?;
+            w.field("version", version.map_or(Scalar::Null, string))?;
+            w.field("value", uint(sym.value))?;
+            w.field("size", uint(sym.size))?;
+            w.field("type", string(sttype_to_string(sym.stype)))?;
+            w.field("bind", string(stbind_to_string(sym.binding)))?;
+            w.field("visibility", string(stvis_to_string(sym.visibility)))?;
+            w.field("shndx", int(sym.shndx))?;
+            w.end()
+        })?;
+        w.end()
+    })
+}
+
+fn relocations(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.relocations.iter(), |w, (section, relocations)| {
+        w.begin_obj(false)?;
+        w.field("section", int(*section))?;
+        w.key("entries")?;
+        w.arr(relocations.iter(), |w, rel| {
+            w.begin_obj(true)?;
+            w.field("offset", uint(rel.offset))?;
+            w.field("symbol", int(rel.sym))?;
+            w.field("type", int(rel.rtype))?;
+            w.field(
+                "type_name",
+                string(reltype_to_string(elf.machine, rel.rtype)),
+            )?;
+            w.field("addend", rel.addend.map_or(Scalar::Null, int))?;
+            w.end()
+        })?;
+        w.end()
+    })
+}
+
+fn version_record(w: &mut JsonWriter, record: &VersionRecord) -> fmt::Result {
+    w.begin_obj(true)?;
+
+    match record {
+        VersionRecord::Need { version, file } => {
+            w.field("kind", string("verneed"))?;
+            w.field("version", int(*version))?;
+            w.field("file", string(file.as_str()))?;
+        }
+        VersionRecord::NeedAux {
+            hash,
+            flags,
+            index,
+            name,
+        } => {
+            w.field("kind", string("vernaux"))?;
+            w.field("hash", int(*hash))?;
+            w.field("flags", int(*flags))?;
+            w.field("index", int(*index))?;
+            w.field("name", string(name.as_str()))?;
+        }
+        VersionRecord::Def {
+            version,
+            flags,
+            index,
+            hash,
+            name,
+        } => {
+            w.field("kind", string("verdef"))?;
+            w.field("version", int(*version))?;
+            w.field("flags", int(*flags))?;
+            w.field("index", int(*index))?;
+            w.field("hash", int(*hash))?;
+            w.field("name", string(name.as_str()))?;
+        }
+        VersionRecord::DefAux { name } => {
+            w.field("kind", string("verdaux"))?;
+            w.field("name", string(name.as_str()))?;
+        }
+    }
+
+    w.end()
+}
+
+fn versions(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.versions.iter(), |w, (section, records)| {
+        w.begin_obj(false)?;
+        w.field("section", int(*section))?;
+        w.key("records")?;
+        w.arr(records.iter(), version_record)?;
+        w.end()
+    })
+}
+
+fn u32s(values: &[u32]) -> impl Iterator<Item = Scalar> + '_ {
+    values.iter().map(|value| int(*value))
+}
+
+fn hash_tables(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.hash_tables.iter(), |w, (section, table)| {
+        w.begin_obj(false)?;
+        w.field("section", int(*section))?;
+
+        match table {
+            HashTable::Sysv { buckets, chains } => {
+                w.field("kind", string("sysv"))?;
+                w.key("buckets")?;
+                w.scalars(u32s(buckets))?;
+                w.key("chains")?;
+                w.scalars(u32s(chains))?;
+            }
+            HashTable::Gnu {
+                symoffset,
+                bloom_shift,
+                bloom_bits,
+                bloom,
+                buckets,
+                chains,
+            } => {
+                w.field("kind", string("gnu"))?;
+                w.field("symoffset", int(*symoffset))?;
+                w.field("bloom_shift", int(*bloom_shift))?;
+                w.field("bloom_bits", int(*bloom_bits))?;
+                w.key("bloom")?;
+                w.scalars(bloom.iter().map(|word| int(*word)))?;
+                w.key("buckets")?;
+                w.scalars(u32s(buckets))?;
+                w.key("chains")?;
+                w.scalars(u32s(chains))?;
+            }
+        }
+
+        w.end()
+    })
+}
+
+fn compressed_sections(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.compressed.iter(), |w, (section, compressed)| {
+        w.begin_obj(true)?;
+        w.field("section", int(*section))?;
+        w.field("compression", int(compressed.compression))?;
+        w.field(
+            "compression_name",
+            string(elfcompress_to_string(compressed.compression)),
+        )?;
+        w.field("legacy", Scalar::Bool(compressed.legacy))?;
+        w.field("size", int(compressed.size))?;
+        w.field("alignment", int(compressed.alignment))?;
+        w.field("header_size", uint(compressed.header_size))?;
+        w.field("decompressed", Scalar::Bool(compressed.data.is_some()))?;
+        w.end()
+    })
+}
+
+fn die(w: &mut JsonWriter, die: &Die) -> fmt::Result {
+    w.begin_obj(false)?;
+    w.field("offset", uint(die.offset))?;
+    w.field("size", uint(die.size))?;
+    w.field("depth", uint(die.depth))?;
+    w.field("tag", int(die.tag))?;
+    w.field("tag_str", string(dwarf_tag_to_string(die.tag)))?;
+    w.field("children", Scalar::Bool(die.children))?;
+    w.key("attributes")?;
+    w.arr(die.attributes.iter(), |w, attribute| {
+        w.begin_obj(true)?;
+        w.field("name", int(attribute.name))?;
+        w.field("name_str", string(dwarf_at_to_string(attribute.name)))?;
+        w.field("form", int(attribute.form))?;
+        w.field("form_str", string(dwarf_form_to_string(attribute.form)))?;
+        w.field("offset", uint(attribute.offset))?;
+        w.field("size", uint(attribute.size))?;
+        w.field("value", string(attribute.value.as_str()))?;
+        w.field("reference", attribute.reference.map_or(Scalar::Null, uint))?;
+        w.end()
+    })?;
+    w.end()
+}
+
+fn dwarf_units(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.dwarf_units.iter(), |w, unit| {
+        w.begin_obj(false)?;
+        w.field("section", int(unit.section))?;
+        w.field("offset", uint(unit.offset))?;
+        w.field("size", uint(unit.size))?;
+        w.field("format", int(if unit.format64 { 64 } else { 32 }))?;
+        w.field("version", int(unit.version))?;
+        w.field("unit_type", int(unit.unit_type))?;
+        w.field("address_size", int(unit.address_size))?;
+        w.field("abbrev_offset", int(unit.abbrev_offset))?;
+        w.key("dies")?;
+        w.arr(unit.dies.iter(), die)?;
+        w.end()
+    })
+}
+
+fn line_program(w: &mut JsonWriter, program: &LineProgram) -> fmt::Result {
+    w.begin_obj(false)?;
+    w.field("section", int(program.section))?;
+    w.field("offset", uint(program.offset))?;
+    w.field("size", uint(program.size))?;
+    w.field("format", int(if program.format64 { 64 } else { 32 }))?;
+    w.field("version", int(program.version))?;
+    w.field("min_inst_length", int(program.min_inst_length))?;
+    w.field("max_ops_per_inst", int(program.max_ops_per_inst))?;
+    w.field("default_is_stmt", Scalar::Bool(program.default_is_stmt))?;
+    w.field("line_base", int(program.line_base))?;
+    w.field("line_range", int(program.line_range))?;
+    w.field("opcode_base", int(program.opcode_base))?;
+    w.key("opcode_lengths")?;
+    w.scalars(program.opcode_lengths.iter().map(|&len| int(len)))?;
+    w.key("directories")?;
+    w.scalars(program.directories.iter().map(|dir| string(dir.as_str())))?;
+    w.key("files")?;
+    w.arr(program.files.iter(), |w, file| {
+        w.begin_obj(true)?;
+        w.field("name", string(file.name.as_str()))?;
+        w.field("directory", int(file.directory))?;
+        w.end()
+    })?;
+    w.key("ops")?;
+    w.arr(program.ops.iter(), |w, op| {
+        w.begin_obj(true)?;
+        w.field("offset", uint(op.offset))?;
+        w.field("size", uint(op.size))?;
+        w.field("name", string(op.name.as_str()))?;
+        w.field("operands", string(op.operands.as_str()))?;
+        w.field("row", op.row.map_or(Scalar::Null, uint))?;
+        w.end()
+    })?;
+    w.key("rows")?;
+    w.arr(program.rows.iter(), |w, row| {
+        w.begin_obj(true)?;
+        w.field("address", int(row.address))?;
+        w.field("file", int(row.file))?;
+        w.field("line", int(row.line))?;
+        w.field("column", int(row.column))?;
+        w.field("is_stmt", Scalar::Bool(row.is_stmt))?;
+        w.field("end_sequence", Scalar::Bool(row.end_sequence))?;
+        w.end()
+    })?;
+    w.end()
+}
+
+fn frame_record(w: &mut JsonWriter, record: &FrameRecord) -> fmt::Result {
+    w.begin_obj(false)?;
+    w.field("offset", uint(record.offset))?;
+    w.field("size", uint(record.size))?;
+    w.field("address", int(record.address))?;
+
+    match &record.entry {
+        FrameEntry::Cie(cie) => {
+            w.field("kind", string("cie"))?;
+            w.field("version", int(cie.version))?;
+            w.field("augmentation", string(cie.augmentation.as_str()))?;
+            w.field("code_alignment", int(cie.code_alignment))?;
+            w.field("data_alignment", int(cie.data_alignment))?;
+            w.field("return_register", int(cie.return_register))?;
+            w.field("fde_encoding", int(cie.fde_encoding))?;
+            w.field("lsda_encoding", int(cie.lsda_encoding))?;
+            w.field("personality", cie.personality.map_or(Scalar::Null, int))?;
+        }
+        FrameEntry::Fde(fde) => {
+            w.field("kind", string("fde"))?;
+            w.field("cie", fde.cie.map_or(Scalar::Null, uint))?;
+            w.field("pc_begin", int(fde.pc_begin))?;
+            w.field("pc_range", int(fde.pc_range))?;
+            w.field("lsda", fde.lsda.map_or(Scalar::Null, int))?;
+        }
+    }
+
+    w.key("instructions")?;
+    w.arr(record.instructions.iter(), |w, op| {
+        w.begin_obj(true)?;
+        w.field("offset", uint(op.offset))?;
+        w.field("size", uint(op.size))?;
+        w.field("name", string(op.name.as_str()))?;
+        w.field("operands", string(op.operands.as_str()))?;
+        w.end()
+    })?;
+    w.end()
+}
+
+fn eh_frame_hdr(w: &mut JsonWriter, hdr: &EhFrameHdr) -> fmt::Result {
+    w.begin_obj(false)?;
+    w.field("offset", uint(hdr.offset))?;
+    w.field("size", uint(hdr.size))?;
+    w.field("address", int(hdr.address))?;
+    w.field("version", int(hdr.version))?;
+    w.field("eh_frame_ptr_encoding", int(hdr.eh_frame_ptr_encoding))?;
+    w.field("fde_count_encoding", int(hdr.fde_count_encoding))?;
+    w.field("table_encoding", int(hdr.table_encoding))?;
+    w.field("eh_frame_ptr", hdr.eh_frame_ptr.map_or(Scalar::Null, int))?;
+    w.field("fde_count", hdr.fde_count.map_or(Scalar::Null, int))?;
+    w.key("table")?;
+    w.arr(hdr.table.iter(), |w, &(initial_location, fde)| {
+        w.begin_obj(true)?;
+        w.field("initial_location", int(initial_location))?;
+        w.field("fde", int(fde))?;
+        w.end()
+    })?;
+    w.end()
+}
+
+fn dynamic(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.dynamic.iter(), |w, entry| {
+        w.begin_obj(true)?;
+        w.field("tag", int(entry.tag))?;
+        w.field("tag_name", string(dtag_to_string(entry.tag)))?;
+        w.field("value", int(entry.val))?;
+        w.end()
+    })
+}
+
+fn diagnostics(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.diagnostics.iter(), |w, diagnostic| {
+        let (start, len) = match diagnostic.location {
+            Some((start, len)) => (uint(start), uint(len)),
+            None => (Scalar::Null, Scalar::Null),
+        };
+
+        w.begin_obj(true)?;
+        w.field("offset", uint(diagnostic.error.offset()))?;
+        w.field("message", string(diagnostic.error.to_string()))?;
+        w.field("start", start)?;
+        w.field("length", len)?;
+        w.end()
+    })
+}
+
+fn range_kind(range_type: &RangeType) -> Vec<(&'static str, Scalar)> {
+    match range_type {
+        RangeType::Ident => vec![("kind", string("ident"))],
+        RangeType::FileHeader => vec![("kind", string("file_header"))],
+        RangeType::HeaderField(field) => {
+            vec![
+                ("kind", string("file_header_field")),
+                ("field", string(*field)),
+            ]
+        }
+        RangeType::ProgramHeader(idx) => {
+            vec![("kind", string("program_header")), ("index", int(*idx))]
+        }
+        RangeType::SectionHeader(idx) => {
+            vec![("kind", string("section_header")), ("index", int(*idx))]
+        }
+        RangeType::PhdrField(field) => {
+            vec![
+                ("kind", string("program_header_field")),
+                ("field", string(*field)),
+            ]
+        }
+        RangeType::ShdrField(field) => {
+            vec![
+                ("kind", string("section_header_field")),
+                ("field", string(*field)),
+            ]
+        }
+        RangeType::Segment(idx) => vec![("kind", string("segment")), ("index", int(*idx))],
+        RangeType::Section(idx) => vec![("kind", string("section")), ("index", int(*idx))],
+        RangeType::Note(idx) => vec![("kind", string("note")), ("index", int(*idx))],
+        RangeType::Version(section, idx) => vec![
+            ("kind", string("version")),
+            ("section", int(*section)),
+            ("index", int(*idx)),
+        ],
+        RangeType::VersionField(field) => {
+            vec![("kind", string("version_field")), ("field", string(*field))]
+        }
+        RangeType::HashField(field) => {
+            vec![("kind", string("hash_field")), ("field", string(*field))]
+        }
+        RangeType::DwarfUnit(idx) => vec![("kind", string("dwarf_unit")), ("index", int(*idx))],
+        RangeType::DwarfField(field) => {
+            vec![("kind", string("dwarf_field")), ("field", string(*field))]
+        }
+        RangeType::Die(unit, idx) => vec![
+            ("kind", string("die")),
+            ("unit", int(*unit)),
+            ("index", int(*idx)),
+        ],
+        RangeType::DieAttribute(unit, die, idx) => vec![
+            ("kind", string("die_attribute")),
+            ("unit", int(*unit)),
+            ("die", int(*die)),
+            ("index", int(*idx)),
+        ],
+        RangeType::LineProgram(idx) => {
+            vec![("kind", string("line_program")), ("index", int(*idx))]
+        }
+        RangeType::LineOp(program, idx) => vec![
+            ("kind", string("line_op")),
+            ("program", int(*program)),
+            ("index", int(*idx)),
+        ],
+        RangeType::FrameRecord(idx) => {
+            vec![("kind", string("frame_record")), ("index", int(*idx))]
+        }
+        RangeType::FrameField(field) => {
+            vec![("kind", string("frame_field")), ("field", string(*field))]
+        }
+        RangeType::CfaOp(record, idx) => vec![
+            ("kind", string("cfa_op")),
+            ("record", int(*record)),
+            ("index", int(*idx)),
+        ],
+        RangeType::FrameHdrEntry(idx) => {
+            vec![("kind", string("eh_frame_hdr_entry")), ("index", int(*idx))]
+        }
+        RangeType::ChdrField(field) => vec![
+            ("kind", string("compression_header_field")),
+            ("field", string(*field)),
+        ],
+        RangeType::NoteField(field) => {
+            vec![("kind", string("note_field")), ("field", string(*field))]
+        }
+        RangeType::Symbol(section, idx) => vec![
+            ("kind", string("symbol")),
+            ("section", int(*section)),
+            ("index", int(*idx)),
+        ],
+        RangeType::SymbolField(field) => {
+            vec![("kind", string("symbol_field")), ("field", string(*field))]
+        }
+        RangeType::Relocation(section, idx) => vec![
+            ("kind", string("relocation")),
+            ("section", int(*section)),
+            ("index", int(*idx)),
+        ],
+        RangeType::RelocationField(field) => {
+            vec![
+                ("kind", string("relocation_field")),
+                ("field", string(*field)),
+            ]
+        }
+        RangeType::Dynamic(idx) => vec![("kind", string("dynamic")), ("index", int(*idx))],
+        RangeType::DynamicField(field) => {
+            vec![("kind", string("dynamic_field")), ("field", string(*field))]
+        }
+        RangeType::Malformed => vec![("kind", string("malformed"))],
+    }
+}
+
+fn ranges(w: &mut JsonWriter, elf: &ParsedElf) -> fmt::Result {
+    w.arr(elf.ranges.iter(), |w, range| {
+        w.begin_obj(true)?;
+        w.field("start", uint(range.start))?;
+        w.field("end", uint(range.end))?;
+
+        for (key, value) in range_kind(&range.range_type) {
+            w.field(key, value)?;
+        }
+
+        w.end()
+    })
+}
+
+pub fn generate_json(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    let mut w = JsonWriter::new(o);
+
+    w.begin_obj(false)?;
+    w.field("schema", string("elfcat"))?;
+    w.field("version", int(JSON_SCHEMA_VERSION))?;
+    w.key("file")?;
+    w.begin_obj(true)?;
+    w.field("name", string(elf.filename.as_str()))?;
+    w.field("size", uint(elf.file_size))?;
+    w.end()?;
+    w.field("machine", int(elf.machine))?;
+    w.key("information")?;
+    information(&mut w, elf)?;
+    w.key("program_headers")?;
+    w.arr(elf.phdrs.iter().enumerate(), |w, (i, phdr)| {
+        program_header(w, i, phdr)
+    })?;
+    w.key("section_headers")?;
+    w.arr(elf.shdrs.iter().enumerate(), |w, (i, shdr)| {
+        section_header(w, elf, i, shdr)
+    })?;
+    w.key("notes")?;
+    w.arr(elf.notes.iter(), note)?;
+    w.key("symbol_tables")?;
+    symbol_tables(&mut w, elf)?;
+    w.key("relocations")?;
+    relocations(&mut w, elf)?;
+    w.key("versions")?;
+    versions(&mut w, elf)?;
+    w.key("hash_tables")?;
+    hash_tables(&mut w, elf)?;
+    w.key("compressed_sections")?;
+    compressed_sections(&mut w, elf)?;
+    w.key("dwarf_units")?;
+    dwarf_units(&mut w, elf)?;
+    w.key("line_programs")?;
+    w.arr(elf.line_programs.iter(), line_program)?;
+    w.key("frame_records")?;
+    w.arr(elf.frame_records.iter(), frame_record)?;
+    w.key("eh_frame_hdr")?;
+    match &elf.eh_frame_hdr {
+        Some(hdr) => eh_frame_hdr(&mut w, hdr)?,
+        None => w.scalar(Scalar::Null)?,
+    }
+    w.key("dynamic")?;
+    dynamic(&mut w, elf)?;
+    w.key("diagnostics")?;
+    diagnostics(&mut w, elf)?;
+    w.key("ranges")?;
+    ranges(&mut w, elf)?;
+    w.end()?;
+
+    o.write_char('\n')
+}
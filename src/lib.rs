@@ -0,0 +1,20 @@
+// elfcat as a library: parse ELF files with ParsedElf::from_bytes and turn
+// them into HTML, JSON or terminal reports with generate_report. The elfcat binary is a thin
+// wrapper around these.
+
+pub mod elf;
+mod json_gen;
+mod report_gen;
+mod term_gen;
+mod utils;
+
+pub use elf::error::{ElfError, Structure};
+pub use elf::parser::{
+    Diagnostic, DynamicEntry, Note, ParsedElf, ParsedPhdr, ParsedShdr, RangeType, Relocation,
+    StrTab, Symbol,
+};
+pub use elf::ranges::{Range, RangeEvent, Ranges};
+pub use json_gen::JSON_SCHEMA_VERSION;
+pub use report_gen::{
+    construct_filename, generate_report, Format, ReportOptions, Theme, ALLOWED_WIDTHS,
+};
@@ -1,41 +1,178 @@
-mod elf;
-mod report_gen;
-mod utils;
-
-use elf::parser::ParsedElf;
+use elfcat::{Format, ParsedElf, ReportOptions, Theme};
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Write};
 
 fn main() {
-    let filename = parse_arguments();
-    let contents = std::fs::read(&filename).unwrap();
-    let elf = ParsedElf::from_bytes(&filename, &contents).unwrap();
-    let report_filename = report_gen::construct_filename(&filename);
-    let report = report_gen::generate_report(&elf);
+    let (filename, output, options) = parse_arguments();
+    let contents = match std::fs::read(&filename) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("elfcat: failed to read '{}': {}", filename, e);
+            std::process::exit(1)
+        }
+    };
+    let elf = match ParsedElf::from_bytes(&filename, &contents) {
+        Ok(elf) => elf,
+        Err(e) => {
+            eprintln!("elfcat: failed to parse '{}': {}", filename, e);
+            std::process::exit(1)
+        }
+    };
+    let output = output.unwrap_or_else(|| match options.format {
+        Format::Term => String::from("-"),
+        format => elfcat::construct_filename(&filename, format),
+    });
+    let result = if output == "-" {
+        let stdout = std::io::stdout();
+
+        write_report(&elf, &options, BufWriter::new(stdout.lock()))
+    } else {
+        File::create(&output).and_then(|file| write_report(&elf, &options, BufWriter::new(file)))
+    };
 
-    std::fs::write(report_filename, report).expect("failed to write report");
+    match result {
+        Ok(()) => {}
+        // quitting a pager early is not an error
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            eprintln!("elfcat: failed to write '{}': {}", output, e);
+            std::process::exit(1)
+        }
+    }
 }
 
-fn parse_arguments() -> String {
-    let args: Vec<String> = std::env::args().collect();
+fn write_report(elf: &ParsedElf, options: &ReportOptions, mut out: impl Write) -> io::Result<()> {
+    elfcat::generate_report(elf, options, &mut out)?;
+
+    out.flush()
+}
 
-    if args.len() != 2 {
-        usage(1);
+fn parse_format(value: Option<&String>) -> Format {
+    match value.map(|value| value.as_str()) {
+        Some("html") => Format::Html,
+        Some("json") => Format::Json,
+        Some("term") => Format::Term,
+        _ => {
+            eprintln!("elfcat: --format must be html, json or term");
+            usage(1)
+        }
     }
+}
+
+fn parse_width(value: Option<&String>) -> usize {
+    let width = value.and_then(|value| value.parse::<usize>().ok());
 
-    if args[1] == "-h" || args[1] == "--help" {
-        usage(0);
+    match width {
+        Some(width) if elfcat::ALLOWED_WIDTHS.contains(&width) => width,
+        _ => {
+            eprintln!("elfcat: --width must be one of 8, 16, 32 or 64");
+            usage(1)
+        }
     }
+}
 
-    if args[1] == "-v" || args[1] == "--version" {
-        println!("elfcat {}", env!("CARGO_PKG_VERSION"));
-        std::process::exit(0);
+fn parse_collapse(value: Option<&String>) -> usize {
+    match value.and_then(|value| value.parse::<usize>().ok()) {
+        Some(kib) => kib.saturating_mul(1024),
+        None => {
+            eprintln!("elfcat: --collapse requires a size in KiB");
+            usage(1)
+        }
     }
+}
 
-    args[1].clone()
+fn parse_theme(value: Option<&String>) -> Theme {
+    match value.map(|value| value.as_str()) {
+        Some("auto") => Theme::Auto,
+        Some("light") => Theme::Light,
+        Some("dark") => Theme::Dark,
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(css) => Theme::Custom(css),
+            Err(e) => {
+                eprintln!("elfcat: failed to read theme '{}': {}", path, e);
+                std::process::exit(1)
+            }
+        },
+        None => {
+            eprintln!("elfcat: --theme requires an argument");
+            usage(1)
+        }
+    }
+}
+
+fn parse_arguments() -> (String, Option<String>, ReportOptions) {
+    let args: Vec<String> = std::env::args().collect();
+    let mut filename = None;
+    let mut output = None;
+    let mut options = ReportOptions::default();
+    let mut i = 1;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "-h" | "--help" => usage(0),
+            "-v" | "--version" => {
+                println!("elfcat {}", env!("CARGO_PKG_VERSION"));
+                std::process::exit(0);
+            }
+            "-o" | "--output" => {
+                i += 1;
+
+                match args.get(i) {
+                    Some(path) => output = Some(path.clone()),
+                    None => {
+                        eprintln!("elfcat: --output requires an argument");
+                        usage(1)
+                    }
+                }
+            }
+            "--format" => {
+                i += 1;
+                options.format = parse_format(args.get(i));
+            }
+            "--lazy" => options.lazy = true,
+            "--collapse" => {
+                i += 1;
+                options.collapse_above = Some(parse_collapse(args.get(i)));
+            }
+            "--width" => {
+                i += 1;
+                options.width = parse_width(args.get(i));
+            }
+            "--theme" => {
+                i += 1;
+                options.theme = parse_theme(args.get(i));
+            }
+            arg => {
+                if filename.is_some() {
+                    usage(1);
+                }
+
+                filename = Some(arg.to_string());
+            }
+        }
+
+        i += 1;
+    }
+
+    match filename {
+        Some(filename) => (filename, output, options),
+        None => usage(1),
+    }
 }
 
-fn usage(ret: i32) {
-    println!("Usage: elfcat <filename>");
-    println!("Writes <filename>.html to CWD.");
+fn usage(ret: i32) -> ! {
+    println!("Usage: elfcat [OPTIONS] <filename>");
+    println!("Writes <filename>.html (or .json) to CWD, or prints to stdout with --format term.");
+    println!();
+    println!("Options:");
+    println!("  --format FORMAT    html, json or term (default: html)");
+    println!("  -o, --output PATH  where to write the report, - for stdout");
+    println!("  --lazy             only build the visible rows of the dump, for large files");
+    println!("  --collapse N       start with segments and sections larger than N KiB collapsed");
+    println!("  --width N          bytes per row in the dump: 8, 16, 32 or 64 (default: 16)");
+    println!(
+        "  --theme THEME      auto, light, dark or path to a CSS file to embed (default: auto)"
+    );
 
     std::process::exit(ret);
 }
@@ -1,10 +1,75 @@
 use crate::elf::defs::*;
-use crate::elf::parser::{Note, ParsedElf, ParsedPhdr, ParsedShdr, RangeType};
-use std::fmt::Write;
+use crate::elf::parser::{
+    CompressedSection, Die, DwarfUnit, DynamicEntry, EhFrameHdr, FrameEntry, FrameRecord,
+    HashTable, LineProgram, MappedFile, Note, ParsedElf, ParsedPhdr, ParsedShdr, RangeType, StrTab,
+    Symbol, VersionRecord,
+};
+use crate::elf::ranges::{Events, Range, RangeEvent};
+use crate::json_gen::generate_json;
+use crate::term_gen::generate_term;
+use crate::utils::{html_escape_str, human_format_bytes, write_base64, Base64Writer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+use std::io;
+use std::iter::Peekable;
 use std::path::Path;
 
 const INDENT: &str = "  ";
 
+pub const ALLOWED_WIDTHS: [usize; 4] = [8, 16, 32, 64];
+
+pub enum Theme {
+    // follow prefers-color-scheme
+    Auto,
+    Light,
+    Dark,
+    // contents of a user-supplied stylesheet, embedded after the built-in ones
+    Custom(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Html,
+    // see json_gen.rs for the schema
+    Json,
+    // ANSI colored hex dump
+    Term,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Json => "json",
+            Format::Term => "txt",
+        }
+    }
+}
+
+pub struct ReportOptions {
+    pub format: Format,
+    // bytes per row in the hex and ASCII dumps, one of ALLOWED_WIDTHS
+    pub width: usize,
+    pub theme: Theme,
+    // embed the file and its ranges as data and only build the rows of the
+    // dump which are in view, for files too large for the regular report
+    pub lazy: bool,
+    // segments and sections larger than this many bytes start out collapsed
+    pub collapse_above: Option<usize>,
+}
+
+impl Default for ReportOptions {
+    fn default() -> ReportOptions {
+        ReportOptions {
+            format: Format::Html,
+            width: 16,
+            theme: Theme::Auto,
+            lazy: false,
+            collapse_above: None,
+        }
+    }
+}
+
 fn basename(path: &str) -> &str {
     // Wish expect() could use String. This is messy.
     match Path::new(path).file_name() {
@@ -20,8 +85,8 @@ fn stem(path: &str) -> &str {
     }
 }
 
-pub fn construct_filename(filename: &str) -> String {
-    stem(basename(filename)).to_string() + ".html"
+pub fn construct_filename(filename: &str, format: Format) -> String {
+    stem(basename(filename)).to_string() + "." + format.extension()
 }
 
 fn indent(level: usize, line: &str) -> String {
@@ -54,31 +119,67 @@ macro_rules! wrow {
 macro_rules! w {
     ($dst:expr, $indent_level:expr, $($arg:tt)*) => {
         wnonl!($dst, $indent_level, $( $arg )* );
-        writeln!($dst, "").unwrap();
+        writeln!($dst, "")?;
     }
 }
 
 macro_rules! wnonl {
     ($dst:expr, $indent_level:expr, $($arg:tt)*) => {
-        write!($dst, "{}", INDENT.repeat($indent_level)).unwrap();
-        write!($dst, $( $arg )* ).unwrap();
+        write!($dst, "{}", INDENT.repeat($indent_level))?;
+        write!($dst, $( $arg )* )?;
     }
 }
 
-fn generate_head(o: &mut String, elf: &ParsedElf) {
+fn generate_head(o: &mut dyn Write, elf: &ParsedElf, options: &ReportOptions) -> fmt::Result {
     let stylesheet: String = include_str!("style.css").indent_lines(3);
 
     w!(o, 1, "<head>");
     w!(o, 2, "<meta charset='utf-8'>");
-    w!(o, 2, "<meta name='viewport' content='width=900, initial-scale=1'>");
+    w!(
+        o,
+        2,
+        "<meta name='viewport' content='width=900, initial-scale=1'>"
+    );
     w!(o, 2, "<title>{}</title>", basename(&elf.filename));
     w!(o, 2, "<style>");
     wnonl!(o, 0, "{}", stylesheet);
+    // see the comment on #bytes in style.css
+    w!(o, 3, "#bytes {{ width: {}ch; }}", options.width * 3);
+    w!(o, 3, "#ascii {{ width: {}ch; }}", options.width);
+    wnonl!(o, 0, "{}", include_str!("themes/dark.css").indent_lines(3));
+
+    if let Theme::Custom(css) = &options.theme {
+        wnonl!(o, 0, "{}", css.indent_lines(3));
+    }
+
     w!(o, 2, "</style>");
+
+    add_theme_script(o, &options.theme)?;
     w!(o, 1, "</head>");
+
+    Ok(())
+}
+
+// runs in <head> so that the page doesn't flash with the light theme
+fn add_theme_script(o: &mut dyn Write, theme: &Theme) -> fmt::Result {
+    let default_theme = match theme {
+        Theme::Light => "light",
+        Theme::Dark => "dark",
+        Theme::Auto | Theme::Custom(_) => "auto",
+    };
+
+    w!(o, 2, "<script type='text/javascript'>");
+
+    w!(o, 3, "var defaultTheme = '{}';", default_theme);
+
+    wnonl!(o, 0, "{}", include_str!("js/theme.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
 }
 
-fn generate_svg_element(o: &mut String) {
+fn generate_svg_element(o: &mut dyn Write) -> fmt::Result {
     w!(o, 2, "<svg width='100%' height='100%'>");
 
     w!(o, 3, "<defs>");
@@ -98,9 +199,11 @@ fn generate_svg_element(o: &mut String) {
     w!(o, 0, "</g>");
 
     w!(o, 2, "</svg>");
+
+    Ok(())
 }
 
-fn generate_file_info_table(o: &mut String, elf: &ParsedElf) {
+fn generate_file_info_table(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
     w!(o, 4, "<table>");
 
     for (id, desc, value) in elf.information.iter() {
@@ -111,9 +214,32 @@ fn generate_file_info_table(o: &mut String, elf: &ParsedElf) {
     }
 
     w!(o, 4, "</table>");
+
+    Ok(())
+}
+
+fn generate_diagnostics_table(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    if elf.diagnostics.is_empty() {
+        return Ok(());
+    }
+
+    w!(o, 4, "<table id='diagnostics'>");
+
+    for diagnostic in elf.diagnostics.iter() {
+        let message = diagnostic.error.to_string();
+
+        wnonl!(o, 5, "<tr> ");
+        wnonl!(o, 0, "<td>{:#x}</td> ", diagnostic.error.offset());
+        wnonl!(o, 0, "<td>{}</td> ", html_escape_str(&message));
+        w!(o, 0, "</tr>");
+    }
+
+    w!(o, 4, "</table>");
+
+    Ok(())
 }
 
-fn generate_phdr_info_table(o: &mut String, phdr: &ParsedPhdr, idx: usize) {
+fn generate_phdr_info_table(o: &mut dyn Write, phdr: &ParsedPhdr, idx: usize) -> fmt::Result {
     let items = [
         ("Type", &ptype_to_string(phdr.ptype)),
         ("Flags", &phdr.flags),
@@ -131,15 +257,24 @@ fn generate_phdr_info_table(o: &mut String, phdr: &ParsedPhdr, idx: usize) {
     }
 
     w!(o, 5, "</table>");
+
+    Ok(())
 }
 
-fn generate_phdr_info_tables(o: &mut String, elf: &ParsedElf) {
+fn generate_phdr_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
     for (idx, phdr) in elf.phdrs.iter().enumerate() {
-        generate_phdr_info_table(o, &phdr, idx);
+        generate_phdr_info_table(o, phdr, idx)?;
     }
+
+    Ok(())
 }
 
-fn generate_shdr_info_table(o: &mut String, elf: &ParsedElf, shdr: &ParsedShdr, idx: usize) {
+fn generate_shdr_info_table(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    shdr: &ParsedShdr,
+    idx: usize,
+) -> fmt::Result {
     let items = [
         ("Name", elf.shnstrtab.get(shdr.name)),
         ("Type", &shtype_to_string(shdr.shtype)),
@@ -148,7 +283,7 @@ fn generate_shdr_info_table(o: &mut String, elf: &ParsedElf, shdr: &ParsedShdr,
         ("Offset in file", &format!("{}", shdr.file_offset)),
         ("Size in file", &format!("{}", shdr.size)),
         ("Linked section", &format!("{}", shdr.link)),
-        ("Extra info", &format!("{}", shdr.link)),
+        ("Extra info", &format!("{}", shdr.info)),
         ("Alignment", &format!("{:#x}", shdr.addralign)),
         ("Size of entries", &format!("{}", shdr.entsize)),
     ];
@@ -160,12 +295,16 @@ fn generate_shdr_info_table(o: &mut String, elf: &ParsedElf, shdr: &ParsedShdr,
     }
 
     w!(o, 5, "</table>");
+
+    Ok(())
 }
 
-fn generate_shdr_info_tables(o: &mut String, elf: &ParsedElf) {
+fn generate_shdr_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
     for (idx, shdr) in elf.shdrs.iter().enumerate() {
-        generate_shdr_info_table(o, elf, &shdr, idx);
+        generate_shdr_info_table(o, elf, shdr, idx)?;
     }
+
+    Ok(())
 }
 
 fn format_string_byte(byte: u8) -> String {
@@ -182,23 +321,25 @@ fn format_string_slice(slice: &[u8]) -> String {
         .fold(String::new(), |s, b| s + &format_string_byte(*b))
 }
 
-fn generate_note_data(o: &mut String, note: &Note) {
-    let name = if note.name.is_empty() {
-        String::new()
-    } else {
-        format_string_slice(&note.name[0..note.name.len() - 1])
-    };
+fn generate_note_data(o: &mut dyn Write, note: &Note) -> fmt::Result {
+    wrow!(o, 6, "Name", format_string_slice(note.owner()));
+
+    wrow!(o, 6, "Type", ntype_to_string(note.owner(), note.ntype));
 
-    wrow!(o, 6, "Name", name);
+    if !note.decoded.is_empty() {
+        for (label, value) in note.decoded.iter() {
+            wrow!(o, 6, html_escape_str(label), html_escape_str(value));
+        }
 
-    wrow!(o, 6, "Type", format!("{:#x}", note.ntype));
+        return Ok(());
+    }
 
     match note.ntype {
-        NT_GNU_BUILD_ID => {
+        NT_GNU_BUILD_ID if note.owner() == b"GNU" => {
             let mut hash = String::new();
 
             for byte in note.desc.iter() {
-                append_hex_byte(&mut hash, *byte);
+                append_hex_byte(&mut hash, *byte)?;
             }
 
             wrow!(o, 6, "Build ID", hash);
@@ -207,36 +348,78 @@ fn generate_note_data(o: &mut String, note: &Note) {
             wrow!(o, 6, "Desc", format_string_slice(&note.desc[..]));
         }
     }
+
+    Ok(())
+}
+
+// notes of a segment or a section, those in both are listed with each of them
+fn generate_notes_data<'a>(
+    o: &mut dyn Write,
+    notes: impl Iterator<Item = &'a Note>,
+) -> fmt::Result {
+    for (i, note) in notes.enumerate() {
+        if i != 0 {
+            w!(o, 6, "<tr> <td><br></td> </tr>");
+        }
+
+        generate_note_data(o, note)?;
+    }
+
+    Ok(())
 }
 
-fn generate_segment_info_table(o: &mut String, elf: &ParsedElf, phdr: &ParsedPhdr) {
+fn generate_segment_info_table(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    phdr: &ParsedPhdr,
+    idx: usize,
+) -> fmt::Result {
     match phdr.ptype {
         PT_INTERP => {
-            let interp_str = format_string_slice(
-                &elf.contents[phdr.file_offset..phdr.file_offset + phdr.file_size - 1],
-            );
+            let end = phdr.file_offset.saturating_add(phdr.file_size);
+            let interp = elf.contents.get(phdr.file_offset..end).unwrap_or(&[]);
+            let interp_str = format_string_slice(interp.strip_suffix(&[0]).unwrap_or(interp));
 
             wrow!(o, 6, "Interpreter", interp_str);
         }
         PT_NOTE => {
-            // this is really bad and made out of desperation.
-            // notes stored in elf.notes don't have to have 1-to-1
-            // correspondence with phdrs, yet here we are.
-            for i in 0..elf.notes.len() {
-                let note = &elf.notes[i];
+            let notes = elf.notes.iter();
 
-                generate_note_data(o, note);
+            generate_notes_data(o, notes.filter(|note| note.segment == Some(idx as u16)))?;
+        }
+        PT_DYNAMIC => {
+            generate_dynamic_data(o, elf)?;
+        }
+        PT_GNU_EH_FRAME => {
+            if let Some(hdr) = elf.eh_frame_hdr.as_ref() {
+                generate_eh_frame_hdr_data(o, elf, hdr)?;
+            }
 
-                if i != elf.notes.len() - 1 {
-                    w!(o, 6, "<tr> <td><br></td> </tr>");
-                }
+            if !has_eh_frame_section(elf) && !elf.frame_records.is_empty() {
+                w!(o, 6, "<tr><td><br></td></tr>");
+                generate_eh_frame_data(o, elf)?;
+            }
+        }
+        PT_LOAD => {
+            if let Some(file) = mapped_file(elf, phdr) {
+                wrow!(o, 6, "Mapped file", html_escape_str(&file.path));
+                wrow!(o, 6, "File offset", format!("{:#x}", file.offset));
             }
         }
         _ => {}
     }
+
+    Ok(())
+}
+
+// the file a LOAD segment of a core dump was mapped from, according to NT_FILE
+fn mapped_file<'a>(elf: &'a ParsedElf, phdr: &ParsedPhdr) -> Option<&'a MappedFile> {
+    elf.mapped_files
+        .iter()
+        .find(|file| file.start == phdr.vaddr as u64)
 }
 
-fn generate_strtab_data(o: &mut String, section: &[u8]) {
+fn generate_strtab_data(o: &mut dyn Write, section: &[u8]) -> fmt::Result {
     let mut curr_start = 0;
 
     w!(o, 6, "<tr>");
@@ -250,10 +433,10 @@ fn generate_strtab_data(o: &mut String, section: &[u8]) {
 
             let maybe = std::str::from_utf8(&section[curr_start..=end]);
 
-            if maybe.is_ok() && section[curr_start] != 0 {
-                let string = maybe.unwrap();
-
-                w!(o, 9, "{}", string);
+            if let Ok(string) = maybe {
+                if section[curr_start] != 0 {
+                    w!(o, 9, "{}", string);
+                }
             }
 
             curr_start = i + 1;
@@ -263,330 +446,2175 @@ fn generate_strtab_data(o: &mut String, section: &[u8]) {
     w!(o, 8, "</div>");
     w!(o, 7, "</td>");
     w!(o, 6, "</tr>");
+
+    Ok(())
 }
 
-fn generate_section_info_table(o: &mut String, elf: &ParsedElf, shdr: &ParsedShdr) {
-    let section = &elf.contents[shdr.file_offset..shdr.file_offset + shdr.size];
+// `id_prefix` must match the id of the entry ranges in the dump so that
+// rows can be linked with their bytes, see js/entries.js
+fn generate_entries_table(
+    o: &mut dyn Write,
+    id_prefix: &str,
+    columns: &[&str],
+    rows: Vec<Vec<String>>,
+) -> fmt::Result {
+    w!(o, 6, "<tr>");
+    w!(o, 7, "<td colspan='2'>");
+    w!(o, 8, "<div class='entries_wrapper'>");
+    w!(o, 9, "<table class='entries sortable'>");
 
-    match shdr.shtype {
-        SHT_STRTAB => {
-            generate_strtab_data(o, section);
+    wnonl!(o, 10, "<tr> ");
+    for column in columns.iter() {
+        wnonl!(o, 0, "<th>{}</th> ", column);
+    }
+    w!(o, 0, "</tr>");
+
+    for (i, row) in rows.iter().enumerate() {
+        wnonl!(o, 10, "<tr id='row_{}_{}'> ", id_prefix, i);
+        for cell in row.iter() {
+            wnonl!(o, 0, "<td>{}</td> ", cell);
         }
-        _ => {}
+        w!(o, 0, "</tr>");
     }
+
+    w!(o, 9, "</table>");
+    w!(o, 8, "</div>");
+    w!(o, 7, "</td>");
+    w!(o, 6, "</tr>");
+
+    Ok(())
 }
 
-// this is ugly
-fn has_segment_detail(ptype: u32) -> bool {
-    match ptype {
-        PT_INTERP => true,
-        PT_NOTE => true,
-        _ => false,
-    }
+fn generate_symtab_data(o: &mut dyn Write, elf: &ParsedElf, idx: usize) -> fmt::Result {
+    let symbols = match elf.symtabs.get(&(idx as u16)) {
+        Some(symbols) => symbols,
+        None => return Ok(()),
+    };
+
+    let columns = ["Num", "Value", "Size", "Type", "Bind", "Vis", "Ndx", "Name"];
+    let rows = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, sym)| {
+            vec![
+                format!("{}", i),
+                format!("{:#x}", sym.value),
+                format!("{}", sym.size),
+                sttype_to_string(sym.stype),
+                stbind_to_string(sym.binding),
+                stvis_to_string(sym.visibility),
+                shndx_to_string(sym.shndx),
+                html_escape_str(&versioned_name(elf, idx, i, &sym.name)),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, &format!("sym{}", idx), &columns, rows)?;
+
+    Ok(())
 }
 
-fn has_section_detail(ptype: u32) -> bool {
-    match ptype {
-        SHT_STRTAB => true,
-        _ => false,
+// e.g. memcpy@GLIBC_2.14 for symbols of a table with a .gnu.version section
+fn versioned_name(elf: &ParsedElf, symtab: usize, sym: usize, name: &str) -> String {
+    match elf.symbol_version(symtab as u16, sym) {
+        Some(version) => format!("{}{}", name, version),
+        None => String::from(name),
     }
 }
 
-fn generate_segment_info_tables(o: &mut String, elf: &ParsedElf) {
-    for (idx, phdr) in elf.phdrs.iter().enumerate() {
-        w!(o, 5, "<table class='conceal' id='info_segment{}'>", idx);
-        wrow!(o, 6, "Segment type", &ptype_to_string(phdr.ptype));
-        wrow!(o, 6, "Size in file", phdr.file_size);
-        wrow!(o, 6, "Size in memory", phdr.memsz);
-
-        if has_segment_detail(phdr.ptype) {
-            w!(o, 6, "<tr><td><br></td></tr>");
-            generate_segment_info_table(o, elf, &phdr);
-        }
+fn generate_versym_data(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    shdr: &ParsedShdr,
+    idx: usize,
+) -> fmt::Result {
+    let versyms = match elf.versyms.get(&(idx as u16)) {
+        Some(versyms) => versyms,
+        None => return Ok(()),
+    };
 
-        w!(o, 5, "</table>");
-    }
+    let symbols = elf.symtabs.get(&(shdr.link as u16));
+    let columns = ["Num", "Ndx", "Symbol name"];
+    let rows = versyms
+        .iter()
+        .enumerate()
+        .map(|(i, versym)| {
+            let name = symbols
+                .and_then(|symbols| symbols.get(i))
+                .map_or("", |sym| sym.name.as_str());
+
+            vec![
+                format!("{}", i),
+                versym_to_string(*versym),
+                html_escape_str(&versioned_name(elf, shdr.link, i, name)),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, &format!("ver{}", idx), &columns, rows)?;
+
+    Ok(())
 }
 
-fn generate_section_info_tables(o: &mut String, elf: &ParsedElf) {
-    for (idx, shdr) in elf.shdrs.iter().enumerate() {
-        w!(o, 5, "<table class='conceal' id='info_section{}'>", idx);
-        wrow!(o, 6, "Section type", &shtype_to_string(shdr.shtype));
-        wrow!(o, 6, "Size", shdr.size);
+// Verneed and Verdef entries followed by their auxiliary ones
+fn generate_version_data(o: &mut dyn Write, elf: &ParsedElf, idx: usize) -> fmt::Result {
+    let records = match elf.versions.get(&(idx as u16)) {
+        Some(records) => records,
+        None => return Ok(()),
+    };
 
-        if has_section_detail(shdr.shtype) {
-            w!(o, 6, "<tr><td><br></td></tr>");
-            generate_section_info_table(o, elf, &shdr);
+    let columns = ["Num", "Entry", "Ndx", "Name", "Flags", "Hash"];
+    let rows = records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let (entry, ndx, name, flags, hash) = match record {
+                VersionRecord::Need { file, .. } => ("Verneed", None, file, None, None),
+                VersionRecord::NeedAux {
+                    hash,
+                    flags,
+                    index,
+                    name,
+                } => ("Vernaux", Some(*index), name, Some(*flags), Some(*hash)),
+                VersionRecord::Def {
+                    flags,
+                    index,
+                    hash,
+                    name,
+                    ..
+                } => ("Verdef", Some(*index), name, Some(*flags), Some(*hash)),
+                VersionRecord::DefAux { name } => ("Verdaux", None, name, None, None),
+            };
+
+            vec![
+                format!("{}", i),
+                String::from(entry),
+                ndx.map_or(String::new(), |ndx| ndx.to_string()),
+                html_escape_str(name),
+                flags.map_or(String::new(), verflags_to_string),
+                hash.map_or(String::new(), |hash| format!("{:#010x}", hash)),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, &format!("ver{}", idx), &columns, rows)?;
+
+    Ok(())
+}
+
+// section symbols have no name of their own, refer to them by the section's one
+fn symbol_name<'a>(elf: &'a ParsedElf, sym: &'a Symbol) -> &'a str {
+    if sym.stype == STT_SECTION {
+        if let Some(shdr) = elf.shdrs.get(sym.shndx as usize) {
+            return elf.shnstrtab.get(shdr.name);
         }
+    }
 
-        w!(o, 5, "</table>");
+    &sym.name
+}
+
+fn format_addend(addend: i64) -> String {
+    if addend < 0 {
+        format!("-{:#x}", addend.unsigned_abs())
+    } else {
+        format!("{:#x}", addend)
     }
 }
 
-fn generate_sticky_info_table(o: &mut String, elf: &ParsedElf) {
-    w!(o, 2, "<table id='sticky_table' cellspacing='0'>");
-    w!(o, 3, "<tr>");
+fn generate_reltab_data(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    shdr: &ParsedShdr,
+    idx: usize,
+) -> fmt::Result {
+    let relocations = match elf.relocations.get(&(idx as u16)) {
+        Some(relocations) => relocations,
+        None => return Ok(()),
+    };
 
-    w!(o, 4, "<td id='desc'></td>");
+    let symbols = elf.symtabs.get(&(shdr.link as u16));
+    let with_addend = shdr.shtype == SHT_RELA;
 
-    w!(o, 4, "<td id='struct_infotables'>");
-    generate_phdr_info_tables(o, elf);
+    let mut columns = vec!["Num", "Offset", "Type", "Sym", "Symbol name"];
 
-    generate_shdr_info_tables(o, elf);
-    w!(o, 4, "</td>");
+    if with_addend {
+        columns.push("Addend");
+    }
 
-    w!(o, 4, "<td id='data_infotables'>");
-    generate_segment_info_tables(o, elf);
+    let rows = relocations
+        .iter()
+        .enumerate()
+        .map(|(i, rel)| {
+            let name = symbols
+                .and_then(|symbols| symbols.get(rel.sym as usize))
+                .map_or("", |sym| symbol_name(elf, sym));
+            let name = versioned_name(elf, shdr.link, rel.sym as usize, name);
+
+            let mut row = vec![
+                format!("{}", i),
+                format!("{:#x}", rel.offset),
+                reltype_to_string(elf.machine, rel.rtype),
+                format!("{}", rel.sym),
+                html_escape_str(&name),
+            ];
+
+            if let Some(addend) = rel.addend {
+                row.push(format_addend(addend));
+            }
 
-    generate_section_info_tables(o, elf);
-    w!(o, 4, "</td>");
+            row
+        })
+        .collect();
 
-    w!(o, 3, "</tr>");
-    w!(o, 2, "</table>");
+    generate_entries_table(o, &format!("rel{}", idx), &columns, rows)?;
+
+    Ok(())
 }
 
-fn add_highlight_script(o: &mut String) {
-    let ids = [
-        "class",
-        "data",
-        "abi",
-        "abi_ver",
-        "e_type",
-        "e_machine",
-        "e_entry",
-        "e_phoff",
-        "e_shoff",
-        "e_flags",
-        "e_ehsize",
-        "e_phentsize",
-        "e_phnum",
-        "e_shentsize",
-        "e_shnum",
-        "e_shstrndx",
-    ];
+fn format_dynamic_address(elf: &ParsedElf, addr: u64) -> String {
+    match elf.vaddr_to_offset(addr as usize) {
+        Some(offset) => format!("{:#x} (file offset {:#x})", addr, offset),
+        None => format!("{:#x}", addr),
+    }
+}
 
-    w!(o, 2, "<script type='text/javascript'>");
+fn format_dynamic_value(elf: &ParsedElf, strtab: &StrTab, entry: &DynamicEntry) -> String {
+    match entry.tag {
+        DT_NEEDED | DT_SONAME | DT_RPATH | DT_RUNPATH | DT_AUXILIARY | DT_FILTER | DT_CONFIG
+        | DT_DEPAUDIT | DT_AUDIT => html_escape_str(strtab.get(entry.val as usize)),
+        DT_FLAGS => dflags_to_string(entry.val),
+        DT_FLAGS_1 => dflags1_to_string(entry.val),
+        DT_PLTREL => match entry.val as i64 {
+            DT_REL => String::from("REL"),
+            DT_RELA => String::from("RELA"),
+            other => format!("{:#x}", other),
+        },
+        DT_PLTGOT | DT_HASH | DT_STRTAB | DT_SYMTAB | DT_RELA | DT_INIT | DT_FINI | DT_REL
+        | DT_JMPREL | DT_INIT_ARRAY | DT_FINI_ARRAY | DT_PREINIT_ARRAY | DT_RELR | DT_GNU_HASH
+        | DT_TLSDESC_PLT | DT_TLSDESC_GOT | DT_GNU_CONFLICT | DT_GNU_LIBLIST | DT_PLTPAD
+        | DT_MOVETAB | DT_SYMINFO | DT_VERSYM | DT_VERDEF | DT_VERNEED => {
+            format_dynamic_address(elf, entry.val)
+        }
+        DT_PLTRELSZ | DT_RELASZ | DT_RELAENT | DT_STRSZ | DT_SYMENT | DT_RELSZ | DT_RELENT
+        | DT_INIT_ARRAYSZ | DT_FINI_ARRAYSZ | DT_PREINIT_ARRAYSZ | DT_RELRSZ | DT_RELRENT
+        | DT_GNU_CONFLICTSZ | DT_GNU_LIBLISTSZ | DT_PLTPADSZ | DT_MOVEENT | DT_MOVESZ
+        | DT_SYMINSZ | DT_SYMINENT => format!("{} (bytes)", entry.val),
+        DT_RELACOUNT | DT_RELCOUNT | DT_VERDEFNUM | DT_VERNEEDNUM => format!("{}", entry.val),
+        _ => format!("{:#x}", entry.val),
+    }
+}
 
-    wnonl!(o, 0, "{}", include_str!("js/highlight.js").indent_lines(3));
+// longer chains are counted together in the histogram
+const HISTOGRAM_MAX: usize = 8;
 
-    for id in ids.iter() {
-        w!(o, 3, "highlightIds('{}', 'info_{}')", id, id);
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
     }
-
-    w!(o, 2, "</script>");
 }
 
-fn add_description_script(o: &mut String) {
-    w!(o, 2, "<script type='text/javascript'>");
+fn generate_hash_data(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    shdr: &ParsedShdr,
+    idx: usize,
+) -> fmt::Result {
+    let table = match elf.hash_tables.get(&(idx as u16)) {
+        Some(table) => table,
+        None => return Ok(()),
+    };
 
-    wnonl!(
+    let chains: Vec<Vec<usize>> = (0..table.bucket_count())
+        .map(|bucket| table.chain(bucket))
+        .collect();
+    let buckets = chains.len();
+    let symbols: usize = chains.iter().map(Vec::len).sum();
+    let empty = chains.iter().filter(|chain| chain.is_empty()).count();
+    let longest = chains.iter().map(Vec::len).max().unwrap_or(0);
+
+    wrow!(o, 6, "Buckets", buckets);
+    wrow!(o, 6, "Symbols", symbols);
+    wrow!(
         o,
-        0,
-        "{}",
-        include_str!("js/description.js").indent_lines(3)
+        6,
+        "Empty buckets",
+        format!("{} ({:.1}%)", empty, percent(empty, buckets))
     );
+    wrow!(o, 6, "Longest chain", longest);
 
-    w!(o, 2, "</script>");
-}
+    if buckets > empty {
+        wrow!(
+            o,
+            6,
+            "Average chain",
+            format!("{:.2}", symbols as f64 / (buckets - empty) as f64)
+        );
+    }
 
-fn add_conceal_script(o: &mut String) {
-    w!(o, 2, "<script type='text/javascript'>");
+    if let HashTable::Gnu {
+        symoffset,
+        bloom_shift,
+        bloom_bits,
+        bloom,
+        ..
+    } = table
+    {
+        let bits = bloom.len() * *bloom_bits as usize;
+        let set: usize = bloom.iter().map(|word| word.count_ones() as usize).sum();
+
+        wrow!(o, 6, "Symbol offset", symoffset);
+        wrow!(o, 6, "Bloom words", bloom.len());
+        wrow!(o, 6, "Bloom shift", bloom_shift);
+        wrow!(
+            o,
+            6,
+            "Bloom bits set",
+            format!("{} of {} ({:.1}%)", set, bits, percent(set, bits))
+        );
+    }
 
-    wnonl!(o, 0, "{}", include_str!("js/conceal.js").indent_lines(3));
+    w!(o, 6, "<tr><td><br></td></tr>");
 
-    w!(o, 2, "</script>");
-}
+    for len in 0..=longest.min(HISTOGRAM_MAX) {
+        let count = chains
+            .iter()
+            .filter(|chain| chain.len() == len || (len == HISTOGRAM_MAX && chain.len() > len))
+            .count();
+        let more = if len == HISTOGRAM_MAX { "+" } else { "" };
 
-fn add_offsets_script(o: &mut String, elf: &ParsedElf) {
-    w!(o, 2, "<script type='text/javascript'>");
+        wrow!(
+            o,
+            6,
+            format!("Chain length {}{}", len, more),
+            format!("{} ({:.1}%)", count, percent(count, buckets))
+        );
+    }
 
-    w!(o, 3, "let fileLen = {}", elf.file_size);
+    // see js/hash.js
+    w!(o, 6, "<tr><td><br></td></tr>");
+    w!(
+        o,
+        6,
+        "<tr> <td>Look up:</td> <td><input type='text' class='hash_lookup' \
+         data-section='{}' placeholder='Symbol name'></td> </tr>",
+        idx
+    );
+    w!(
+        o,
+        6,
+        "<tr> <td></td> <td id='hash_result{}'></td> </tr>",
+        idx
+    );
 
-    wnonl!(o, 0, "{}", include_str!("js/offsets.js").indent_lines(3));
+    let symtab = elf.symtabs.get(&(shdr.link as u16));
+    let columns = ["Bucket", "Length", "Symbols"];
+    let rows = chains
+        .iter()
+        .enumerate()
+        .map(|(bucket, chain)| {
+            let names: Vec<String> = chain
+                .iter()
+                .map(|sym| {
+                    let name = symtab
+                        .and_then(|symbols| symbols.get(*sym))
+                        .map_or("", |sym| sym.name.as_str());
+
+                    html_escape_str(name)
+                })
+                .collect();
+
+            vec![
+                format!("{}", bucket),
+                format!("{}", chain.len()),
+                names.join(" "),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, &format!("hash{}", idx), &columns, rows)?;
+
+    Ok(())
+}
 
-    w!(o, 3, "populateOffsets(16)");
+fn die_label(die: &Die) -> String {
+    let name = die.attributes.iter().find(|attr| attr.name == DW_AT_NAME);
 
-    w!(o, 2, "</script>");
+    match name {
+        Some(name) => format!(
+            "{} {}",
+            dwarf_tag_to_string(die.tag),
+            html_escape_str(&name.value)
+        ),
+        None => dwarf_tag_to_string(die.tag),
+    }
 }
 
-fn add_arrows_script(o: &mut String, elf: &ParsedElf) {
-    w!(o, 2, "<script type='text/javascript'>");
+// each DIE is a <details> element holding its attributes and children, its
+// summary and the attributes have the row ids linked to bytes by js/entries.js
+fn generate_unit_tree(
+    o: &mut dyn Write,
+    dies_by_offset: &HashMap<usize, &Die>,
+    unit: &DwarfUnit,
+    idx: usize,
+) -> fmt::Result {
+    w!(
+        o,
+        9,
+        "<details><summary id='row_cu{}'>{:#x}: {} unit, version {}</summary>",
+        idx,
+        unit.offset,
+        dwarf_ut_to_string(unit.unit_type),
+        unit.version
+    );
 
-    wnonl!(o, 0, "{}", include_str!("js/arrows.js").indent_lines(3));
+    let mut open = 0;
 
-    w!(o, 3, "connect('#e_phoff', '#bin_phdr0');");
-    w!(o, 3, "connect('#e_shoff', '#bin_shdr0');");
+    for (i, die) in unit.dies.iter().enumerate() {
+        while open > die.depth {
+            w!(o, 10 + open, "</details>");
+            open -= 1;
+        }
 
-    for i in 0..elf.phdrs.len() {
         w!(
             o,
-            3,
-            "connect('#bin_phdr{} > .p_offset', '#bin_segment{}');",
+            10 + open,
+            "<details><summary id='row_die{}_{}'>{:#x}: {}</summary>",
+            idx,
             i,
-            i
+            die.offset,
+            die_label(die)
         );
-    }
 
-    for i in 0..elf.shdrs.len() {
-        w!(
-            o,
-            3,
-            "connect('#bin_shdr{} > .sh_offset', '#bin_section{}');",
-            i,
-            i
-        );
-    }
+        for (a, attr) in die.attributes.iter().enumerate() {
+            let target = attr
+                .reference
+                .and_then(|offset| dies_by_offset.get(&offset));
+            let target = match target {
+                Some(die) => format!(" {}", die_label(die)),
+                None => String::new(),
+            };
+
+            w!(
+                o,
+                11 + open,
+                "<div id='row_die{}_{}_{}'>{} ({}): {}{}</div>",
+                idx,
+                i,
+                a,
+                dwarf_at_to_string(attr.name),
+                dwarf_form_to_string(attr.form),
+                html_escape_str(&attr.value),
+                target
+            );
+        }
 
-    w!(o, 2, "</script>");
-}
+        if die.children {
+            open += 1;
+        } else {
+            w!(o, 10 + open, "</details>");
+        }
+    }
 
-#[allow(dead_code)]
-fn add_collapsible_script(o: &mut String) {
-    w!(o, 2, "<script type='text/javascript'>");
+    while open > 0 {
+        w!(o, 9 + open, "</details>");
+        open -= 1;
+    }
 
-    wnonl!(o, 0, "{}", include_str!("js/collapse.js").indent_lines(3));
+    w!(o, 9, "</details>");
 
-    w!(o, 2, "</script>");
+    Ok(())
 }
 
-fn add_scripts(o: &mut String, elf: &ParsedElf) {
-    add_highlight_script(o);
+fn generate_debug_info_data(o: &mut dyn Write, elf: &ParsedElf, idx: usize) -> fmt::Result {
+    // ids are indices into dwarf_units like those of the ranges
+    let units: Vec<(usize, &DwarfUnit)> = elf
+        .dwarf_units
+        .iter()
+        .enumerate()
+        .filter(|(_, unit)| unit.section == idx as u16)
+        .collect();
+    // references can point at DIEs of other units of the section
+    let dies_by_offset: HashMap<usize, &Die> = units
+        .iter()
+        .flat_map(|(_, unit)| unit.dies.iter())
+        .map(|die| (die.offset, die))
+        .collect();
 
-    add_description_script(o);
+    wrow!(o, 6, "Units", units.len());
+    wrow!(o, 6, "DIEs", dies_by_offset.len());
+    w!(o, 6, "<tr><td><br></td></tr>");
 
-    add_conceal_script(o);
+    w!(o, 6, "<tr>");
+    w!(o, 7, "<td colspan='2'>");
+    w!(o, 8, "<div class='entries_wrapper dwarf'>");
 
-    // disabled while working on section headers because it doesn't work for nested elements
-    // add_collapsible_script(o);
+    for (unit_idx, unit) in units.iter() {
+        generate_unit_tree(o, &dies_by_offset, unit, *unit_idx)?;
+    }
 
-    add_offsets_script(o, elf);
+    w!(o, 8, "</div>");
+    w!(o, 7, "</td>");
+    w!(o, 6, "</tr>");
 
-    add_arrows_script(o, elf);
+    Ok(())
 }
 
-fn format_magic(byte: u8) -> String {
-    if byte.is_ascii_graphic() {
-        format!("&nbsp;{}", char::from(byte))
-    } else {
-        format!("{:02x}", byte)
+// e.g. m.c:4, `file` is a file number of the program's rows
+fn source_location(program: &LineProgram, file: u64, line: u64) -> String {
+    match program.file_name(file) {
+        Some(name) => format!("{}:{}", name, line),
+        None => format!("file {}:{}", file, line),
     }
 }
 
-fn digit_to_hex(digit: u8) -> char {
-    [
-        '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
-    ][digit as usize]
-}
+fn generate_line_program_tree(o: &mut dyn Write, program: &LineProgram, idx: usize) -> fmt::Result {
+    let name = program.file_name(if program.version >= 5 { 0 } else { 1 });
 
-fn append_hex_byte(s: &mut String, byte: u8) {
-    if byte < 0x10 {
-        s.push('0');
+    w!(
+        o,
+        9,
+        "<details><summary id='row_lp{}'>{:#x}: {}, version {}, {} rows</summary>",
+        idx,
+        program.offset,
+        html_escape_str(name.unwrap_or("line-number program")),
+        program.version,
+        program.rows.len()
+    );
 
-        s.push(digit_to_hex(byte));
-    } else {
-        let trailing_digit = byte % 16;
-        let leading_digit = byte / 16;
+    for (i, op) in program.ops.iter().enumerate() {
+        let row = op.row.and_then(|row| program.rows.get(row));
+        let row = match row {
+            Some(row) if row.end_sequence => format!(" &#x2192; {:#x} end", row.address),
+            Some(row) => format!(
+                " &#x2192; {:#x} {}",
+                row.address,
+                html_escape_str(&source_location(program, row.file, row.line))
+            ),
+            None => String::new(),
+        };
+
+        let operands = if op.operands.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", html_escape_str(&op.operands))
+        };
+
+        w!(
+            o,
+            10,
+            "<div id='row_lop{}_{}'>{:#x}: {}{}{}</div>",
+            idx,
+            i,
+            op.offset,
+            op.name,
+            operands,
+            row
+        );
+    }
+
+    w!(o, 9, "</details>");
+
+    Ok(())
+}
+
+fn generate_debug_line_data(o: &mut dyn Write, elf: &ParsedElf, idx: usize) -> fmt::Result {
+    // ids are indices into line_programs like those of the ranges
+    let programs: Vec<(usize, &LineProgram)> = elf
+        .line_programs
+        .iter()
+        .enumerate()
+        .filter(|(_, program)| program.section == idx as u16)
+        .collect();
+    let rows: usize = programs.iter().map(|(_, program)| program.rows.len()).sum();
+
+    wrow!(o, 6, "Programs", programs.len());
+    wrow!(o, 6, "Rows", rows);
+    w!(o, 6, "<tr><td><br></td></tr>");
+
+    w!(o, 6, "<tr>");
+    w!(o, 7, "<td colspan='2'>");
+    w!(o, 8, "<div class='entries_wrapper dwarf'>");
+
+    for (program_idx, program) in programs.iter() {
+        generate_line_program_tree(o, program, *program_idx)?;
+    }
+
+    w!(o, 8, "</div>");
+    w!(o, 7, "</td>");
+    w!(o, 6, "</tr>");
+    w!(o, 6, "<tr><td><br></td></tr>");
+
+    let columns = ["Address", "File", "Line", "Column", "Stmt"];
+    let rows = programs
+        .iter()
+        .flat_map(|(_, program)| program.rows.iter().map(move |row| (program, row)))
+        .map(|(program, row)| {
+            let file = program.file_name(row.file).unwrap_or("");
+            let line = if row.end_sequence {
+                String::from("-")
+            } else {
+                format!("{}", row.line)
+            };
+
+            vec![
+                format!("{:#x}", row.address),
+                html_escape_str(file),
+                line,
+                format!("{}", row.column),
+                String::from(if row.is_stmt { "x" } else { "" }),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, &format!("line{}", idx), &columns, rows)?;
+
+    Ok(())
+}
+
+fn has_line_programs(elf: &ParsedElf, idx: usize) -> bool {
+    elf.line_programs
+        .iter()
+        .any(|program| program.section == idx as u16)
+}
+
+fn has_dwarf_units(elf: &ParsedElf, idx: usize) -> bool {
+    elf.dwarf_units
+        .iter()
+        .any(|unit| unit.section == idx as u16)
+}
+
+// names of the functions FDEs cover, by address
+fn function_names<'a>(elf: &'a ParsedElf) -> HashMap<u64, &'a str> {
+    elf.symtabs
+        .values()
+        .flatten()
+        .filter(|sym| sym.stype == STT_FUNC && !sym.name.is_empty())
+        .map(|sym| (sym.value as u64, sym.name.as_str()))
+        .collect()
+}
+
+fn frame_record_label(
+    elf: &ParsedElf,
+    functions: &HashMap<u64, &str>,
+    record: &FrameRecord,
+) -> String {
+    match &record.entry {
+        FrameEntry::Cie(cie) => format!(
+            "CIE \"{}\", {} ops",
+            html_escape_str(&cie.augmentation),
+            record.instructions.len()
+        ),
+        FrameEntry::Fde(fde) => {
+            let cie = fde
+                .cie
+                .and_then(|cie| elf.frame_records.get(cie))
+                .map_or(String::from("none"), |cie| format!("{:#x}", cie.offset));
+            let function = match functions.get(&fde.pc_begin) {
+                Some(name) => format!(" {}", html_escape_str(name)),
+                None => String::new(),
+            };
+
+            format!(
+                "FDE {:#x}..{:#x}{}, CIE {}",
+                fde.pc_begin,
+                fde.pc_begin.wrapping_add(fde.pc_range),
+                function,
+                cie
+            )
+        }
+    }
+}
+
+fn generate_frame_record_tree(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    functions: &HashMap<u64, &str>,
+    record: &FrameRecord,
+    idx: usize,
+) -> fmt::Result {
+    w!(
+        o,
+        9,
+        "<details><summary id='row_cfi{}'>{:#x}: {}</summary>",
+        idx,
+        record.offset,
+        frame_record_label(elf, functions, record)
+    );
+
+    for (i, op) in record.instructions.iter().enumerate() {
+        let operands = if op.operands.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", html_escape_str(&op.operands))
+        };
+
+        w!(
+            o,
+            10,
+            "<div id='row_cfa{}_{}'>{:#x}: {}{}</div>",
+            idx,
+            i,
+            op.offset,
+            op.name,
+            operands
+        );
+    }
+
+    w!(o, 9, "</details>");
+
+    Ok(())
+}
+
+fn generate_eh_frame_data(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    let functions = function_names(elf);
+    let fdes = elf
+        .frame_records
+        .iter()
+        .filter(|record| matches!(record.entry, FrameEntry::Fde(_)))
+        .count();
+
+    wrow!(o, 6, "CIEs", elf.frame_records.len() - fdes);
+    wrow!(o, 6, "FDEs", fdes);
+    w!(o, 6, "<tr><td><br></td></tr>");
+
+    w!(o, 6, "<tr>");
+    w!(o, 7, "<td colspan='2'>");
+    w!(o, 8, "<div class='entries_wrapper dwarf'>");
+
+    for (idx, record) in elf.frame_records.iter().enumerate() {
+        generate_frame_record_tree(o, elf, &functions, record, idx)?;
+    }
+
+    w!(o, 8, "</div>");
+    w!(o, 7, "</td>");
+    w!(o, 6, "</tr>");
+
+    Ok(())
+}
+
+fn format_eh_pointer(value: Option<u64>, encoding: u8) -> String {
+    match value {
+        Some(value) => format!("{:#x} ({})", value, eh_pe_to_string(encoding)),
+        None => eh_pe_to_string(encoding),
+    }
+}
+
+fn generate_eh_frame_hdr_data(o: &mut dyn Write, elf: &ParsedElf, hdr: &EhFrameHdr) -> fmt::Result {
+    let functions = function_names(elf);
+
+    wrow!(o, 6, "Version", hdr.version);
+    wrow!(
+        o,
+        6,
+        ".eh_frame",
+        format_eh_pointer(hdr.eh_frame_ptr, hdr.eh_frame_ptr_encoding)
+    );
+    wrow!(
+        o,
+        6,
+        "FDE count",
+        format_eh_pointer(hdr.fde_count, hdr.fde_count_encoding)
+    );
+    wrow!(o, 6, "Table encoding", eh_pe_to_string(hdr.table_encoding));
+
+    if hdr.table.is_empty() {
+        return Ok(());
+    }
+
+    w!(o, 6, "<tr><td><br></td></tr>");
+
+    let columns = ["Num", "Initial location", "FDE", "Function"];
+    let rows = hdr
+        .table
+        .iter()
+        .enumerate()
+        .map(|(i, (initial_location, fde))| {
+            let function = functions.get(initial_location).copied().unwrap_or("");
+
+            vec![
+                format!("{}", i),
+                format!("{:#x}", initial_location),
+                format!("{:#x}", fde),
+                html_escape_str(function),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, "ehhdr", &columns, rows)?;
+
+    Ok(())
+}
+
+// the first section of that name, the one which is parsed
+fn first_section_named(elf: &ParsedElf, name: &str) -> Option<usize> {
+    elf.shdrs
+        .iter()
+        .position(|shdr| elf.shnstrtab.get(shdr.name) == name)
+}
+
+// .eh_frame and .eh_frame_hdr are shown with their sections, or with
+// PT_GNU_EH_FRAME when there are no section headers
+fn is_eh_frame_section(elf: &ParsedElf, idx: usize) -> bool {
+    !elf.frame_records.is_empty() && first_section_named(elf, ".eh_frame") == Some(idx)
+}
+
+fn has_eh_frame_section(elf: &ParsedElf) -> bool {
+    first_section_named(elf, ".eh_frame").is_some()
+}
+
+// with both, the header table is only shown with the segment so that its row
+// ids stay unique
+fn is_eh_frame_hdr_section(elf: &ParsedElf, idx: usize) -> bool {
+    elf.eh_frame_hdr.is_some()
+        && first_section_named(elf, ".eh_frame_hdr") == Some(idx)
+        && !has_eh_frame_hdr_segment(elf)
+}
+
+fn has_eh_frame_hdr_segment(elf: &ParsedElf) -> bool {
+    elf.phdrs.iter().any(|phdr| phdr.ptype == PT_GNU_EH_FRAME)
+}
+
+fn generate_dynamic_data(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    let strtab = elf.dynamic_strtab();
+
+    let columns = ["Num", "Tag", "Name", "Value"];
+    let rows = elf
+        .dynamic
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            vec![
+                format!("{}", i),
+                format!("{:#x}", entry.tag),
+                dtag_to_string(entry.tag),
+                format_dynamic_value(elf, &strtab, entry),
+            ]
+        })
+        .collect();
+
+    generate_entries_table(o, "dyn", &columns, rows)?;
+
+    Ok(())
+}
+
+// the table is shown with PT_DYNAMIC if there is one, so that row ids stay unique
+fn has_dynamic_segment(elf: &ParsedElf) -> bool {
+    elf.phdrs.iter().any(|phdr| phdr.ptype == PT_DYNAMIC)
+}
+
+fn generate_section_info_table(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    shdr: &ParsedShdr,
+    idx: usize,
+) -> fmt::Result {
+    let section = elf.section_contents(idx);
+    let section = section.as_deref().unwrap_or(&[]);
+
+    match shdr.shtype {
+        SHT_STRTAB => {
+            generate_strtab_data(o, section)?;
+        }
+        SHT_SYMTAB | SHT_DYNSYM => {
+            generate_symtab_data(o, elf, idx)?;
+        }
+        SHT_REL | SHT_RELA => {
+            generate_reltab_data(o, elf, shdr, idx)?;
+        }
+        SHT_DYNAMIC if !has_dynamic_segment(elf) => {
+            generate_dynamic_data(o, elf)?;
+        }
+        SHT_HASH | SHT_GNU_HASH => {
+            generate_hash_data(o, elf, shdr, idx)?;
+        }
+        SHT_VER_SYM => {
+            generate_versym_data(o, elf, shdr, idx)?;
+        }
+        SHT_VER_NEED | SHT_VER_DEF => {
+            generate_version_data(o, elf, idx)?;
+        }
+        SHT_NOTE => {
+            let notes = elf.notes.iter();
+
+            generate_notes_data(o, notes.filter(|note| note.section == Some(idx as u16)))?;
+        }
+        _ if has_dwarf_units(elf, idx) => {
+            generate_debug_info_data(o, elf, idx)?;
+        }
+        _ if has_line_programs(elf, idx) => {
+            generate_debug_line_data(o, elf, idx)?;
+        }
+        _ if is_eh_frame_section(elf, idx) => {
+            generate_eh_frame_data(o, elf)?;
+        }
+        _ if is_eh_frame_hdr_section(elf, idx) => {
+            if let Some(hdr) = elf.eh_frame_hdr.as_ref() {
+                generate_eh_frame_hdr_data(o, elf, hdr)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// this is ugly
+fn has_segment_detail(elf: &ParsedElf, phdr: &ParsedPhdr) -> bool {
+    match phdr.ptype {
+        PT_INTERP | PT_NOTE | PT_DYNAMIC => true,
+        PT_GNU_EH_FRAME => elf.eh_frame_hdr.is_some() || !elf.frame_records.is_empty(),
+        PT_LOAD => mapped_file(elf, phdr).is_some(),
+        _ => false,
+    }
+}
+
+fn has_section_detail(elf: &ParsedElf, shdr: &ParsedShdr, idx: usize) -> bool {
+    match shdr.shtype {
+        SHT_STRTAB | SHT_SYMTAB | SHT_DYNSYM | SHT_REL | SHT_RELA | SHT_NOTE | SHT_HASH
+        | SHT_GNU_HASH | SHT_VER_SYM | SHT_VER_NEED | SHT_VER_DEF => true,
+        // same condition as generate_section_info_table()
+        SHT_DYNAMIC => !has_dynamic_segment(elf),
+        _ => {
+            has_dwarf_units(elf, idx)
+                || has_line_programs(elf, idx)
+                || is_eh_frame_section(elf, idx)
+                || is_eh_frame_hdr_section(elf, idx)
+        }
+    }
+}
+
+fn generate_segment_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (idx, phdr) in elf.phdrs.iter().enumerate() {
+        w!(o, 5, "<table class='conceal' id='info_segment{}'>", idx);
+        wrow!(o, 6, "Segment type", &ptype_to_string(phdr.ptype));
+        wrow!(o, 6, "Size in file", phdr.file_size);
+        wrow!(o, 6, "Size in memory", phdr.memsz);
+
+        if has_segment_detail(elf, phdr) {
+            w!(o, 6, "<tr><td><br></td></tr>");
+            generate_segment_info_table(o, elf, phdr, idx)?;
+        }
+
+        w!(o, 5, "</table>");
+    }
+
+    Ok(())
+}
+
+fn generate_compression_rows(o: &mut dyn Write, compressed: &CompressedSection) -> fmt::Result {
+    let compression = elfcompress_to_string(compressed.compression);
+
+    if compressed.legacy {
+        wrow!(o, 6, "Compression", format!("{} (.zdebug)", compression));
+    } else {
+        wrow!(o, 6, "Compression", compression);
+    }
+
+    wrow!(o, 6, "Decompressed size", compressed.size);
+
+    if !compressed.legacy {
+        wrow!(
+            o,
+            6,
+            "Decompressed alignment",
+            format!("{:#x}", compressed.alignment)
+        );
+    }
+
+    Ok(())
+}
+
+// sections like .debug_info can be much larger than the rest of the report,
+// only their start is dumped
+const DECOMPRESSED_DUMP_MAX: usize = 0x10000;
+
+// hex and ASCII dump of decompressed contents, which aren't in the file dump
+fn generate_decompressed_dump(o: &mut dyn Write, data: &[u8]) -> fmt::Result {
+    let shown = &data[..data.len().min(DECOMPRESSED_DUMP_MAX)];
+    let summary = if shown.len() < data.len() {
+        format!("Decompressed contents, first {} bytes", shown.len())
+    } else {
+        String::from("Decompressed contents")
+    };
+
+    w!(o, 6, "<tr>");
+    w!(o, 7, "<td colspan='2'>");
+    w!(o, 8, "<details><summary>{}</summary>", summary);
+    w!(o, 9, "<div class='entries_wrapper decompressed'>");
+
+    for (i, row) in shown.chunks(16).enumerate() {
+        let hex: Vec<String> = row.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = row
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+            .collect();
+
+        w!(
+            o,
+            10,
+            "<div>{:08x}  {:<47}  {}</div>",
+            i * 16,
+            hex.join(" "),
+            html_escape_str(&ascii)
+        );
+    }
+
+    w!(o, 9, "</div>");
+    w!(o, 8, "</details>");
+    w!(o, 7, "</td>");
+    w!(o, 6, "</tr>");
+
+    Ok(())
+}
+
+fn generate_section_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (idx, shdr) in elf.shdrs.iter().enumerate() {
+        let compressed = elf.compressed.get(&(idx as u16));
+
+        w!(o, 5, "<table class='conceal' id='info_section{}'>", idx);
+        wrow!(o, 6, "Section type", &shtype_to_string(shdr.shtype));
+        wrow!(o, 6, "Size", shdr.size);
+
+        if let Some(compressed) = compressed {
+            generate_compression_rows(o, compressed)?;
+        }
+
+        if has_section_detail(elf, shdr, idx) {
+            w!(o, 6, "<tr><td><br></td></tr>");
+            generate_section_info_table(o, elf, shdr, idx)?;
+        }
+
+        if let Some(data) = compressed.and_then(|compressed| compressed.data.as_ref()) {
+            w!(o, 6, "<tr><td><br></td></tr>");
+            generate_decompressed_dump(o, data)?;
+        }
+
+        w!(o, 5, "</table>");
+    }
+
+    Ok(())
+}
+
+fn generate_note_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (idx, note) in elf.notes.iter().enumerate() {
+        w!(o, 5, "<table class='conceal' id='info_note{}'>", idx);
+        generate_note_data(o, note)?;
+        w!(o, 5, "</table>");
+    }
+
+    Ok(())
+}
+
+fn generate_dwarf_unit_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (idx, unit) in elf.dwarf_units.iter().enumerate() {
+        w!(o, 5, "<table class='conceal' id='info_cu{}'>", idx);
+        wrow!(o, 6, "Offset", format!("{:#x}", unit.offset));
+        wrow!(o, 6, "Length", unit.size);
+        wrow!(
+            o,
+            6,
+            "Format",
+            if unit.format64 { "DWARF64" } else { "DWARF32" }
+        );
+        wrow!(o, 6, "Version", unit.version);
+        wrow!(o, 6, "Unit type", dwarf_ut_to_string(unit.unit_type));
+        wrow!(o, 6, "Abbrev offset", format!("{:#x}", unit.abbrev_offset));
+        wrow!(o, 6, "Address size", unit.address_size);
+        wrow!(o, 6, "DIEs", unit.dies.len());
+        w!(o, 5, "</table>");
+    }
+
+    Ok(())
+}
+
+fn generate_line_program_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (idx, program) in elf.line_programs.iter().enumerate() {
+        w!(o, 5, "<table class='conceal' id='info_lp{}'>", idx);
+        wrow!(o, 6, "Offset", format!("{:#x}", program.offset));
+        wrow!(o, 6, "Length", program.size);
+        wrow!(
+            o,
+            6,
+            "Format",
+            if program.format64 {
+                "DWARF64"
+            } else {
+                "DWARF32"
+            }
+        );
+        wrow!(o, 6, "Version", program.version);
+        wrow!(o, 6, "Min instruction length", program.min_inst_length);
+        wrow!(o, 6, "Max ops per instruction", program.max_ops_per_inst);
+        wrow!(o, 6, "Default is_stmt", program.default_is_stmt);
+        wrow!(o, 6, "Line base", program.line_base);
+        wrow!(o, 6, "Line range", program.line_range);
+        wrow!(o, 6, "Opcode base", program.opcode_base);
+
+        // numbering starts at 1 before DWARF 5
+        let first = if program.version >= 5 { 0 } else { 1 };
+
+        if !program.directories.is_empty() {
+            w!(o, 6, "<tr><td><br></td></tr>");
+        }
+
+        for (i, directory) in program.directories.iter().enumerate() {
+            wrow!(
+                o,
+                6,
+                format!("Directory {}", i + first),
+                html_escape_str(directory)
+            );
+        }
+
+        if !program.files.is_empty() {
+            w!(o, 6, "<tr><td><br></td></tr>");
+        }
+
+        for (i, file) in program.files.iter().enumerate() {
+            wrow!(
+                o,
+                6,
+                format!("File {}", i + first),
+                format!(
+                    "{} (directory {})",
+                    html_escape_str(&file.name),
+                    file.directory
+                )
+            );
+        }
+
+        w!(o, 5, "</table>");
+    }
+
+    Ok(())
+}
+
+fn generate_frame_record_info_tables(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (idx, record) in elf.frame_records.iter().enumerate() {
+        w!(o, 5, "<table class='conceal' id='info_cfi{}'>", idx);
+        wrow!(o, 6, "Offset", format!("{:#x}", record.offset));
+        wrow!(o, 6, "Length", record.size);
+
+        match &record.entry {
+            FrameEntry::Cie(cie) => {
+                wrow!(o, 6, "Version", cie.version);
+                wrow!(
+                    o,
+                    6,
+                    "Augmentation",
+                    format!("\"{}\"", html_escape_str(&cie.augmentation))
+                );
+                wrow!(o, 6, "Code alignment", cie.code_alignment);
+                wrow!(o, 6, "Data alignment", cie.data_alignment);
+                wrow!(
+                    o,
+                    6,
+                    "Return address",
+                    dwarf_register_to_string(elf.machine, cie.return_register)
+                );
+                wrow!(o, 6, "FDE encoding", eh_pe_to_string(cie.fde_encoding));
+
+                if cie.lsda_encoding != DW_EH_PE_OMIT {
+                    wrow!(o, 6, "LSDA encoding", eh_pe_to_string(cie.lsda_encoding));
+                }
+
+                if let Some(personality) = cie.personality {
+                    wrow!(o, 6, "Personality", format!("{:#x}", personality));
+                }
+            }
+            FrameEntry::Fde(fde) => {
+                if let Some(cie) = fde.cie.and_then(|cie| elf.frame_records.get(cie)) {
+                    wrow!(o, 6, "CIE", format!("{:#x}", cie.offset));
+                }
+
+                wrow!(o, 6, "PC begin", format!("{:#x}", fde.pc_begin));
+                wrow!(o, 6, "PC range", format!("{:#x}", fde.pc_range));
+
+                if let Some(lsda) = fde.lsda {
+                    wrow!(o, 6, "LSDA", format!("{:#x}", lsda));
+                }
+            }
+        }
+
+        wrow!(o, 6, "Instructions", record.instructions.len());
+        w!(o, 5, "</table>");
+    }
+
+    Ok(())
+}
+
+// mappings are made with page granularity regardless of p_align
+const PAGE_SIZE: usize = 0x1000;
+
+fn page_align_down(addr: usize) -> usize {
+    addr & !(PAGE_SIZE - 1)
+}
+
+fn page_align_up(addr: usize) -> usize {
+    addr.saturating_add(PAGE_SIZE - 1) & !(PAGE_SIZE - 1)
+}
+
+// "RX" -> "r-x"
+fn format_permissions(flags: &str) -> String {
+    ['R', 'W', 'X']
+        .iter()
+        .map(|perm| {
+            if flags.contains(*perm) {
+                perm.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn format_vrange(start: usize, end: usize) -> String {
+    format!("{:#x}-{:#x}", start, end)
+}
+
+// `load_idx` is the LOAD segment whose mapping holds the overlay, an overlay
+// can land in more than one mapping when LOAD segments overlap
+fn generate_vmap_overlay(
+    o: &mut dyn Write,
+    level: usize,
+    phdr: &ParsedPhdr,
+    idx: usize,
+    load_idx: Option<usize>,
+) -> fmt::Result {
+    let id = match load_idx {
+        Some(load_idx) => format!("vmap_segment{}_{}", idx, load_idx),
+        None => format!("vmap_segment{}", idx),
+    };
+    let name = match phdr.ptype {
+        PT_GNU_RELRO => "relro",
+        PT_TLS => "tls",
+        _ => "stack",
+    };
+
+    wnonl!(
+        o,
+        level,
+        "<div class='vmap_overlay' id='{}' data-segment='{}'>",
+        id,
+        idx
+    );
+
+    if phdr.ptype == PT_GNU_STACK {
+        wnonl!(o, 0, "{} {}", name, format_permissions(&phdr.flags));
+    } else {
+        let end = phdr.vaddr.saturating_add(phdr.memsz);
+
+        wnonl!(o, 0, "{} {}", name, format_vrange(phdr.vaddr, end));
+
+        if phdr.memsz > phdr.file_size {
+            wnonl!(
+                o,
+                0,
+                " ({} zeroed)",
+                human_format_bytes((phdr.memsz - phdr.file_size) as u64)
+            );
+        }
+    }
+
+    w!(o, 0, "</div>");
+
+    Ok(())
+}
+
+fn generate_vmap_mapping(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    phdr: &ParsedPhdr,
+    idx: usize,
+) -> fmt::Result {
+    let start = page_align_down(phdr.vaddr);
+    let end = page_align_up(phdr.vaddr.saturating_add(phdr.memsz));
+    let file_end = phdr.vaddr.saturating_add(phdr.file_size);
+    let mem_end = phdr.vaddr.saturating_add(phdr.memsz);
+
+    w!(
+        o,
+        3,
+        "<div class='vmap_mapping' id='vmap_segment{}' data-segment='{}'>",
+        idx,
+        idx
+    );
+    w!(
+        o,
+        4,
+        "<div class='vmap_header'>{} {} ({})</div>",
+        format_permissions(&phdr.flags),
+        format_vrange(start, end),
+        human_format_bytes((end - start) as u64)
+    );
+
+    if phdr.file_size > 0 {
+        w!(
+            o,
+            4,
+            "<div class='vmap_file'>file {} @ {:#x}</div>",
+            format_vrange(phdr.vaddr, file_end),
+            phdr.file_offset
+        );
+    }
+
+    if phdr.memsz > phdr.file_size {
+        w!(
+            o,
+            4,
+            "<div class='vmap_bss'>bss {}</div>",
+            format_vrange(file_end, mem_end)
+        );
+    }
+
+    for (overlay_idx, overlay) in elf.phdrs.iter().enumerate() {
+        let is_overlay = overlay.ptype == PT_GNU_RELRO || overlay.ptype == PT_TLS;
+
+        if is_overlay && overlay.vaddr >= phdr.vaddr && overlay.vaddr < mem_end {
+            generate_vmap_overlay(o, 4, overlay, overlay_idx, Some(idx))?;
+        }
+    }
+
+    w!(o, 3, "</div>");
+
+    Ok(())
+}
+
+fn generate_vmap(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    let mut loads: Vec<(usize, &ParsedPhdr)> = elf
+        .phdrs
+        .iter()
+        .enumerate()
+        .filter(|(_, phdr)| phdr.ptype == PT_LOAD)
+        .collect();
+
+    if loads.is_empty() {
+        return Ok(());
+    }
+
+    loads.sort_by_key(|(_, phdr)| phdr.vaddr);
+
+    w!(o, 2, "<div id='vmap'>");
+
+    let mut prev_end: Option<usize> = None;
+
+    for (idx, phdr) in loads.iter() {
+        let start = page_align_down(phdr.vaddr);
+
+        if let Some(prev_end) = prev_end {
+            if start > prev_end {
+                w!(
+                    o,
+                    3,
+                    "<div class='vmap_gap'>{} unmapped</div>",
+                    human_format_bytes((start - prev_end) as u64)
+                );
+            }
+        }
+
+        generate_vmap_mapping(o, elf, phdr, *idx)?;
+
+        prev_end = Some(page_align_up(phdr.vaddr.saturating_add(phdr.memsz)));
+    }
+
+    // stack and overlays which don't land in any LOAD mapping
+    for (idx, phdr) in elf.phdrs.iter().enumerate() {
+        let is_overlay = phdr.ptype == PT_GNU_RELRO || phdr.ptype == PT_TLS;
+        let in_load = loads.iter().any(|(_, load)| {
+            phdr.vaddr >= load.vaddr && phdr.vaddr < load.vaddr.saturating_add(load.memsz)
+        });
+
+        if phdr.ptype == PT_GNU_STACK || (is_overlay && !in_load) {
+            generate_vmap_overlay(o, 3, phdr, idx, None)?;
+        }
+    }
+
+    w!(o, 2, "</div>");
+
+    Ok(())
+}
+
+fn generate_sticky_info_table(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    w!(o, 2, "<table id='sticky_table' cellspacing='0'>");
+    w!(o, 3, "<tr>");
+
+    w!(o, 4, "<td id='desc'></td>");
+
+    w!(o, 4, "<td id='struct_infotables'>");
+    generate_phdr_info_tables(o, elf)?;
+
+    generate_shdr_info_tables(o, elf)?;
+    w!(o, 4, "</td>");
+
+    w!(o, 4, "<td id='data_infotables'>");
+    generate_segment_info_tables(o, elf)?;
+
+    generate_section_info_tables(o, elf)?;
+
+    generate_note_info_tables(o, elf)?;
+    generate_dwarf_unit_info_tables(o, elf)?;
+    generate_line_program_info_tables(o, elf)?;
+    generate_frame_record_info_tables(o, elf)?;
+    w!(o, 4, "</td>");
+
+    w!(o, 3, "</tr>");
+    w!(o, 2, "</table>");
+
+    Ok(())
+}
+
+fn add_highlight_script(o: &mut dyn Write) -> fmt::Result {
+    let ids = [
+        "class",
+        "data",
+        "abi",
+        "abi_ver",
+        "e_type",
+        "e_machine",
+        "e_entry",
+        "e_phoff",
+        "e_shoff",
+        "e_flags",
+        "e_ehsize",
+        "e_phentsize",
+        "e_phnum",
+        "e_shentsize",
+        "e_shnum",
+        "e_shstrndx",
+    ];
+
+    w!(o, 2, "<script type='text/javascript'>");
+
+    wnonl!(o, 0, "{}", include_str!("js/highlight.js").indent_lines(3));
+
+    for id in ids.iter() {
+        w!(o, 3, "highlightIds('{}', 'info_{}')", id, id);
+        w!(o, 3, "highlightIds('ascii_{}', 'info_{}')", id, id);
+    }
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+// file offsets of the instructions of each line table row, sorted
+fn source_lines(elf: &ParsedElf) -> Vec<(usize, usize, String)> {
+    let code: Vec<&ParsedShdr> = elf
+        .shdrs
+        .iter()
+        .filter(|shdr| shdr.flags & SHF_EXECINSTR != 0 && shdr.shtype != SHT_NOBITS)
+        .collect();
+    let mut lines = vec![];
+
+    for program in elf.line_programs.iter() {
+        // a row covers the addresses up to the next one of its sequence
+        for rows in program.rows.windows(2) {
+            let (row, next) = (&rows[0], &rows[1]);
+
+            if row.end_sequence || next.address <= row.address {
+                continue;
+            }
+
+            let address = row.address as usize;
+            let shdr = code
+                .iter()
+                .find(|shdr| address >= shdr.addr && address - shdr.addr < shdr.size);
+
+            // sections whose bytes aren't in the file have no code to annotate
+            let (shdr, data) = match shdr.and_then(|shdr| Some((shdr, elf.section_data(shdr)?))) {
+                Some(found) => found,
+                None => continue,
+            };
+            let skip = address - shdr.addr;
+            let len = (next.address - row.address) as usize;
+            let start = shdr.file_offset + skip;
+            let end = start + len.min(data.len() - skip);
+
+            lines.push((start, end, source_location(program, row.file, row.line)));
+        }
+    }
+
+    lines.sort_by_key(|&(start, _, _)| start);
+    lines
+}
+
+fn add_description_script(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    let lines: Vec<String> = source_lines(elf)
+        .iter()
+        .map(|(start, end, location)| {
+            format!(
+                "[{}, {}, {}]",
+                start,
+                end,
+                js_string(&html_escape_str(location))
+            )
+        })
+        .collect();
+
+    w!(o, 3, "var sourceLines = [{}];", lines.join(", "));
+
+    wnonl!(
+        o,
+        0,
+        "{}",
+        include_str!("js/description.js").indent_lines(3)
+    );
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_conceal_script(o: &mut dyn Write) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    wnonl!(o, 0, "{}", include_str!("js/conceal.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_entries_script(o: &mut dyn Write) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    wnonl!(o, 0, "{}", include_str!("js/entries.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_ascii_script(o: &mut dyn Write, elf: &ParsedElf, options: &ReportOptions) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    w!(o, 3, "let fileLen = {};", elf.file_size);
+    w!(o, 3, "var bytesPerRow = {};", options.width);
+
+    wnonl!(o, 0, "{}", include_str!("js/ascii.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+// names of the linked symbols are needed to look them up through the tables
+fn add_hash_script(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    if elf.hash_tables.is_empty() {
+        return Ok(());
+    }
+
+    let join = |values: &[u32]| {
+        let values: Vec<String> = values.iter().map(u32::to_string).collect();
+
+        values.join(", ")
+    };
+
+    w!(o, 2, "<script type='text/javascript'>");
+
+    w!(o, 3, "var hashTables = {{}};");
+
+    for (idx, table) in elf.hash_tables.iter() {
+        let names: Vec<String> = elf
+            .shdrs
+            .get(*idx as usize)
+            .and_then(|shdr| elf.symtabs.get(&(shdr.link as u16)))
+            .map_or(vec![], |symbols| {
+                symbols.iter().map(|sym| js_string(&sym.name)).collect()
+            });
+
+        w!(o, 3, "hashTables[{}] = {{", idx);
+        w!(o, 4, "names: [{}],", names.join(", "));
+
+        match table {
+            HashTable::Sysv { buckets, chains } => {
+                w!(o, 4, "gnu: false,");
+                w!(o, 4, "buckets: [{}],", join(buckets));
+                w!(o, 4, "chains: [{}],", join(chains));
+            }
+            HashTable::Gnu {
+                symoffset,
+                bloom_shift,
+                bloom_bits,
+                bloom,
+                buckets,
+                chains,
+            } => {
+                // bloom words can be 64-bit, they're BigInts
+                let bloom: Vec<String> =
+                    bloom.iter().map(|word| format!("'{:#x}'", word)).collect();
+
+                w!(o, 4, "gnu: true,");
+                w!(o, 4, "symoffset: {},", symoffset);
+                w!(o, 4, "bloomShift: {},", bloom_shift);
+                w!(o, 4, "bloomBits: {},", bloom_bits);
+                w!(o, 4, "bloom: [{}],", bloom.join(", "));
+                w!(o, 4, "buckets: [{}],", join(buckets));
+                w!(o, 4, "chains: [{}],", join(chains));
+            }
+        }
+
+        w!(o, 3, "}};");
+    }
+
+    wnonl!(o, 0, "{}", include_str!("js/hash.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_vmap_script(o: &mut dyn Write) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
 
-        s.push(digit_to_hex(leading_digit));
-        s.push(digit_to_hex(trailing_digit));
+    wnonl!(o, 0, "{}", include_str!("js/vmap.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_offsets_script(o: &mut dyn Write) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    wnonl!(o, 0, "{}", include_str!("js/offsets.js").indent_lines(3));
+
+    w!(o, 3, "populateOffsets()");
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_arrows_script(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    wnonl!(o, 0, "{}", include_str!("js/arrows.js").indent_lines(3));
+
+    w!(o, 3, "connect('#e_phoff', '#bin_phdr0');");
+    w!(o, 3, "connect('#e_shoff', '#bin_shdr0');");
+
+    for i in 0..elf.phdrs.len() {
+        w!(
+            o,
+            3,
+            "connect('#bin_phdr{} > .p_offset', '#bin_segment{}');",
+            i,
+            i
+        );
+    }
+
+    for i in 0..elf.shdrs.len() {
+        w!(
+            o,
+            3,
+            "connect('#bin_shdr{} > .sh_offset', '#bin_section{}');",
+            i,
+            i
+        );
     }
+
+    w!(o, 2, "</script>");
+
+    Ok(())
 }
 
-fn generate_dump_for_byte(idx: usize, dump: &mut String, elf: &ParsedElf) {
-    let byte = elf.contents[idx];
+// single-quoted, and safe to put inside of <script>
+fn js_string(s: &str) -> String {
+    let mut quoted = String::from("'");
 
-    for range_type in &elf.ranges.data[idx] {
-        if *range_type != RangeType::End {
-            dump.push_str(format!("<span {}>", range_type.span_attributes()).as_str());
+    for ch in s.chars() {
+        match ch {
+            '\'' | '\\' => {
+                quoted.push('\\');
+                quoted.push(ch);
+            }
+            '<' => quoted.push_str("\\x3c"),
+            c if (c as u32) < 0x20 || c == '\u{2028}' || c == '\u{2029}' => {
+                write!(quoted, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => quoted.push(c),
         }
     }
 
-    if idx < 4 {
-        dump.push_str(&format_magic(byte))
-    } else {
-        append_hex_byte(dump, byte);
+    quoted.push('\'');
+    quoted
+}
+
+fn js_string_or_null(value: Option<String>) -> String {
+    match value {
+        Some(value) => js_string(&value),
+        None => String::from("null"),
+    }
+}
+
+// the pairs of ranges that add_arrows_script connects, as indices into
+// elf.ranges.iter()
+fn lazy_arrows(elf: &ParsedElf) -> Vec<(usize, usize)> {
+    let mut ehdr = None;
+    let mut phdrs = BTreeMap::new();
+    let mut shdrs = BTreeMap::new();
+    let mut segments = BTreeMap::new();
+    let mut sections = BTreeMap::new();
+    let mut fields = BTreeMap::new();
+
+    for (i, range) in elf.ranges.iter().enumerate() {
+        let location = (range.start, range.end, i);
+
+        match range.range_type {
+            RangeType::FileHeader => ehdr = Some(location),
+            RangeType::ProgramHeader(idx) => {
+                phdrs.insert(idx as usize, location);
+            }
+            RangeType::SectionHeader(idx) => {
+                shdrs.insert(idx as usize, location);
+            }
+            RangeType::Segment(idx) => {
+                segments.insert(idx as usize, location);
+            }
+            RangeType::Section(idx) => {
+                sections.insert(idx as usize, location);
+            }
+            RangeType::HeaderField(name)
+            | RangeType::PhdrField(name)
+            | RangeType::ShdrField(name) => {
+                // lookup_field finds the first one, same as here
+                fields.entry((range.start, range.end, name)).or_insert(i);
+            }
+            _ => {}
+        }
+    }
+
+    let field = |(start, end, _): (usize, usize, usize), name: &'static str| {
+        elf.ranges
+            .lookup_field(start, end - start, name)
+            .and_then(|(start, len)| fields.get(&(start, start + len, name)).copied())
+    };
+    let mut arrows = vec![];
+    let mut connect = |from: Option<usize>, to: Option<&(usize, usize, usize)>| {
+        if let (Some(from), Some(to)) = (from, to) {
+            arrows.push((from, to.2));
+        }
+    };
+
+    if let Some(ehdr) = ehdr {
+        connect(field(ehdr, "e_phoff"), phdrs.get(&0));
+        connect(field(ehdr, "e_shoff"), shdrs.get(&0));
     }
 
-    for _ in 0..elf.ranges.lookup_range_ends(idx) {
-        dump.push_str("</span>");
+    for (idx, phdr) in &phdrs {
+        connect(field(*phdr, "p_offset"), segments.get(idx));
     }
 
-    dump.push_str(if (idx + 1) % 16 == 0 { "\n" } else { " " });
+    for (idx, shdr) in &shdrs {
+        connect(field(*shdr, "sh_offset"), sections.get(idx));
+    }
+
+    arrows
 }
 
-// assumes balance == 1
-fn skip_bytes(idx: usize, len: usize, elf: &ParsedElf) -> Option<usize> {
-    if !(elf.ranges.data[idx].len() == 1 && elf.ranges.data[idx][0].skippable()) {
-        return None;
+fn push_uleb128(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
     }
 
-    let mut new_idx = idx + 1;
+    bytes.push(value as u8);
+}
 
-    while new_idx < len {
-        match elf.ranges.data[new_idx].len() {
-            0 => {
-                new_idx += 1;
-            }
+// "bin_die3_45" -> (["bin_die", "_", ""], [3, 45]). Numbers which wouldn't be
+// written back the same, e.g. with leading zeros, stay in the text.
+fn split_id(id: &str) -> (Vec<&str>, Vec<u64>) {
+    let bytes = id.as_bytes();
+    let mut parts = vec![];
+    let mut numbers = vec![];
+    let mut literal_start = 0;
+    let mut i = 0;
 
-            1 => {
-                if elf.ranges.data[new_idx][0] == RangeType::End {
-                    return Some(new_idx);
-                }
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
 
-                return None;
+        let end = bytes[i..]
+            .iter()
+            .position(|byte| !byte.is_ascii_digit())
+            .map_or(bytes.len(), |len| i + len);
+        let digits = &id[i..end];
+
+        match digits.parse::<u64>() {
+            Ok(number) if number.to_string() == digits => {
+                parts.push(&id[literal_start..i]);
+                numbers.push(number);
+                literal_start = end;
             }
+            _ => {}
+        }
+
+        i = end;
+    }
+
+    parts.push(&id[literal_start..]);
+
+    (parts, numbers)
+}
+
+// 1 + the index of `key` in `table`, which it's added to if it isn't there yet
+fn table_index(table: &mut HashMap<String, usize>, key: String) -> u64 {
+    let len = table.len();
+
+    *table.entry(key).or_insert(len) as u64 + 1
+}
 
-            _ => {
-                return None;
+fn write_js_table(o: &mut dyn Write, name: &str, table: HashMap<String, usize>) -> fmt::Result {
+    let mut entries: Vec<(String, usize)> = table.into_iter().collect();
+
+    entries.sort_by_key(|(_, idx)| *idx);
+
+    w!(o, 3, "var {} = [", name);
+
+    for (entry, _) in entries {
+        w!(o, 4, "{},", entry);
+    }
+
+    w!(o, 3, "];");
+
+    Ok(())
+}
+
+// The ranges are the bulk of a lazy report, so they're packed into bytes and
+// written as base64. Every range is a run of LEB128 integers: its start
+// relative to the start of the previous range, its length, 1 + the index of
+// its class in rangeClasses and of its id template in rangeIdTemplates (0 for
+// none), then the numbers to put between the parts of the template. See
+// unpackRanges() in js/lazy.js.
+fn write_packed_ranges(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    let mut classes = HashMap::new();
+    let mut templates = HashMap::new();
+    let mut bytes = vec![];
+    let mut prev_start = 0;
+
+    wnonl!(o, 3, "var packedRanges = '");
+
+    let mut base64 = Base64Writer::new(o);
+
+    for range in elf.ranges.iter() {
+        bytes.clear();
+
+        push_uleb128(&mut bytes, (range.start - prev_start) as u64);
+        push_uleb128(&mut bytes, (range.end - range.start) as u64);
+
+        let class = range.range_type.html_class();
+        let class = class.map_or(0, |class| table_index(&mut classes, js_string(&class)));
+
+        push_uleb128(&mut bytes, class);
+
+        match range.range_type.html_id() {
+            Some(id) => {
+                let (parts, numbers) = split_id(&id);
+                let template = format!(
+                    "[{}]",
+                    parts
+                        .into_iter()
+                        .map(js_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+
+                push_uleb128(&mut bytes, table_index(&mut templates, template));
+
+                for number in numbers {
+                    push_uleb128(&mut bytes, number);
+                }
             }
+            None => push_uleb128(&mut bytes, 0),
         }
+
+        base64.write(&bytes)?;
+        prev_start = range.start;
     }
 
-    None
+    base64.finish()?;
+    w!(o, 0, "';");
+
+    write_js_table(o, "rangeClasses", classes)?;
+    write_js_table(o, "rangeIdTemplates", templates)
 }
 
-fn generate_file_dump(elf: &ParsedElf) -> String {
-    let mut dump = String::new();
-    let mut i = 0;
-    let len = elf.contents.len();
-    let mut balance: i64 = 0;
-
-    while i < len {
-        // we are iterating twice for one byte, which is not very smart
-        for r in &elf.ranges.data[i] {
-            if *r == RangeType::End {
-                balance -= 1;
-            } else {
-                balance += 1;
-            }
+// Instead of the dumps, the file and its ranges are embedded as data for
+// js/lazy.js to build the rows in view from. Offsets and arrows are drawn by
+// it too.
+fn add_lazy_script(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    wnonl!(o, 3, "var fileBase64 = '");
+    write_base64(o, elf.contents)?;
+    w!(o, 0, "';");
+
+    write_packed_ranges(o, elf)?;
+
+    // [from, to] as indices into fileRanges
+    w!(o, 3, "var fileArrows = [");
+
+    for (from, to) in lazy_arrows(elf) {
+        w!(o, 4, "[{}, {}],", from, to);
+    }
+
+    w!(o, 3, "];");
+
+    wnonl!(o, 0, "{}", include_str!("js/lazy.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+// segments and sections which js/collapse.js can hide
+fn collapsible_ranges<'a>(elf: &'a ParsedElf) -> Vec<&'a Range> {
+    elf.ranges
+        .iter()
+        .filter(|range| range.range_type.skippable())
+        .collect()
+}
+
+fn collapse_label(elf: &ParsedElf, range: &Range) -> String {
+    let name = match range.range_type {
+        RangeType::Segment(idx) => match elf.phdrs.get(idx as usize) {
+            Some(phdr) => format!("segment {} ({})", idx, ptype_to_string(phdr.ptype)),
+            None => format!("segment {}", idx),
+        },
+        RangeType::Section(idx) => match elf.shdrs.get(idx as usize) {
+            Some(shdr) => format!("section {} ({})", idx, elf.shnstrtab.get(shdr.name)),
+            None => format!("section {}", idx),
+        },
+        _ => String::new(),
+    };
+
+    format!(
+        "{}, {}",
+        name,
+        human_format_bytes((range.end - range.start) as u64)
+    )
+}
+
+fn add_collapsible_script(
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    options: &ReportOptions,
+) -> fmt::Result {
+    w!(o, 2, "<script type='text/javascript'>");
+
+    // [start, end, id, label, starts collapsed], ordered like fileRanges
+    w!(o, 3, "var collapsibleRanges = [");
+
+    for range in collapsible_ranges(elf) {
+        let collapsed = options
+            .collapse_above
+            .is_some_and(|size| range.end - range.start > size);
+
+        w!(
+            o,
+            4,
+            "[{}, {}, {}, {}, {}],",
+            range.start,
+            range.end,
+            js_string_or_null(range.range_type.html_id()),
+            js_string(&html_escape_str(&collapse_label(elf, range))),
+            collapsed
+        );
+    }
+
+    w!(o, 3, "];");
+
+    wnonl!(o, 0, "{}", include_str!("js/collapse.js").indent_lines(3));
+
+    w!(o, 2, "</script>");
+
+    Ok(())
+}
+
+fn add_scripts(o: &mut dyn Write, elf: &ParsedElf, options: &ReportOptions) -> fmt::Result {
+    add_highlight_script(o)?;
+
+    add_description_script(o, elf)?;
+
+    add_conceal_script(o)?;
+
+    add_entries_script(o)?;
+
+    add_hash_script(o, elf)?;
+
+    add_vmap_script(o)?;
+
+    add_ascii_script(o, elf, options)?;
+
+    add_collapsible_script(o, elf, options)?;
+
+    if options.lazy {
+        add_lazy_script(o, elf)?;
+    } else {
+        add_offsets_script(o)?;
+
+        add_arrows_script(o, elf)?;
+    }
+
+    Ok(())
+}
+
+fn format_magic(byte: u8) -> String {
+    if byte.is_ascii_graphic() {
+        format!("&nbsp;{}", char::from(byte))
+    } else {
+        format!("{:02x}", byte)
+    }
+}
+
+fn digit_to_hex(digit: u8) -> char {
+    [
+        '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+    ][digit as usize]
+}
+
+fn append_hex_byte(o: &mut dyn Write, byte: u8) -> fmt::Result {
+    if byte < 0x10 {
+        o.write_char('0')?;
+
+        o.write_char(digit_to_hex(byte))?;
+    } else {
+        let trailing_digit = byte % 16;
+        let leading_digit = byte / 16;
+
+        o.write_char(digit_to_hex(leading_digit))?;
+        o.write_char(digit_to_hex(trailing_digit))?;
+    }
+
+    Ok(())
+}
+
+// the hex and ASCII dumps share the same span structure so that one can be
+// mapped onto the other, see js/ascii.js
+fn open_ranges<'a>(
+    idx: usize,
+    o: &mut dyn Write,
+    events: &mut Peekable<Events<'a>>,
+    open: &mut Vec<&'a Range>,
+    id_prefix: &str,
+) -> fmt::Result {
+    while let Some(RangeEvent::Open(range)) = events.peek() {
+        if range.start != idx {
+            break;
         }
 
-        // account for one potential skippable range which would already start (incr. balance by 1)
-        // disable while working on offsets
-        if false && balance == 1 {
-            if let Some(new_idx) = skip_bytes(i, len, elf) {
-                dump += format!(
-                    "<span {}>..</span>",
-                    elf.ranges.data[i][0].span_attributes()
-                )
-                .as_str();
+        wnonl!(
+            o,
+            0,
+            "<span {}>",
+            range.range_type.span_attributes(id_prefix)
+        );
+        open.push(range);
+        events.next();
+    }
 
-                dump += if (i + 1) % 16 == 0 { "\n" } else { " " };
+    Ok(())
+}
 
-                i = new_idx;
-                continue;
-            }
+// A range can end while ranges that started inside of it are still open. Their
+// spans are closed along with it and continue in new ones, so that every span
+// covers exactly the bytes of its range.
+fn close_ranges<'a>(
+    idx: usize,
+    o: &mut dyn Write,
+    events: &mut Peekable<Events<'a>>,
+    open: &mut Vec<&'a Range>,
+    id_prefix: &str,
+) -> fmt::Result {
+    while let Some(RangeEvent::Close(range)) = events.peek() {
+        if range.end != idx + 1 {
+            break;
         }
 
-        generate_dump_for_byte(i, &mut dump, elf);
-        i += 1;
+        events.next();
+    }
+
+    let first = match open.iter().position(|range| range.end == idx + 1) {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+
+    for _ in first..open.len() {
+        wnonl!(o, 0, "</span>");
+    }
+
+    let continued: Vec<&Range> = open
+        .drain(first..)
+        .filter(|range| range.end != idx + 1)
+        .collect();
+
+    for range in continued {
+        wnonl!(
+            o,
+            0,
+            "<span {}>",
+            range.range_type.continuation_attributes(id_prefix)
+        );
+        open.push(range);
+    }
+
+    Ok(())
+}
+
+fn generate_dump_for_byte<'a>(
+    idx: usize,
+    o: &mut dyn Write,
+    elf: &ParsedElf,
+    events: &mut Peekable<Events<'a>>,
+    open: &mut Vec<&'a Range>,
+    width: usize,
+) -> fmt::Result {
+    let byte = elf.contents[idx];
+
+    open_ranges(idx, o, events, open, "")?;
+
+    if idx < 4 {
+        wnonl!(o, 0, "{}", format_magic(byte));
+    } else {
+        append_hex_byte(o, byte)?;
+    }
+
+    close_ranges(idx, o, events, open, "")?;
+
+    if (idx + 1).is_multiple_of(width) {
+        w!(o, 0, "");
+    } else {
+        wnonl!(o, 0, " ");
+    }
+
+    Ok(())
+}
+
+fn generate_file_dump(o: &mut dyn Write, elf: &ParsedElf, width: usize) -> fmt::Result {
+    let mut events = elf.ranges.events().peekable();
+    let mut open = vec![];
+
+    for i in 0..elf.contents.len() {
+        generate_dump_for_byte(i, o, elf, &mut events, &mut open, width)?;
     }
 
-    dump
+    Ok(())
 }
 
-fn generate_ascii_dump(o: &mut String, elf: &ParsedElf) {
+fn generate_ascii_dump(o: &mut dyn Write, elf: &ParsedElf, width: usize) -> fmt::Result {
+    let mut events = elf.ranges.events().peekable();
+    let mut open = vec![];
+
     for (i, b) in elf.contents.iter().enumerate() {
+        open_ranges(i, o, &mut events, &mut open, "ascii_")?;
+
         if b.is_ascii_graphic() {
             let ch = *b as char;
 
@@ -602,22 +2630,32 @@ fn generate_ascii_dump(o: &mut String, elf: &ParsedElf) {
             wnonl!(o, 0, ".");
         }
 
-        if (i + 1) % 16 == 0 {
+        close_ranges(i, o, &mut events, &mut open, "ascii_")?;
+
+        if (i + 1).is_multiple_of(width) {
             w!(o, 0, "");
         }
     }
+
+    Ok(())
 }
 
-fn generate_body(o: &mut String, elf: &ParsedElf) {
+fn generate_body(o: &mut dyn Write, elf: &ParsedElf, options: &ReportOptions) -> fmt::Result {
     w!(o, 1, "<body>");
 
-    generate_svg_element(o);
+    generate_svg_element(o)?;
 
     w!(o, 2, "<table id='headertable'>");
     w!(o, 3, "<td>");
-    generate_file_info_table(o, elf);
+    generate_file_info_table(o, elf)?;
+    generate_diagnostics_table(o, elf)?;
     w!(o, 3, "</td>");
     w!(o, 3, "<td id='rightmenu'>");
+    w!(
+        o,
+        4,
+        "<p><button id='theme_toggle'>Toggle dark theme</button></p>"
+    );
     w!(
         o,
         4,
@@ -629,31 +2667,216 @@ fn generate_body(o: &mut String, elf: &ParsedElf) {
 
     w!(o, 2, "<div id='offsets'></div>");
 
-    w!(o, 2, "<div id='bytes'>");
-    wnonl!(o, 0, "{}", generate_file_dump(elf));
-    w!(o, 2, "</div>");
+    if options.lazy {
+        // filled in by js/lazy.js
+        w!(o, 2, "<div id='bytes'></div>");
+        w!(o, 2, "<div id='ascii'></div>");
+    } else {
+        w!(o, 2, "<div id='bytes'>");
+        generate_file_dump(o, elf, options.width)?;
+        w!(o, 2, "</div>");
 
-    w!(o, 2, "<div id='ascii'>");
-    generate_ascii_dump(o, elf);
-    w!(o, 2, "</div>");
+        w!(o, 2, "<div id='ascii'>");
+        generate_ascii_dump(o, elf, options.width)?;
+        w!(o, 2, "</div>");
+    }
 
-    generate_sticky_info_table(o, elf);
+    generate_vmap(o, elf)?;
 
-    add_scripts(o, elf);
+    generate_sticky_info_table(o, elf)?;
+
+    add_scripts(o, elf, options)?;
 
     w!(o, 1, "</body>");
+
+    Ok(())
+}
+
+// Lets the generators write with fmt::Write into an io::Write. fmt::Error
+// can't carry the io error, so it's kept here and every write after it fails
+// to stop the generators early.
+struct IoSink<'a> {
+    out: &'a mut dyn io::Write,
+    error: Option<io::Error>,
+}
+
+impl Write for IoSink<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+
+        self.out.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+// The report is written incrementally, wrap `out` in a BufWriter if it isn't
+// buffered already.
+pub fn generate_report(
+    elf: &ParsedElf,
+    options: &ReportOptions,
+    out: &mut dyn io::Write,
+) -> io::Result<()> {
+    let mut sink = IoSink { out, error: None };
+
+    let result = match options.format {
+        Format::Html => generate_html(&mut sink, elf, options),
+        Format::Json => generate_json(&mut sink, elf),
+        Format::Term => generate_term(&mut sink, elf, options.width),
+    };
+
+    match (sink.error, result) {
+        (Some(e), _) => Err(e),
+        (None, Ok(())) => Ok(()),
+        (None, Err(_)) => Err(io::Error::other("formatting failed")),
+    }
+}
+
+fn generate_html(o: &mut dyn Write, elf: &ParsedElf, options: &ReportOptions) -> fmt::Result {
+    w!(o, 0, "<!doctype html>");
+    w!(o, 0, "<html>");
+
+    generate_head(o, elf, options)?;
+    generate_body(o, elf, options)?;
+
+    w!(o, 0, "</html>");
+
+    Ok(())
 }
 
-pub fn generate_report(elf: &ParsedElf) -> String {
-    let mut output = String::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(buf: &mut Vec<u8>, value: u64, size: usize) {
+        buf.extend_from_slice(&value.to_le_bytes()[..size]);
+    }
+
+    // an ELF64 file with a segment at 0x100..0x180 and a section at
+    // 0x140..0x1c0, which cross each other
+    fn crossing_elf() -> Vec<u8> {
+        let mut buf = vec![0x7f, b'E', b'L', b'F', 2, 1, 1];
+        buf.resize(16, 0);
+
+        for (value, size) in [
+            (ELF_ET_EXEC as u64, 2),
+            (0x3e, 2),
+            (1, 4),
+            (0, 8),
+            (0x40, 8),
+            (0x1e0, 8),
+            (0, 4),
+            (64, 2),
+            (56, 2),
+            (1, 2),
+            (64, 2),
+            (3, 2),
+            (2, 2),
+        ] {
+            push(&mut buf, value, size);
+        }
+
+        // PT_LOAD
+        for (value, size) in [(1, 4), (4, 4), (0x100, 8), (0x100, 8), (0x100, 8)] {
+            push(&mut buf, value, size);
+        }
+        for (value, size) in [(0x80, 8), (0x80, 8), (0x1000, 8)] {
+            push(&mut buf, value, size);
+        }
+
+        buf.resize(0x1c0, 0xaa);
+        buf.extend_from_slice(b"\0.data\0.shstrtab\0");
+        buf.resize(0x1e0, 0);
+
+        let shdrs: [(u64, u64, u64, u64); 3] = [
+            (0, 0, 0, 0),
+            (1, SHT_PROGBITS as u64, 0x140, 0x80),
+            (7, SHT_STRTAB as u64, 0x1c0, 17),
+        ];
+
+        for (name, shtype, offset, size) in shdrs {
+            for (value, size) in [
+                (name, 4),
+                (shtype, 4),
+                (0, 8),
+                (0, 8),
+                (offset, 8),
+                (size, 8),
+                (0, 4),
+                (0, 4),
+                (1, 8),
+                (0, 8),
+            ] {
+                push(&mut buf, value, size);
+            }
+        }
+
+        buf
+    }
+
+    // bytes of the dump under the spans of each range, continued ones included
+    fn span_bytes(dump: &str) -> HashMap<String, Vec<usize>> {
+        let mut bytes: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut open: Vec<Option<String>> = vec![];
+        let mut idx = 0;
+        let mut rest = dump;
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("</span>") {
+                open.pop();
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("<span ") {
+                let end = after.find('>').unwrap();
+                let id = after[..end]
+                    .split(' ')
+                    .find_map(|attribute| {
+                        attribute
+                            .strip_prefix("id=")
+                            .or_else(|| attribute.strip_prefix("data-continues="))
+                    })
+                    .map(|id| id.trim_matches('\'').to_string());
+
+                open.push(id);
+                rest = &after[end + 1..];
+            } else if rest.starts_with(' ') || rest.starts_with('\n') {
+                rest = &rest[1..];
+            } else {
+                let len = if rest.starts_with("&nbsp;") { 7 } else { 2 };
+
+                for id in open.iter().flatten() {
+                    bytes.entry(id.clone()).or_default().push(idx);
+                }
+
+                idx += 1;
+                rest = &rest[len..];
+            }
+        }
 
-    w!(&mut output, 0, "<!doctype html>");
-    w!(&mut output, 0, "<html>");
+        bytes
+    }
+
+    #[test]
+    fn crossing_ranges_are_collapsible() {
+        let buf = crossing_elf();
+        let elf = ParsedElf::from_bytes("crossing", &buf).unwrap();
+        let collapsible: Vec<RangeType> = collapsible_ranges(&elf)
+            .iter()
+            .map(|range| range.range_type.clone())
+            .collect();
 
-    generate_head(&mut output, elf);
-    generate_body(&mut output, elf);
+        assert!(collapsible.contains(&RangeType::Segment(0)));
+        assert!(collapsible.contains(&RangeType::Section(1)));
 
-    w!(&mut output, 0, "</html>");
+        let mut dump = String::new();
+        generate_file_dump(&mut dump, &elf, 16).unwrap();
 
-    output
+        let bytes = span_bytes(&dump);
+
+        assert_eq!(bytes["bin_segment0"], (0x100..0x180).collect::<Vec<_>>());
+        assert_eq!(bytes["bin_section1"], (0x140..0x1c0).collect::<Vec<_>>());
+        assert!(dump.contains("<span data-continues='bin_section1'"));
+    }
 }
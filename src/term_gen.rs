@@ -0,0 +1,378 @@
+use crate::elf::defs::*;
+use crate::elf::parser::{FrameEntry, FrameRecord, ParsedElf, RangeType};
+use crate::elf::ranges::{Events, Range, RangeEvent};
+use crate::utils::strip_html_tags;
+use std::fmt::{self, Write};
+use std::iter::Peekable;
+
+// Colors are plain SGR sequences from the 16 color palette, so that the
+// output survives `less -R`. Every line is self-contained: styles are reset
+// before the newline and restored on the next line.
+
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Category {
+    FileHeader,
+    ProgramHeader,
+    SectionHeader,
+    Segment,
+    Section,
+}
+
+const LEGEND: [(Category, &str); 5] = [
+    (Category::FileHeader, "file header"),
+    (Category::ProgramHeader, "program headers"),
+    (Category::SectionHeader, "section headers"),
+    (Category::Segment, "segments"),
+    (Category::Section, "sections"),
+];
+
+impl Category {
+    fn of(range_type: &RangeType) -> Option<Category> {
+        match range_type {
+            RangeType::Ident | RangeType::FileHeader | RangeType::HeaderField(_) => {
+                Some(Category::FileHeader)
+            }
+            RangeType::ProgramHeader(_) | RangeType::PhdrField(_) => Some(Category::ProgramHeader),
+            RangeType::SectionHeader(_) | RangeType::ShdrField(_) => Some(Category::SectionHeader),
+            RangeType::Segment(_) | RangeType::Note(_) | RangeType::NoteField(_) => {
+                Some(Category::Segment)
+            }
+            RangeType::Section(_)
+            | RangeType::Symbol(_, _)
+            | RangeType::SymbolField(_)
+            | RangeType::Relocation(_, _)
+            | RangeType::RelocationField(_)
+            | RangeType::Dynamic(_)
+            | RangeType::DynamicField(_)
+            | RangeType::Version(_, _)
+            | RangeType::VersionField(_)
+            | RangeType::HashField(_)
+            | RangeType::DwarfUnit(_)
+            | RangeType::DwarfField(_)
+            | RangeType::Die(_, _)
+            | RangeType::DieAttribute(_, _, _)
+            | RangeType::LineProgram(_)
+            | RangeType::LineOp(_, _)
+            | RangeType::FrameRecord(_)
+            | RangeType::FrameField(_)
+            | RangeType::CfaOp(_, _)
+            | RangeType::FrameHdrEntry(_)
+            | RangeType::ChdrField(_) => Some(Category::Section),
+            RangeType::Malformed => None,
+        }
+    }
+
+    // foreground color, neighbouring ranges alternate with the bright variant
+    fn color(self, bright: bool) -> u8 {
+        let color = match self {
+            Category::FileHeader => 33,
+            Category::ProgramHeader => 32,
+            Category::SectionHeader => 36,
+            Category::Segment => 34,
+            Category::Section => 35,
+        };
+
+        if bright {
+            color + 60
+        } else {
+            color
+        }
+    }
+}
+
+// an open range together with its position among its siblings, the root
+// has no range
+struct OpenRange<'a> {
+    range: Option<&'a Range>,
+    ordinal: usize,
+    children: usize,
+}
+
+fn byte_style(open: &[OpenRange], byte: u8) -> String {
+    let mut ranges = open.iter().filter_map(|r| r.range);
+
+    if ranges.any(|range| range.range_type == RangeType::Malformed) {
+        return String::from("\x1b[1;4;31m");
+    }
+
+    let innermost = open
+        .iter()
+        .rev()
+        .find_map(|r| Some((Category::of(&r.range?.range_type)?, r.ordinal)));
+
+    match innermost {
+        Some((category, ordinal)) => format!("\x1b[{}m", category.color(ordinal % 2 == 1)),
+        None if byte == 0 => String::from("\x1b[2m"),
+        None => String::new(),
+    }
+}
+
+// Names and values from the file could hold escape sequences that recolor the
+// output or retitle the terminal. Control characters (C0, DEL and C1) are
+// written like Rust escapes them instead.
+fn escape_control(s: &str) -> String {
+    s.chars().fold(String::new(), |mut acc, ch| {
+        if ch.is_control() {
+            acc.extend(ch.escape_debug());
+        } else {
+            acc.push(ch);
+        }
+
+        acc
+    })
+}
+
+// fields of entries (symbols, relocations, ...) are left out to keep the
+// annotations readable, the entries themselves are named
+fn annotation(elf: &ParsedElf, range_type: &RangeType) -> Option<String> {
+    match range_type {
+        RangeType::Ident => Some(String::from("e_ident")),
+        RangeType::FileHeader => Some(String::from("file header")),
+        RangeType::HeaderField(field)
+        | RangeType::PhdrField(field)
+        | RangeType::ShdrField(field)
+        | RangeType::HashField(field)
+        | RangeType::ChdrField(field) => Some(String::from(*field)),
+        RangeType::ProgramHeader(idx) => Some(format!("phdr {}", idx)),
+        RangeType::SectionHeader(idx) => Some(format!("shdr {}", idx)),
+        RangeType::Segment(idx) => Some(match elf.phdrs.get(*idx as usize) {
+            Some(phdr) => format!("segment {} ({})", idx, ptype_to_string(phdr.ptype)),
+            None => format!("segment {}", idx),
+        }),
+        RangeType::Section(idx) => Some(match elf.shdrs.get(*idx as usize) {
+            Some(shdr) => format!("section {} ({})", idx, elf.shnstrtab.get(shdr.name)),
+            None => format!("section {}", idx),
+        }),
+        RangeType::Note(idx) => Some(format!("note {}", idx)),
+        RangeType::Symbol(section, idx) => {
+            let symbol = elf
+                .symtabs
+                .get(section)
+                .and_then(|symbols| symbols.get(*idx as usize))
+                .filter(|symbol| !symbol.name.is_empty());
+
+            let version = elf.symbol_version(*section, *idx as usize);
+
+            Some(match symbol {
+                Some(symbol) => format!("sym {}{}", symbol.name, version.unwrap_or_default()),
+                None => format!("sym {}", idx),
+            })
+        }
+        RangeType::Relocation(_, idx) => Some(format!("rel {}", idx)),
+        RangeType::Dynamic(idx) => Some(match elf.dynamic.get(*idx as usize) {
+            Some(entry) => dtag_to_string(entry.tag),
+            None => format!("dyn {}", idx),
+        }),
+        RangeType::Malformed => Some(String::from("malformed")),
+        RangeType::Version(_, idx) => Some(format!("ver {}", idx)),
+        RangeType::DwarfUnit(idx) => Some(format!("cu {}", idx)),
+        RangeType::Die(unit, idx) => elf
+            .dwarf_units
+            .get(*unit as usize)
+            .and_then(|unit| unit.dies.get(*idx as usize))
+            .map(|die| dwarf_tag_to_string(die.tag)),
+        RangeType::LineProgram(idx) => Some(format!("lines {}", idx)),
+        RangeType::LineOp(program, idx) => elf
+            .line_programs
+            .get(*program as usize)
+            .and_then(|program| program.ops.get(*idx as usize))
+            .map(|op| op.name.clone()),
+        RangeType::FrameRecord(idx) => Some(match elf.frame_records.get(*idx as usize) {
+            Some(FrameRecord {
+                entry: FrameEntry::Cie(_),
+                ..
+            }) => format!("cie {}", idx),
+            _ => format!("fde {}", idx),
+        }),
+        RangeType::CfaOp(record, idx) => elf
+            .frame_records
+            .get(*record as usize)
+            .and_then(|record| record.instructions.get(*idx as usize))
+            .map(|op| op.name.clone()),
+        RangeType::FrameHdrEntry(idx) => Some(format!("hdr entry {}", idx)),
+        RangeType::FrameField(_)
+        | RangeType::NoteField(_)
+        | RangeType::VersionField(_)
+        | RangeType::DwarfField(_)
+        | RangeType::DieAttribute(_, _, _)
+        | RangeType::SymbolField(_)
+        | RangeType::RelocationField(_)
+        | RangeType::DynamicField(_) => None,
+    }
+}
+
+fn generate_header(o: &mut dyn Write, elf: &ParsedElf) -> fmt::Result {
+    for (_, desc, value) in &elf.information {
+        writeln!(o, "{}: {}", desc, escape_control(&strip_html_tags(value)))?;
+    }
+
+    for diagnostic in &elf.diagnostics {
+        let error = escape_control(&diagnostic.error.to_string());
+
+        writeln!(o, "\x1b[1;31mwarning:{} {}", RESET, error)?;
+    }
+
+    writeln!(o)?;
+    write!(o, "Legend:")?;
+
+    for (category, name) in LEGEND.iter() {
+        write!(o, "  \x1b[{}m{}{}", category.color(false), name, RESET)?;
+    }
+
+    writeln!(o, "  \x1b[1;4;31mmalformed{}", RESET)?;
+    writeln!(
+        o,
+        "Neighbouring structures and fields alternate between normal and bright colors."
+    )?;
+    writeln!(o)?;
+
+    Ok(())
+}
+
+struct Row {
+    // everything after the offset, used to fold repeated rows
+    body: String,
+    annotations: Vec<String>,
+}
+
+fn generate_row<'a>(
+    elf: &ParsedElf,
+    events: &mut Peekable<Events<'a>>,
+    open: &mut Vec<OpenRange<'a>>,
+    start: usize,
+    width: usize,
+) -> Row {
+    let end = (start + width).min(elf.contents.len());
+    let mut hex = String::new();
+    let mut ascii = String::new();
+    let mut annotations = vec![];
+    let mut current_style = String::new();
+
+    for idx in start..end {
+        while let Some(&RangeEvent::Open(range)) = events.peek() {
+            if range.start != idx {
+                break;
+            }
+
+            events.next();
+
+            let parent = open.last_mut().expect("root is never closed");
+            let ordinal = parent.children;
+
+            parent.children += 1;
+
+            open.push(OpenRange {
+                range: Some(range),
+                ordinal,
+                children: 0,
+            });
+
+            if let Some(annotation) = annotation(elf, &range.range_type) {
+                let annotation = escape_control(&annotation);
+
+                annotations.push(format!("{}{}{}", byte_style(open, 1), annotation, RESET));
+            }
+        }
+
+        let byte = elf.contents[idx];
+        let style = byte_style(open, byte);
+
+        // separators only get a style (underline) between bytes of a range
+        if idx != start {
+            hex.push(' ');
+        }
+
+        if style != current_style {
+            if !current_style.is_empty() {
+                hex.push_str(RESET);
+                ascii.push_str(RESET);
+            }
+
+            hex.push_str(&style);
+            ascii.push_str(&style);
+            current_style = style.clone();
+        }
+
+        write!(hex, "{:02x}", byte).unwrap();
+
+        ascii.push(if byte.is_ascii_graphic() || byte == b' ' {
+            byte as char
+        } else {
+            '.'
+        });
+
+        // ranges of malformed files may cross instead of nesting
+        while let Some(&RangeEvent::Close(range)) = events.peek() {
+            if range.end != idx + 1 {
+                break;
+            }
+
+            events.next();
+
+            if let Some(pos) = open
+                .iter()
+                .rposition(|r| r.range.is_some_and(|r| std::ptr::eq(r, range)))
+            {
+                open.remove(pos);
+            }
+        }
+    }
+
+    if !current_style.is_empty() {
+        hex.push_str(RESET);
+        ascii.push_str(RESET);
+    }
+
+    let padding = "   ".repeat(width - (end - start));
+
+    Row {
+        body: format!("{}{}  |{}|", hex, padding, ascii),
+        annotations,
+    }
+}
+
+pub fn generate_term(o: &mut dyn Write, elf: &ParsedElf, width: usize) -> fmt::Result {
+    // top-level ranges need a parent to alternate colors too
+    let mut events = elf.ranges.events().peekable();
+    let mut open = vec![OpenRange {
+        range: None,
+        ordinal: 0,
+        children: 0,
+    }];
+    let mut previous_body = None;
+    let mut folded = false;
+
+    generate_header(o, elf)?;
+
+    for start in (0..elf.contents.len()).step_by(width) {
+        let row = generate_row(elf, &mut events, &mut open, start, width);
+
+        // like hexdump -C, identical rows without anything starting in them
+        // are folded into a single '*'
+        if row.annotations.is_empty() && previous_body.as_ref() == Some(&row.body) {
+            if !folded {
+                writeln!(o, "*")?;
+                folded = true;
+            }
+
+            continue;
+        }
+
+        write!(o, "{:08x}  {}", start, row.body)?;
+
+        if !row.annotations.is_empty() {
+            write!(o, "  {}", row.annotations.join(", "))?;
+        }
+
+        writeln!(o)?;
+
+        previous_body = Some(row.body);
+        folded = false;
+    }
+
+    writeln!(o, "{:08x}", elf.contents.len())?;
+
+    Ok(())
+}
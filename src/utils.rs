@@ -24,3 +24,124 @@ pub fn html_escape(ch: char) -> Option<&'static str> {
         _ => None,
     }
 }
+
+pub fn html_escape_str(s: &str) -> String {
+    s.chars().fold(String::new(), |mut acc, ch| {
+        match html_escape(ch) {
+            Some(escaped) => acc.push_str(escaped),
+            None => acc.push(ch),
+        }
+
+        acc
+    })
+}
+
+// for output formats other than HTML, some information values contain links
+// and escaped characters
+pub fn strip_html_tags(s: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_tag = false;
+
+    for ch in s.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    unescape_html(&stripped)
+}
+
+// the reverse of html_escape_str
+fn unescape_html(s: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('&') {
+        unescaped.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        let escaped = ['&', '<', '>', '"']
+            .iter()
+            .map(|&ch| (ch, html_escape(ch).unwrap_or_default()))
+            .find(|(_, entity)| rest.starts_with(entity));
+
+        match escaped {
+            Some((ch, entity)) => {
+                unescaped.push(ch);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// written out piece by piece so that large inputs aren't copied in memory
+pub fn write_base64(o: &mut dyn std::fmt::Write, bytes: &[u8]) -> std::fmt::Result {
+    let mut buf = String::with_capacity(4096);
+
+    for chunk in bytes.chunks(3) {
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let n = (chunk[0] as u32) << 16 | b1 << 8 | b2;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                buf.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                buf.push('=');
+            }
+        }
+
+        if buf.len() >= 4092 {
+            o.write_str(&buf)?;
+            buf.clear();
+        }
+    }
+
+    o.write_str(&buf)
+}
+
+// base64 of bytes which are produced a few at a time, e.g. while going through
+// the ranges. Pieces of a multiple of 3 bytes encode the same as the whole.
+pub struct Base64Writer<'a> {
+    o: &'a mut dyn std::fmt::Write,
+    pending: Vec<u8>,
+}
+
+impl<'a> Base64Writer<'a> {
+    pub fn new(o: &'a mut dyn std::fmt::Write) -> Base64Writer<'a> {
+        Base64Writer {
+            o,
+            pending: Vec::with_capacity(4096),
+        }
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) -> std::fmt::Result {
+        self.pending.extend_from_slice(bytes);
+
+        if self.pending.len() >= 3072 {
+            let len = self.pending.len() - self.pending.len() % 3;
+
+            write_base64(self.o, &self.pending[..len])?;
+            self.pending.drain(..len);
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> std::fmt::Result {
+        write_base64(self.o, &self.pending)
+    }
+}